@@ -0,0 +1,44 @@
+//! time per `new_interned` call with the reasons of a busy proxy, on one thread and on
+//! several at once, run with `cargo bench --bench intern`
+
+use curerr::*;
+use std::time::Instant;
+
+const REASONS: [&str; 8] = [
+    "connection reset by peer",
+    "broken pipe",
+    "connection refused",
+    "operation timed out",
+    "no route to host",
+    "host is unreachable",
+    "network is down",
+    "connection aborted",
+];
+const CALLS: usize = 1_000_000;
+
+fn interned(call: usize) -> CursedErrorHandle {
+    CursedErrorHandle::new_interned(CursedError::Connection(CursedErrorType::Reset), REASONS[call % REASONS.len()])
+}
+
+fn owned(call: usize) -> CursedErrorHandle {
+    CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Reset), REASONS[call % REASONS.len()].to_string())
+}
+
+fn main() {
+    capture_backtraces(false);
+
+    for (name, create) in [("new_interned", interned as fn(usize) -> CursedErrorHandle), ("new", owned)] {
+        for threads in [1, 8] {
+            let start = Instant::now();
+            std::thread::scope(|scope| {
+                for _ in 0..threads {
+                    scope.spawn(|| (0..CALLS).for_each(|call| drop(std::hint::black_box(create(call)))));
+                }
+            });
+            let elapsed = start.elapsed();
+
+            println!("{:<12} {} threads: {:>6.1} ns per call", name, threads, elapsed.as_nanos() as f64 / (CALLS * threads) as f64);
+        }
+    }
+    println!("{:?}", intern_stats());
+}
@@ -0,0 +1,233 @@
+//! circuit breaker tripped by the errors of a failing dependency
+
+use crate::*;
+use crate::handle::format_duration;
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// stops calls to a dependency after more than `threshold` matching errors within `window`
+///
+/// Errors are counted with [`CircuitBreaker::record`], the ones of other kinds are
+/// ignored without locking. Once tripped, [`CircuitBreaker::guard`] fails with a
+/// `Connection(Refused)` error carrying the rest of the cooldown as its
+/// [retry hint](CursedErrorHandle::retry_after). After the cooldown the breaker is
+/// half-open, the next guard lets a single probe call through, then
+/// [`CircuitBreaker::record_success`] closes it and another matching error trips it again.
+/// A probe that reports neither frees its slot after another cooldown.
+///
+/// The breaker is shared with an [`std::sync::Arc`] or kept in a static.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::LazyLock;
+/// use std::time::{Duration, Instant};
+///
+/// static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+/// static ELAPSED_MS: AtomicU64 = AtomicU64::new(0);
+///
+/// let advance = |ms: u64| ELAPSED_MS.fetch_add(ms, Ordering::Relaxed);
+/// let refused = || CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "redis is down".to_string());
+///
+/// let breaker = CircuitBreaker::new(CursedCategory::Connection, 2, Duration::from_secs(10), Duration::from_secs(5))
+///     .with_clock(|| *START + Duration::from_millis(ELAPSED_MS.load(Ordering::Relaxed)));
+///
+/// // other kinds don't count, old errors leave the window
+/// breaker.record(&CursedErrorHandle::new(CursedError::Data(CursedErrorType::Parse), "bad reply".to_string()));
+/// breaker.record(&refused());
+/// advance(11_000);
+/// breaker.record(&refused());
+/// breaker.record(&refused());
+/// assert!(!breaker.is_open());
+/// assert!(breaker.guard().is_ok());
+///
+/// // the third error within 10s trips it
+/// breaker.record(&refused());
+/// assert!(breaker.is_open());
+///
+/// let error = breaker.guard().unwrap_err();
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Refused));
+/// assert_eq!(error.get_reason(), "circuit open, retry in 5s");
+/// assert_eq!(error.retry_after(), Some(Duration::from_secs(5)));
+///
+/// advance(2_000);
+/// assert_eq!(breaker.guard().unwrap_err().retry_after(), Some(Duration::from_secs(3)));
+///
+/// // half-open after the cooldown, one probe at a time
+/// advance(3_000);
+/// assert!(!breaker.is_open());
+/// assert!(breaker.guard().is_ok());
+/// assert!(breaker.is_open());
+/// assert_eq!(breaker.guard().unwrap_err().get_reason(), "circuit half-open, a probe is running");
+///
+/// // a failed probe trips it again
+/// breaker.record(&refused());
+/// assert_eq!(breaker.guard().unwrap_err().retry_after(), Some(Duration::from_secs(5)));
+///
+/// // a successful probe closes it
+/// advance(5_000);
+/// assert!(breaker.guard().is_ok());
+/// breaker.record_success();
+/// assert!(!breaker.is_open());
+/// breaker.record(&refused());
+/// breaker.record(&refused());
+/// assert!(breaker.guard().is_ok());
+///
+/// breaker.record(&refused());
+/// assert!(breaker.is_open());
+/// breaker.reset();
+/// assert!(breaker.guard().is_ok());
+/// ```
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    filter: Filter,
+    threshold: u32,
+    window: Duration,
+    cooldown: Duration,
+    clock: fn() -> Instant,
+    state: Mutex<State>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Filter {
+    Category(CursedCategory),
+    Predicate(fn(&CursedError) -> bool),
+}
+
+#[derive(Debug)]
+enum State {
+    /// times of the recent matching errors, at most `threshold + 1`
+    Closed(VecDeque<Instant>),
+    Open { since: Instant },
+    HalfOpen { probe: Option<Instant> },
+}
+
+impl CircuitBreaker {
+    /// counts errors of `category`
+    pub fn new(category: CursedCategory, threshold: u32, window: Duration, cooldown: Duration) -> Self {
+        Self::with_filter(Filter::Category(category), threshold, window, cooldown)
+    }
+    /// counts errors whose kind is accepted by `predicate`
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::time::Duration;
+    ///
+    /// let breaker = CircuitBreaker::with_predicate(
+    ///     CursedError::is_retryable,
+    ///     0,
+    ///     Duration::from_secs(1),
+    ///     Duration::from_secs(60)
+    /// );
+    ///
+    /// breaker.record(&CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), String::new()));
+    /// assert!(!breaker.is_open());
+    ///
+    /// breaker.record(&CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), String::new()));
+    /// assert!(breaker.is_open());
+    /// ```
+    pub fn with_predicate(predicate: fn(&CursedError) -> bool, threshold: u32, window: Duration, cooldown: Duration) -> Self {
+        Self::with_filter(Filter::Predicate(predicate), threshold, window, cooldown)
+    }
+    fn with_filter(filter: Filter, threshold: u32, window: Duration, cooldown: Duration) -> Self {
+        Self {
+            filter,
+            threshold,
+            window,
+            cooldown,
+            clock: Instant::now,
+            state: Mutex::new(State::Closed(VecDeque::new())),
+        }
+    }
+    /// reads the time from `clock` instead of [`Instant::now`], e.g. a fake clock in tests
+    pub fn with_clock(mut self, clock: fn() -> Instant) -> Self {
+        self.clock = clock;
+        self
+    }
+    /// counts `error` if it matches, trips the breaker when there are more than
+    /// `threshold` within the window or when it is half-open
+    pub fn record(&self, error: &CursedErrorHandle) {
+        let matches = match self.filter {
+            Filter::Category(category) => error.get_error().category() == category,
+            Filter::Predicate(predicate) => predicate(error.get_error()),
+        };
+        if !matches {
+            return
+        }
+
+        let now = (self.clock)();
+        let mut state = self.lock();
+        match &mut *state {
+            State::Closed(errors) => {
+                while errors.front().is_some_and(|time| now.saturating_duration_since(*time) >= self.window) {
+                    errors.pop_front();
+                }
+                errors.push_back(now);
+                if errors.len() > self.threshold as usize {
+                    *state = State::Open { since: now };
+                }
+            },
+            State::Open { .. } => {},
+            State::HalfOpen { .. } => *state = State::Open { since: now },
+        }
+    }
+    /// closes a half-open breaker after a successful probe, ignored in the other states
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        if let State::HalfOpen { .. } = *state {
+            *state = State::Closed(VecDeque::new());
+        }
+    }
+    /// closes the breaker and forgets the counted errors
+    pub fn reset(&self) {
+        *self.lock() = State::Closed(VecDeque::new());
+    }
+    /// whether [`CircuitBreaker::guard`] would fail now
+    pub fn is_open(&self) -> bool {
+        let now = (self.clock)();
+        match *self.lock() {
+            State::Closed(_) => false,
+            State::Open { since } => now.saturating_duration_since(since) < self.cooldown,
+            State::HalfOpen { probe } => probe.is_some_and(|probe| now.saturating_duration_since(probe) < self.cooldown),
+        }
+    }
+    /// fails while the breaker is open, call it before each call to the dependency
+    #[track_caller]
+    pub fn guard(&self) -> CursedResult<()> {
+        let now = (self.clock)();
+        let mut state = self.lock();
+
+        if let State::Open { since } = *state {
+            let elapsed = now.saturating_duration_since(since);
+            if elapsed < self.cooldown {
+                let remaining = self.cooldown - elapsed;
+                return Err(CursedErrorHandle::new(
+                    CursedError::Connection(CursedErrorType::Refused),
+                    format!("circuit open, retry in {}", format_duration(remaining))
+                ).with_retry_after(remaining))
+            }
+            *state = State::HalfOpen { probe: None };
+        }
+
+        match &mut *state {
+            State::HalfOpen { probe: Some(probe) } if now.saturating_duration_since(*probe) < self.cooldown => {
+                Err(CursedErrorHandle::new(
+                    CursedError::Connection(CursedErrorType::Refused),
+                    "circuit half-open, a probe is running".to_string()
+                ))
+            },
+            State::HalfOpen { probe } => {
+                *probe = Some(now);
+                Ok(())
+            },
+            _ => Ok(()),
+        }
+    }
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,41 @@
+//! days since the Unix epoch to dates of the proleptic Gregorian calendar and back, for
+//! the timestamps of [`ErrorHistory`](crate::ErrorHistory) and dates of `Retry-After`
+
+// Years are counted from March, so the leap day is the last day of a year, in eras of
+// 400 years starting at 0000-03-01.
+
+/// the days of an era of 400 years
+const ERA_DAYS: u64 = 146_097;
+/// the days from 0000-03-01 to 1970-01-01
+const EPOCH_DAYS: u64 = 719_468;
+
+/// the days since the epoch of a date from 1970 on, `None` if they don't fit
+pub(crate) fn days_from_civil(year: u64, month: u64, day: u64) -> Option<u64> {
+    let (year, month) = if month <= 2 { (year.checked_sub(1)?, month + 9) } else { (year, month - 3) };
+    let (era, year_of_era) = (year / 400, year % 400);
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.checked_mul(ERA_DAYS)?.checked_add(day_of_era)?.checked_sub(EPOCH_DAYS)
+}
+
+/// the number of days in `month` of `year`
+pub(crate) fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// the year, month and day `days` after the epoch
+pub(crate) fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + EPOCH_DAYS;
+    let (era, day_of_era) = (days / ERA_DAYS, days % ERA_DAYS);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / (ERA_DAYS - 1)) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    (era * 400 + year_of_era + u64::from(month <= 2), month, day)
+}
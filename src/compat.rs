@@ -0,0 +1,83 @@
+//! the classification of std errors as data, for tests that pin the mapping
+//!
+//! Crates depending on a curerr version can run the table in their own tests to
+//! notice when an update classifies an error differently.
+//!
+//! # Examples
+//! ```
+//! use curerr::*;
+//!
+//! compat::assert_classification("std::num::ParseIntError(PosOverflow)", || {
+//!     wrap_any("70000".parse::<u16>().unwrap_err())
+//! });
+//!
+//! let skipped = compat::assert_all(|name| match name {
+//!     "std::io::Error(NotFound)" => Some(wrap_any(std::io::Error::from(std::io::ErrorKind::NotFound))),
+//!     "std::net::AddrParseError" => Some(wrap_any("localhost".parse::<std::net::IpAddr>().unwrap_err())),
+//!     _ => None,
+//! });
+//! assert_eq!(skipped.len(), compat::classifications().len() - 2);
+//! ```
+
+use crate::*;
+
+/// an error named by its type and, when it matters, the variant or kind of the sample,
+/// with the category and type it gets
+pub type Classification = (std::borrow::Cow<'static, str>, CursedCategory, CursedErrorType);
+
+/// errors the crate converts or [classifies](CursedError::classify) with the kind they get
+///
+/// The io rows come from the [`std::io::ErrorKind`] mapping and the rows of the other
+/// errors from the tables next to their conversions, the integrations are only listed
+/// when their feature is enabled. Errors with a [`From`] impl are converted by it, the
+/// others by [`wrap_any`], for std errors both give the same kind.
+pub fn classifications() -> &'static [Classification] {
+    static TABLE: std::sync::OnceLock<Vec<Classification>> = std::sync::OnceLock::new();
+    TABLE.get_or_init(crate::convert::classifications)
+}
+
+/// expected kind of the error named `name` in [`classifications`]
+pub fn expected(name: &str) -> Option<CursedError> {
+    classifications().iter()
+        .find(|(entry, _, _)| entry == name)
+        .map(|(_, category, error_type)| category.with_type(*error_type))
+}
+
+/// panics unless the error made by `make` has the kind listed for `name`
+#[track_caller]
+pub fn assert_classification(name: &str, make: impl FnOnce() -> CursedErrorHandle) {
+    let Some(expected) = expected(name) else {
+        panic!("{} is not in the classification table", name)
+    };
+
+    let error = make();
+    assert!(
+        error.get_error() == &expected,
+        "{} is classified as {}, the table expects {}",
+        name,
+        error.get_error(),
+        expected
+    );
+}
+
+/// checks every entry of [`classifications`] with the error `make` returns for its name,
+/// panics with all mismatches at once and returns the names `make` returned `None` for
+#[track_caller]
+pub fn assert_all(mut make: impl FnMut(&'static str) -> Option<CursedErrorHandle>) -> Vec<&'static str> {
+    let mut skipped = Vec::new();
+    let mut mismatches = Vec::new();
+
+    for (name, category, error_type) in classifications() {
+        let expected = category.with_type(*error_type);
+        match make(name) {
+            Some(error) if error.get_error() != &expected => {
+                mismatches.push(format!("{} is classified as {}, the table expects {}", name, error.get_error(), expected));
+            },
+            Some(_) => {},
+            None => skipped.push(&**name),
+        }
+    }
+
+    assert!(mismatches.is_empty(), "classifications changed:\n{}", mismatches.join("\n"));
+    skipped
+}
@@ -0,0 +1,48 @@
+//! conversions of `async-channel` errors
+
+use crate::*;
+
+/// sending into a closed channel becomes `Connection(Aborted)`, the message that
+/// wasn't sent is dropped
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let (sender, receiver) = async_channel::bounded::<String>(1);
+/// drop(receiver);
+/// let error = futures::executor::block_on(sender.send("hello".to_string())).unwrap_err();
+/// let error = CursedErrorHandle::from(error);
+///
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Aborted));
+/// assert_eq!(error.get_reason(), "sending into a closed channel, the message was dropped");
+/// ```
+impl<T> From<async_channel::SendError<T>> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: async_channel::SendError<T>) -> Self {
+        Self::new(
+            CursedError::Connection(CursedErrorType::Aborted),
+            format!("{}, the message was dropped", error)
+        )
+    }
+}
+
+/// receiving from a closed and empty channel becomes `Connection(Aborted)`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let (sender, receiver) = async_channel::unbounded::<u32>();
+/// drop(sender);
+/// let error = CursedErrorHandle::from(futures::executor::block_on(receiver.recv()).unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Aborted));
+/// assert_eq!(error.get_reason(), "receiving from an empty and closed channel");
+/// ```
+impl From<async_channel::RecvError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: async_channel::RecvError) -> Self {
+        Self::new(CursedError::Connection(CursedErrorType::Aborted), error.to_string())
+    }
+}
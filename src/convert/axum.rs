@@ -0,0 +1,80 @@
+//! responses for `axum` handlers
+
+use crate::*;
+
+pub(crate) static EXPOSE_INTERNAL_REASONS: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// adds the internal reason to the `axum` responses of debug builds, ignored in release builds
+pub fn expose_internal_reasons(expose: bool) {
+    EXPOSE_INTERNAL_REASONS.store(expose, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[derive(serde::Serialize)]
+struct Body<'a> {
+    code: std::borrow::Cow<'static, str>,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'a str>,
+}
+
+/// responds with the [HTTP status](CursedError::http_status) of the error and a
+/// `{"code": "...", "message": "..."}` body
+///
+/// The message is the [public message](CursedErrorHandle::with_public_message). Without it
+/// client errors show their reason, server errors only the generic text of the status.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use axum::response::IntoResponse;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// async fn body(response: axum::response::Response) -> serde_json::Value {
+///     let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+///     serde_json::from_slice(&bytes).unwrap()
+/// }
+///
+/// let response = CursedErrorHandle::new(
+///     CursedError::File(CursedErrorType::NotFound),
+///     "user 42 not found".to_string()
+/// ).into_response();
+///
+/// assert_eq!(response.status(), 404);
+/// assert_eq!(body(response).await, serde_json::json!({"code": "FILE_NOT_FOUND", "message": "user 42 not found"}));
+///
+/// let error = CursedErrorHandle::new(
+///     CursedError::Connection(CursedErrorType::Refused),
+///     "postgres at 10.0.0.3:5432 refused".to_string()
+/// );
+/// let response = error.clone().into_response();
+///
+/// assert_eq!(response.status(), 502);
+/// assert_eq!(body(response).await, serde_json::json!({"code": "CONNECTION_REFUSED", "message": "Bad Gateway"}));
+///
+/// let response = error.with_public_message("storage is unavailable").into_response();
+/// assert_eq!(body(response).await["message"], "storage is unavailable");
+///
+/// expose_internal_reasons(true);
+/// let response = CursedErrorHandle::new(CursedError::Unknown(None), "oops".to_string()).into_response();
+/// # #[cfg(debug_assertions)]
+/// assert_eq!(body(response).await["reason"], "oops");
+/// # });
+/// ```
+impl axum::response::IntoResponse for CursedErrorHandle {
+    fn into_response(self) -> axum::response::Response {
+        let status = axum::http::StatusCode::from_u16(self.get_error().http_status())
+            .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        let message = self.safe_message(status.canonical_reason().unwrap_or("internal error"));
+        let expose = cfg!(debug_assertions)
+            && EXPOSE_INTERNAL_REASONS.load(std::sync::atomic::Ordering::Relaxed);
+
+        let body = Body {
+            code: self.code(),
+            message,
+            reason: expose.then(|| self.get_reason()),
+        };
+
+        (status, axum::Json(body)).into_response()
+    }
+}
@@ -0,0 +1,63 @@
+//! conversions of `base64` decode errors
+
+use crate::*;
+use crate::convert::Classification;
+
+/// decode errors by variant, listed by [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("base64::DecodeError(InvalidByte)", CursedCategory::Data, CursedErrorType::Parse),
+    ("base64::DecodeError(InvalidLength)", CursedCategory::Data, CursedErrorType::NotEnough),
+    ("base64::DecodeError(InvalidPadding)", CursedCategory::Data, CursedErrorType::Parse),
+];
+
+/// bad symbols and padding become `Data(Parse)`, a truncated input `Data(NotEnough)`
+///
+/// The input may be secret, so the reason only contains the position of the failure
+/// and the error is not kept as the source, its message includes the offending byte.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use base64::Engine;
+///
+/// let decode = |input: &str| CursedErrorHandle::from(
+///     base64::engine::general_purpose::STANDARD.decode(input).unwrap_err()
+/// );
+///
+/// let error = decode("c2Vj!mV0");
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert_eq!(error.get_reason(), "invalid base64 symbol at offset 4");
+///
+/// let error = decode("c2VjcmU");
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert_eq!(error.get_reason(), "invalid base64 padding");
+///
+/// let error = decode("c2VjcmV0c");
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::NotEnough));
+/// assert_eq!(error.get_reason(), "invalid base64 length of 9 symbols");
+/// ```
+impl From<base64::DecodeError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: base64::DecodeError) -> Self {
+        let (kind, reason) = match error {
+            base64::DecodeError::InvalidByte(offset, _) => (
+                CursedErrorType::Parse,
+                format!("invalid base64 symbol at offset {}", offset)
+            ),
+            base64::DecodeError::InvalidLastSymbol(offset, _) => (
+                CursedErrorType::Parse,
+                format!("invalid last base64 symbol at offset {}", offset)
+            ),
+            base64::DecodeError::InvalidLength(length) => (
+                CursedErrorType::NotEnough,
+                format!("invalid base64 length of {} symbols", length)
+            ),
+            base64::DecodeError::InvalidPadding => (
+                CursedErrorType::Parse,
+                "invalid base64 padding".to_string()
+            ),
+        };
+
+        Self::new(CursedError::Data(kind), reason)
+    }
+}
@@ -0,0 +1,37 @@
+//! conversions of `chrono` errors
+
+use crate::*;
+use crate::convert::Classification;
+
+/// parse errors by [`chrono::format::ParseErrorKind`], listed by [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("chrono::ParseError(OutOfRange)", CursedCategory::Data, CursedErrorType::Invalid),
+    ("chrono::ParseError(Invalid)", CursedCategory::Data, CursedErrorType::Parse),
+];
+
+/// out of range or impossible values become `Data(Invalid)`, other failures `Data(Parse)`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use chrono::NaiveDate;
+///
+/// let error = CursedErrorHandle::from(NaiveDate::parse_from_str("2024-xx-01", "%Y-%m-%d").unwrap_err());
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert_eq!(error.get_reason(), "input contains invalid characters");
+///
+/// let error = CursedErrorHandle::from(NaiveDate::parse_from_str("2024-02-30", "%Y-%m-%d").unwrap_err());
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+/// ```
+impl From<chrono::ParseError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: chrono::ParseError) -> Self {
+        let kind = match error.kind() {
+            chrono::format::ParseErrorKind::OutOfRange
+            | chrono::format::ParseErrorKind::Impossible => CursedError::Data(CursedErrorType::Invalid),
+            _ => CursedError::Data(CursedErrorType::Parse),
+        };
+
+        Self::new(kind, error.to_string())
+    }
+}
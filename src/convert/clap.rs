@@ -0,0 +1,66 @@
+//! conversions of `clap` errors
+
+use crate::*;
+
+use clap::error::ErrorKind as ClapKind;
+
+/// the rendered clap message becomes the reason and clap's exit code is kept
+///
+/// `--help` and `--version` are not failures, they become [`CursedError::NoError`]
+/// with the help or version text as the reason and exit code 0, so callers should
+/// check for them and print the reason to stdout. Invalid values become `Input(Invalid)`,
+/// missing arguments `Input(NotEnough)` and unknown arguments `Input(NotFound)`.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use clap::Parser;
+///
+/// #[derive(Parser, Debug)]
+/// #[command(name = "serve", version = "1.0.0")]
+/// struct Args {
+///     #[arg(long)]
+///     port: u16,
+/// }
+///
+/// let parse = |args: &[&str]| CursedErrorHandle::from(Args::try_parse_from(args).unwrap_err());
+///
+/// let error = parse(&["serve", "--port", "http"]);
+/// assert_eq!(error.get_error(), &CursedError::Input(CursedErrorType::Invalid));
+/// assert!(error.get_reason().starts_with("error: invalid value 'http' for '--port <PORT>'"));
+/// assert_eq!(error.exit_code(), 2);
+///
+/// let error = parse(&["serve"]);
+/// assert_eq!(error.get_error(), &CursedError::Input(CursedErrorType::NotEnough));
+///
+/// let error = parse(&["serve", "--port", "80", "--verbose"]);
+/// assert_eq!(error.get_error(), &CursedError::Input(CursedErrorType::NotFound));
+///
+/// let error = parse(&["serve", "--help"]);
+/// assert_eq!(error.get_error(), &CursedError::NoError);
+/// assert!(error.get_reason().contains("Usage: serve --port <PORT>"));
+/// assert_eq!(error.exit_code(), 0);
+///
+/// let error = parse(&["serve", "--version"]);
+/// assert_eq!(error.get_error(), &CursedError::NoError);
+/// assert_eq!(error.get_reason(), "serve 1.0.0");
+/// ```
+impl From<clap::Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: clap::Error) -> Self {
+        let kind = match error.kind() {
+            ClapKind::DisplayHelp | ClapKind::DisplayVersion => CursedError::NoError,
+            ClapKind::MissingRequiredArgument
+            | ClapKind::MissingSubcommand
+            | ClapKind::DisplayHelpOnMissingArgumentOrSubcommand
+            | ClapKind::TooFewValues => CursedError::Input(CursedErrorType::NotEnough),
+            ClapKind::UnknownArgument | ClapKind::InvalidSubcommand => CursedError::Input(CursedErrorType::NotFound),
+            ClapKind::Io | ClapKind::Format => CursedError::Unknown(None),
+            _ => CursedError::Input(CursedErrorType::Invalid),
+        };
+        let exit_code = u8::try_from(error.exit_code()).unwrap_or(1);
+        let reason = error.render().to_string().trim_end().to_string();
+
+        Self::new(kind, reason).with_exit_code(exit_code)
+    }
+}
@@ -0,0 +1,74 @@
+//! conversions of `futures` channel errors
+
+use crate::*;
+
+use futures_channel::{mpsc, oneshot};
+
+/// a sender dropped before sending becomes `Connection(Aborted)`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let (sender, receiver) = futures::channel::oneshot::channel::<u32>();
+/// drop(sender);
+/// let error = CursedErrorHandle::from(futures::executor::block_on(receiver).unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Aborted));
+/// assert_eq!(error.get_reason(), "oneshot canceled");
+/// ```
+impl From<oneshot::Canceled> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: oneshot::Canceled) -> Self {
+        Self::new(CursedError::Connection(CursedErrorType::Aborted), error.to_string())
+    }
+}
+
+/// a dropped receiver becomes `Connection(Aborted)`, a full channel `Buffer(NotEnough)`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use futures::SinkExt;
+///
+/// let (mut sender, receiver) = futures::channel::mpsc::channel::<u32>(0);
+/// drop(receiver);
+/// let error = CursedErrorHandle::from(futures::executor::block_on(sender.send(1)).unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Aborted));
+/// assert_eq!(error.get_reason(), "send failed because receiver is gone");
+/// ```
+impl From<mpsc::SendError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: mpsc::SendError) -> Self {
+        Self::new(send_error_kind(&error), error.to_string())
+    }
+}
+
+/// classified like [`mpsc::SendError`], the message that wasn't sent is dropped
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let (mut sender, _receiver) = futures::channel::mpsc::channel::<u32>(0);
+/// sender.try_send(1).unwrap();
+/// let error = CursedErrorHandle::from(sender.try_send(2).unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Buffer(CursedErrorType::NotEnough));
+/// assert_eq!(error.get_reason(), "send failed because channel is full, the message was dropped");
+/// ```
+impl<T> From<mpsc::TrySendError<T>> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: mpsc::TrySendError<T>) -> Self {
+        let error = error.into_send_error();
+        Self::new(send_error_kind(&error), format!("{}, the message was dropped", error))
+    }
+}
+
+fn send_error_kind(error: &mpsc::SendError) -> CursedError {
+    match error.is_full() {
+        true => CursedError::Buffer(CursedErrorType::NotEnough),
+        false => CursedError::Connection(CursedErrorType::Aborted),
+    }
+}
@@ -0,0 +1,56 @@
+//! conversions of `hex` decode errors
+
+use crate::*;
+use crate::convert::Classification;
+
+/// decode errors by variant, the position of a bad character doesn't matter, listed by
+/// [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("hex::FromHexError(InvalidHexCharacter)", CursedCategory::Data, CursedErrorType::Parse),
+    ("hex::FromHexError(OddLength)", CursedCategory::Data, CursedErrorType::NotEnough),
+    ("hex::FromHexError(InvalidStringLength)", CursedCategory::Buffer, CursedErrorType::NotEnough),
+];
+
+/// bad characters become `Data(Parse)`, an odd number of digits `Data(NotEnough)` and
+/// a length not matching the output buffer `Buffer(NotEnough)`
+///
+/// The input may be secret, so the reason only contains the position of the failure
+/// and the error is not kept as the source, its message includes the offending character.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedErrorHandle::from(hex::decode("deadbeeg").unwrap_err());
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert_eq!(error.get_reason(), "invalid hex character at index 7");
+///
+/// let error = CursedErrorHandle::from(hex::decode("abc").unwrap_err());
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::NotEnough));
+/// assert_eq!(error.get_reason(), "odd number of hex digits");
+///
+/// let mut key = [0u8; 4];
+/// let error = CursedErrorHandle::from(hex::decode_to_slice("abcd", &mut key).unwrap_err());
+/// assert_eq!(error.get_error(), &CursedError::Buffer(CursedErrorType::NotEnough));
+/// ```
+impl From<hex::FromHexError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: hex::FromHexError) -> Self {
+        let (kind, reason) = match error {
+            hex::FromHexError::InvalidHexCharacter { index, .. } => (
+                CursedError::Data(CursedErrorType::Parse),
+                format!("invalid hex character at index {}", index)
+            ),
+            hex::FromHexError::OddLength => (
+                CursedError::Data(CursedErrorType::NotEnough),
+                "odd number of hex digits".to_string()
+            ),
+            hex::FromHexError::InvalidStringLength => (
+                CursedError::Buffer(CursedErrorType::NotEnough),
+                "hex string length doesn't match the output size".to_string()
+            ),
+        };
+
+        Self::new(kind, reason)
+    }
+}
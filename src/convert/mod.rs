@@ -0,0 +1,683 @@
+//! conversions from std and third party errors, each integration is behind a feature of the same name
+
+use crate::*;
+use crate::ext::downcast_handle;
+use crate::handle::Inner;
+use crate::policy;
+use crate::typed::typed_handle;
+use std::io::ErrorKind;
+
+#[cfg(feature = "async-channel")]
+mod async_channel;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "axum")]
+pub use axum::expose_internal_reasons;
+#[cfg(feature = "base64")]
+mod base64;
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "clap")]
+mod clap;
+#[cfg(feature = "futures")]
+mod futures;
+#[cfg(feature = "hex")]
+mod hex;
+#[cfg(feature = "native-tls")]
+mod native_tls;
+#[cfg(feature = "proptest")]
+mod proptest;
+#[cfg(feature = "regex")]
+mod regex;
+#[cfg(feature = "rustls")]
+mod rustls;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "std-conversions")]
+mod stdlib;
+#[cfg(feature = "time")]
+mod time;
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "tonic")]
+mod tonic;
+#[cfg(feature = "url")]
+mod url;
+#[cfg(feature = "walkdir")]
+mod walkdir;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub(crate) const ERROR_KIND_MAPPINGS: [(ErrorKind, CursedError); 15] = [
+    (ErrorKind::NotFound, CursedError::Io(CursedErrorType::NotFound)),
+    (ErrorKind::PermissionDenied, CursedError::Io(CursedErrorType::AccessDenied)),
+    (ErrorKind::ConnectionRefused, CursedError::Connection(CursedErrorType::Refused)),
+    (ErrorKind::ConnectionReset, CursedError::Connection(CursedErrorType::Reset)),
+    (ErrorKind::ConnectionAborted, CursedError::Connection(CursedErrorType::Aborted)),
+    (ErrorKind::NotConnected, CursedError::Connection(CursedErrorType::NotImplemented)),
+    (ErrorKind::AddrInUse, CursedError::Address(CursedErrorType::AlreadyExists)),
+    (ErrorKind::AddrNotAvailable, CursedError::Address(CursedErrorType::NotSupported)),
+    (ErrorKind::AlreadyExists, CursedError::Io(CursedErrorType::AlreadyExists)),
+    (ErrorKind::InvalidInput, CursedError::Input(CursedErrorType::Invalid)),
+    (ErrorKind::InvalidData, CursedError::Data(CursedErrorType::Invalid)),
+    (ErrorKind::TimedOut, CursedError::Call(CursedErrorType::Timedout)),
+    (ErrorKind::Interrupted, CursedError::Io(CursedErrorType::Interrupted)),
+    (ErrorKind::Unsupported, CursedError::Io(CursedErrorType::NotSupported)),
+    (ErrorKind::OutOfMemory, CursedError::Memory(CursedErrorType::NotEnough)),
+];
+
+pub(crate) const REVERSE_ERROR_KIND_MAPPINGS: [(CursedError, ErrorKind); 6] = [
+    (CursedError::Connection(CursedErrorType::NotImplemented), ErrorKind::NotConnected),
+    (CursedError::Address(CursedErrorType::AlreadyExists), ErrorKind::AddrInUse),
+    (CursedError::Address(CursedErrorType::NotSupported), ErrorKind::AddrNotAvailable),
+    (CursedError::Memory(CursedErrorType::NotEnough), ErrorKind::OutOfMemory),
+    (CursedError::Data(CursedErrorType::Invalid), ErrorKind::InvalidData),
+    (CursedError::Data(CursedErrorType::Parse), ErrorKind::InvalidData),
+];
+
+/// an error named by its type and, when it matters, its variant or kind, with the
+/// category and type the conversions give it, the rows of [`crate::compat::classifications`]
+pub(crate) type Classification = (&'static str, CursedCategory, CursedErrorType);
+
+/// std errors other than [`std::io::Error`], converted by the `std-conversions` impls or
+/// classified by [`classify_type`]
+const STD_CLASSIFICATIONS: &[Classification] = &[
+    ("std::num::ParseIntError(InvalidDigit)", CursedCategory::Data, CursedErrorType::Parse),
+    ("std::num::ParseIntError(PosOverflow)", CursedCategory::Data, CursedErrorType::Overflow),
+    ("std::num::ParseIntError(NegOverflow)", CursedCategory::Data, CursedErrorType::Overflow),
+    ("std::num::ParseIntError(Empty)", CursedCategory::Input, CursedErrorType::NotEnough),
+    ("std::num::ParseIntError(Zero)", CursedCategory::Data, CursedErrorType::Parse),
+    ("std::num::ParseFloatError", CursedCategory::Data, CursedErrorType::Parse),
+    ("std::num::TryFromIntError", CursedCategory::Data, CursedErrorType::Overflow),
+    ("std::str::ParseBoolError", CursedCategory::Data, CursedErrorType::Parse),
+    ("std::char::ParseCharError", CursedCategory::Data, CursedErrorType::Parse),
+    ("std::char::TryFromCharError", CursedCategory::Data, CursedErrorType::Parse),
+    ("std::str::Utf8Error", CursedCategory::Data, CursedErrorType::Invalid),
+    ("std::string::FromUtf8Error", CursedCategory::Data, CursedErrorType::Invalid),
+    ("std::string::FromUtf16Error", CursedCategory::Data, CursedErrorType::Invalid),
+    ("std::net::AddrParseError", CursedCategory::Address, CursedErrorType::Parse),
+    ("std::alloc::LayoutError", CursedCategory::Memory, CursedErrorType::Invalid),
+    ("std::collections::TryReserveError", CursedCategory::Memory, CursedErrorType::NotEnough),
+    ("std::time::SystemTimeError", CursedCategory::Other, CursedErrorType::Invalid),
+    ("std::env::VarError(NotPresent)", CursedCategory::Envvar, CursedErrorType::NotFound),
+    ("std::env::VarError(NotUnicode)", CursedCategory::Envvar, CursedErrorType::Invalid),
+];
+
+/// the io rows from [`ERROR_KIND_MAPPINGS`], the other std errors and the tables of the
+/// enabled integrations
+pub(crate) fn classifications() -> Vec<(std::borrow::Cow<'static, str>, CursedCategory, CursedErrorType)> {
+    let io = ERROR_KIND_MAPPINGS.iter().filter_map(|(kind, error)| {
+        Some((format!("std::io::Error({:?})", kind).into(), error.category(), error.get_type()?))
+    });
+    #[allow(unused_mut)]
+    let mut tables = vec![STD_CLASSIFICATIONS];
+    #[cfg(feature = "base64")]
+    tables.push(base64::CLASSIFICATIONS);
+    #[cfg(feature = "chrono")]
+    tables.push(chrono::CLASSIFICATIONS);
+    #[cfg(feature = "hex")]
+    tables.push(hex::CLASSIFICATIONS);
+    #[cfg(feature = "native-tls")]
+    tables.push(native_tls::CLASSIFICATIONS);
+    #[cfg(feature = "regex")]
+    tables.push(regex::CLASSIFICATIONS);
+    #[cfg(feature = "rustls")]
+    tables.push(rustls::CLASSIFICATIONS);
+    #[cfg(feature = "time")]
+    tables.push(time::CLASSIFICATIONS);
+    #[cfg(feature = "tokio")]
+    tables.push(tokio::CLASSIFICATIONS);
+    #[cfg(feature = "toml")]
+    tables.push(toml::CLASSIFICATIONS);
+    #[cfg(feature = "url")]
+    tables.push(url::CLASSIFICATIONS);
+    #[cfg(feature = "walkdir")]
+    tables.push(walkdir::CLASSIFICATIONS);
+    #[cfg(feature = "yaml")]
+    tables.push(yaml::CLASSIFICATIONS);
+
+    let mut rows: Vec<_> = io.collect();
+    rows.extend(tables.into_iter().flatten().map(|(name, category, error_type)| ((*name).into(), *category, *error_type)));
+    #[cfg(feature = "tonic")]
+    rows.extend(tonic::classifications());
+    rows
+}
+
+/// kinds [`CursedError::Unknown`] can be read back from by their `Debug` name
+pub(crate) const IO_ERROR_KINDS: [ErrorKind; 39] = [
+    ErrorKind::NotFound, ErrorKind::PermissionDenied, ErrorKind::ConnectionRefused, ErrorKind::ConnectionReset,
+    ErrorKind::HostUnreachable, ErrorKind::NetworkUnreachable, ErrorKind::ConnectionAborted, ErrorKind::NotConnected,
+    ErrorKind::AddrInUse, ErrorKind::AddrNotAvailable, ErrorKind::NetworkDown, ErrorKind::BrokenPipe,
+    ErrorKind::AlreadyExists, ErrorKind::WouldBlock, ErrorKind::NotADirectory, ErrorKind::IsADirectory,
+    ErrorKind::DirectoryNotEmpty, ErrorKind::ReadOnlyFilesystem, ErrorKind::StaleNetworkFileHandle,
+    ErrorKind::InvalidInput, ErrorKind::InvalidData, ErrorKind::TimedOut, ErrorKind::WriteZero, ErrorKind::StorageFull,
+    ErrorKind::NotSeekable, ErrorKind::QuotaExceeded, ErrorKind::FileTooLarge, ErrorKind::ResourceBusy,
+    ErrorKind::ExecutableFileBusy, ErrorKind::Deadlock, ErrorKind::CrossesDevices, ErrorKind::TooManyLinks,
+    ErrorKind::InvalidFilename, ErrorKind::ArgumentListTooLong, ErrorKind::Interrupted, ErrorKind::Unsupported,
+    ErrorKind::UnexpectedEof, ErrorKind::OutOfMemory, ErrorKind::Other,
+];
+
+pub(crate) fn io_kind_from_name(name: &str) -> Option<ErrorKind> {
+    IO_ERROR_KINDS.into_iter().find(|kind| format!("{:?}", kind) == name)
+}
+
+/// resolves `host` with [`std::net::ToSocketAddrs`], failures are classified by
+/// [`CursedErrorHandle::resolve_err`]
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// assert_eq!(resolve("127.0.0.1", 8080).unwrap(), ["127.0.0.1:8080".parse().unwrap()]);
+///
+/// // rejected before asking the resolver
+/// let error = resolve("db\0internal", 5432).unwrap_err();
+///
+/// assert_eq!(error.get_error(), &CursedError::Input(CursedErrorType::Invalid));
+/// assert!(error.get_reason().starts_with("failed to resolve db\0internal: "));
+/// ```
+#[track_caller]
+pub fn resolve(host: &str, port: u16) -> CursedResult<Vec<std::net::SocketAddr>> {
+    match std::net::ToSocketAddrs::to_socket_addrs(&(host, port)) {
+        Ok(addrs) => Ok(addrs.collect()),
+        Err(error) => Err(CursedErrorHandle::resolve_err(host, error)),
+    }
+}
+
+/// table used by the [`ErrorKind`] to [`CursedError`] conversion, kinds that are not
+/// listed become [`CursedError::Unknown`] with the original kind
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// for (kind, error) in error_kind_mappings() {
+///     assert_eq!(CursedError::from(*kind), *error);
+/// }
+/// assert_eq!(
+///     CursedError::from(std::io::ErrorKind::WriteZero),
+///     CursedError::Unknown(Some(std::io::ErrorKind::WriteZero))
+/// );
+/// ```
+pub fn error_kind_mappings() -> &'static [(ErrorKind, CursedError)] {
+    &ERROR_KIND_MAPPINGS
+}
+
+/// exact pairs used by the [`CursedError`] to [`ErrorKind`] conversion before it falls
+/// back to mapping by the error type
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// for (error, kind) in reverse_error_kind_mappings() {
+///     assert_eq!(std::io::ErrorKind::from(*error), *kind);
+/// }
+/// for (kind, error) in error_kind_mappings() {
+///     assert_eq!(std::io::ErrorKind::from(*error), *kind);
+/// }
+/// ```
+pub fn reverse_error_kind_mappings() -> &'static [(CursedError, ErrorKind)] {
+    &REVERSE_ERROR_KIND_MAPPINGS
+}
+
+impl From<ErrorKind> for CursedError {
+    fn from(error: ErrorKind) -> Self {
+        if let Some(cursed) = policy::io_kind(error) {
+            return cursed
+        }
+        ERROR_KIND_MAPPINGS.iter()
+            .find(|(kind, _)| *kind == error)
+            .map_or(Self::Unknown(Some(error).filter(|kind| *kind != ErrorKind::Other)), |(_, cursed)| *cursed)
+    }
+}
+
+pub(crate) static CLASSIFY_BY_TEXT: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// lets [`CursedError::classify`] fall back to searching error messages for known phrases
+///
+/// Messages are not a stable interface, so this is disabled by default.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::error::Error;
+///
+/// let cases: [(Box<dyn Error>, CursedError); 5] = [
+///     ("Permission denied for /etc".into(), CursedError::Io(CursedErrorType::AccessDenied)),
+///     ("request timed out".into(), CursedError::Call(CursedErrorType::Timedout)),
+///     ("connection refused by peer".into(), CursedError::Connection(CursedErrorType::Refused)),
+///     ("user not found".into(), CursedError::Io(CursedErrorType::NotFound)),
+///     ("something odd".into(), CursedError::Unknown(None)),
+/// ];
+///
+/// classify_by_text(true);
+/// for (error, expected) in cases {
+///     assert_eq!(CursedError::classify(&*error), expected, "{}", error);
+/// }
+/// ```
+pub fn classify_by_text(enabled: bool) {
+    CLASSIFY_BY_TEXT.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+pub(crate) fn classify_type(error: &(dyn std::error::Error + 'static)) -> Option<CursedError> {
+    if let Some(error) = error.downcast_ref::<CursedErrorHandle>().or_else(|| typed_handle(error)) {
+        return Some(*error.get_error()).filter(|kind| !matches!(kind, CursedError::Unknown(_)));
+    }
+    if let Some(error) = error.downcast_ref::<std::io::Error>() {
+        return match CursedError::from(error.kind()) {
+            CursedError::Unknown(_) => error.get_ref().and_then(|error| classify_type(error)),
+            kind => Some(kind),
+        };
+    }
+    if let Some(error) = error.downcast_ref::<std::env::VarError>() {
+        return Some(match error {
+            std::env::VarError::NotPresent => CursedError::Envvar(CursedErrorType::NotFound),
+            std::env::VarError::NotUnicode(_) => CursedError::Envvar(CursedErrorType::Invalid),
+        });
+    }
+    #[cfg(feature = "rustls")]
+    if let Some(error) = error.downcast_ref::<::rustls::Error>() {
+        return Some(rustls::rustls_kind(error)).filter(|kind| !matches!(kind, CursedError::Unknown(_)));
+    }
+    #[cfg(feature = "native-tls")]
+    if let Some(error) = error.downcast_ref::<::native_tls::Error>() {
+        return Some(native_tls::native_tls_kind(error)).filter(|kind| !matches!(kind, CursedError::Unknown(_)));
+    }
+
+    if error.is::<std::net::AddrParseError>() {
+        Some(CursedError::Address(CursedErrorType::Parse))
+    } else if error.is::<std::str::Utf8Error>()
+        || error.is::<std::string::FromUtf8Error>()
+        || error.is::<std::string::FromUtf16Error>() {
+        Some(CursedError::Data(CursedErrorType::Invalid))
+    } else if let Some(error) = error.downcast_ref::<std::num::ParseIntError>() {
+        Some(parse_int_kind(error))
+    } else if error.is::<std::num::ParseFloatError>()
+        || error.is::<std::str::ParseBoolError>()
+        || error.is::<std::char::ParseCharError>()
+        || error.is::<std::char::TryFromCharError>() {
+        Some(CursedError::Data(CursedErrorType::Parse))
+    } else if error.is::<std::num::TryFromIntError>() {
+        Some(CursedError::Data(CursedErrorType::Overflow))
+    } else if error.is::<std::time::SystemTimeError>() {
+        Some(CursedError::Other(CursedErrorType::Invalid))
+    } else if error.is::<std::collections::TryReserveError>() {
+        Some(CursedError::Memory(CursedErrorType::NotEnough))
+    } else if error.is::<std::alloc::LayoutError>() {
+        Some(CursedError::Memory(CursedErrorType::Invalid))
+    } else {
+        None
+    }
+}
+
+/// kind of a failed integer parse, by the [`std::num::IntErrorKind`] of the error
+pub(crate) fn parse_int_kind(error: &std::num::ParseIntError) -> CursedError {
+    match error.kind() {
+        std::num::IntErrorKind::PosOverflow | std::num::IntErrorKind::NegOverflow => {
+            CursedError::Data(CursedErrorType::Overflow)
+        },
+        std::num::IntErrorKind::Empty => CursedError::Input(CursedErrorType::NotEnough),
+        _ => CursedError::Data(CursedErrorType::Parse),
+    }
+}
+
+pub(crate) fn classify_text(message: &str) -> Option<CursedError> {
+    const PHRASES: [(&str, CursedError); 9] = [
+        ("permission denied", CursedError::Io(CursedErrorType::AccessDenied)),
+        ("access denied", CursedError::Io(CursedErrorType::AccessDenied)),
+        ("timed out", CursedError::Call(CursedErrorType::Timedout)),
+        ("connection refused", CursedError::Connection(CursedErrorType::Refused)),
+        ("connection reset", CursedError::Connection(CursedErrorType::Reset)),
+        ("out of memory", CursedError::Memory(CursedErrorType::NotEnough)),
+        ("already exists", CursedError::Io(CursedErrorType::AlreadyExists)),
+        ("no such file", CursedError::Io(CursedErrorType::NotFound)),
+        ("not found", CursedError::Io(CursedErrorType::NotFound)),
+    ];
+
+    let message = message.to_lowercase();
+    PHRASES.iter()
+        .find(|(phrase, _)| message.contains(phrase))
+        .map(|(_, kind)| *kind)
+}
+
+/// wraps a foreign error as the source of a new handle classified by [`CursedError::classify`]
+#[track_caller]
+pub(crate) fn wrap_foreign(error: Box<dyn std::error::Error + Send + Sync>, reason: String) -> CursedErrorHandle {
+    let mut handle = CursedErrorHandle::new(CursedError::classify(&*error), reason);
+
+    if let Some(os_code) = error.downcast_ref::<std::io::Error>().and_then(std::io::Error::raw_os_error) {
+        handle.inner_mut().os_code = Some(os_code);
+    }
+    handle.with_source(error)
+}
+
+/// converts any error into a handle, for errors without a [`From`] impl
+///
+/// The kind is picked by [`CursedError::classify`], the reason is the message of the
+/// error and the error is kept as the source. Handles are returned as they are.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::error::Error;
+///
+/// let error = wrap_any(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+///
+/// assert_eq!(error.get_error(), &CursedError::Io(CursedErrorType::AccessDenied));
+/// assert!(std::error::Error::source(&error).unwrap().is::<std::io::Error>());
+///
+/// #[derive(Debug)]
+/// struct Opaque;
+///
+/// impl std::fmt::Display for Opaque {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         f.write_str("the vendor sdk said no")
+///     }
+/// }
+/// impl Error for Opaque {}
+///
+/// let error = wrap_any(Opaque);
+///
+/// assert_eq!(error.get_error(), &CursedError::Unknown(None));
+/// assert_eq!(error.get_reason(), "the vendor sdk said no");
+/// assert!(error.source().unwrap().is::<Opaque>());
+/// ```
+#[track_caller]
+pub fn wrap_any<E: std::error::Error + Send + Sync + 'static>(error: E) -> CursedErrorHandle {
+    match downcast_handle(error) {
+        Ok(handle) => handle,
+        Err(error) => {
+            let reason = error.to_string();
+            wrap_foreign(Box::new(error), reason)
+        },
+    }
+}
+
+/// runs `f` and wraps its error in a handle of the given kind, keeping the error as the source
+///
+/// The reason is the message of the error, any error converts into the box, so `?`
+/// works on all of them inside the closure.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::error::Error;
+///
+/// fn load(path: &str) -> CursedResult<u16> {
+///     try_wrap(CursedError::File(CursedErrorType::Invalid), || {
+///         let text = std::fs::read_to_string(path)?;
+///         Ok(text.trim().parse::<u16>()?)
+///     })
+/// }
+///
+/// let error = load("/curerr/missing/port").unwrap_err();
+///
+/// assert_eq!(error.get_error(), &CursedError::File(CursedErrorType::Invalid));
+/// assert!(std::error::Error::source(&error).unwrap().is::<std::io::Error>());
+/// # #[cfg(unix)]
+/// assert_eq!(error.os_code(), Some(2));
+///
+/// let ok = try_wrap(CursedError::Data(CursedErrorType::Parse), || Ok("7".parse::<u8>()?));
+/// assert_eq!(ok.unwrap(), 7);
+/// ```
+#[track_caller]
+pub fn try_wrap<T>(
+    error: CursedError,
+    f: impl FnOnce() -> Result<T, Box<dyn std::error::Error + Send + Sync>>
+) -> CursedResult<T> {
+    let source = match f() {
+        Ok(value) => return Ok(value),
+        Err(source) => source,
+    };
+
+    let mut handle = CursedErrorHandle::new(error, source.to_string());
+    if let Some(os_code) = source.downcast_ref::<std::io::Error>().and_then(std::io::Error::raw_os_error) {
+        handle.inner_mut().os_code = Some(os_code);
+    }
+    Err(handle.with_source(source))
+}
+
+/// runs `f` and converts its error into a handle, keeping the classification of the conversion
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// # #[cfg(feature = "std-conversions")] {
+/// let error = try_wrap_with(|| "x".parse::<u8>()).unwrap_err();
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+///
+/// let value = try_wrap_with(|| Ok::<_, std::io::Error>(3)).unwrap();
+/// assert_eq!(value, 3);
+/// # }
+/// ```
+#[track_caller]
+pub fn try_wrap_with<T, E>(f: impl FnOnce() -> Result<T, E>) -> CursedResult<T>
+where
+    CursedErrorHandle: From<E>,
+{
+    match f() {
+        Ok(value) => Ok(value),
+        Err(error) => Err(CursedErrorHandle::from(error)),
+    }
+}
+
+/// runs the io operation `op` on `path` and converts its error, the reason is like
+/// `"read /etc/app.conf: permission denied"`
+///
+/// The kind comes from the [`ErrorKind`] conversion with the `File` category instead of
+/// `Io`, like [`CursedErrorHandle::open_err`]. `op` is kept as the
+/// [op](CursedErrorHandle::op), the path as the [path](CursedErrorHandle::path) and the
+/// io error as the source. Errors of the OS are described by their kind, the message of
+/// other errors is used as is.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let path = "/definitely/not/here.conf";
+/// let error = wrap_io("read", path, || std::fs::read_to_string(path)).unwrap_err();
+///
+/// assert_eq!(error.get_error(), &CursedError::File(CursedErrorType::NotFound));
+/// assert_eq!(error.get_reason(), "read /definitely/not/here.conf: entity not found");
+/// assert_eq!(error.op(), Some("read"));
+/// assert_eq!(error.path(), Some(std::path::Path::new(path)));
+/// assert!(std::error::Error::source(&error).unwrap().is::<std::io::Error>());
+/// # #[cfg(unix)]
+/// assert_eq!(error.os_code(), Some(2));
+///
+/// let error = wrap_io("parse", "app.conf", || -> std::io::Result<()> {
+///     Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "line 3 has no value"))
+/// }).unwrap_err();
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+/// assert_eq!(error.get_reason(), "parse app.conf: line 3 has no value");
+/// ```
+///
+/// Running a file without execute permission fails with a permission error, also for
+/// root. The example only runs on unix.
+///
+/// ```
+/// # #[cfg(unix)] {
+/// use curerr::*;
+/// use std::os::unix::fs::PermissionsExt;
+///
+/// let file = std::env::temp_dir().join(format!("curerr-wrap-io-{}.sh", std::process::id()));
+/// std::fs::write(&file, "#!/bin/sh\n").unwrap();
+/// std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o644)).unwrap();
+///
+/// let result = wrap_io("run", &file, || std::process::Command::new(&file).status());
+/// std::fs::remove_file(&file).unwrap();
+///
+/// let error = result.unwrap_err();
+/// assert_eq!(error.get_error(), &CursedError::File(CursedErrorType::AccessDenied));
+/// assert_eq!(error.get_reason(), format!("run {}: permission denied", file.display()));
+/// assert_eq!(error.os_code(), Some(13));
+/// # }
+/// ```
+#[track_caller]
+pub fn wrap_io<T>(
+    op: &'static str,
+    path: impl AsRef<std::path::Path>,
+    f: impl FnOnce() -> std::io::Result<T>
+) -> CursedResult<T> {
+    let error = match f() {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    let path = path.as_ref();
+    let mut inner = io_inner(format!("{} {}", op, path.display()), op, error);
+    inner.error = file_error(inner.error);
+    inner.path = Some(path.to_path_buf());
+    Err(CursedErrorHandle::from_inner(inner))
+}
+
+/// [`wrap_io`] for io operations without a path, like on sockets, the reason is like
+/// `"connect: connection refused"`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::io::Read;
+///
+/// let mut socket: &[u8] = &[];
+/// let error = wrap_io_op("read header", || socket.read_exact(&mut [0; 4])).unwrap_err();
+///
+/// assert_eq!(error.get_error(), &CursedError::Unknown(Some(std::io::ErrorKind::UnexpectedEof)));
+/// assert_eq!(error.get_reason(), "read header: failed to fill whole buffer");
+/// assert_eq!(error.op(), Some("read header"));
+/// assert_eq!(error.path(), None);
+/// ```
+#[track_caller]
+pub fn wrap_io_op<T>(op: &'static str, f: impl FnOnce() -> std::io::Result<T>) -> CursedResult<T> {
+    match f() {
+        Ok(value) => Ok(value),
+        Err(error) => Err(CursedErrorHandle::from_inner(io_inner(op.to_string(), op, error))),
+    }
+}
+
+/// `error` with the `File` category if it has the `Io` one, for errors of operations on a path
+pub(crate) fn file_error(error: CursedError) -> CursedError {
+    match error {
+        CursedError::Io(error_type) => CursedError::File(error_type),
+        error => error,
+    }
+}
+
+#[track_caller]
+fn io_inner(subject: String, op: &'static str, error: std::io::Error) -> Inner {
+    let message = match error.raw_os_error() {
+        Some(_) => error.kind().to_string(),
+        None => error.to_string(),
+    };
+
+    let mut inner = Inner::new(CursedError::from(error.kind()), format!("{}: {}", subject, message));
+    inner.op = Some(op);
+    inner.os_code = error.raw_os_error();
+    inner.source = Some(std::sync::Arc::new(error));
+    inner
+}
+
+/// best effort reverse of the [`ErrorKind`] to [`CursedError`] mapping
+///
+/// Kinds produced by the forward mapping are restored exactly, other errors are
+/// mapped by their type, falling back to [`ErrorKind::Other`].
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::io::ErrorKind;
+///
+/// assert_eq!(ErrorKind::from(CursedError::from(ErrorKind::AddrInUse)), ErrorKind::AddrInUse);
+/// assert_eq!(ErrorKind::from(CursedError::File(CursedErrorType::NotFound)), ErrorKind::NotFound);
+/// assert_eq!(ErrorKind::from(CursedError::Unknown(None)), ErrorKind::Other);
+/// assert_eq!(ErrorKind::from(CursedError::from(ErrorKind::FileTooLarge)), ErrorKind::FileTooLarge);
+/// ```
+impl From<CursedError> for ErrorKind {
+    fn from(error: CursedError) -> Self {
+        if let Some((_, kind)) = REVERSE_ERROR_KIND_MAPPINGS.iter().find(|(cursed, _)| *cursed == error) {
+            return *kind;
+        }
+        if let CursedError::Unknown(Some(kind)) = error {
+            return kind;
+        }
+
+        match error.get_type() {
+            Some(CursedErrorType::NotImplemented | CursedErrorType::NotSupported) => ErrorKind::Unsupported,
+            Some(CursedErrorType::AlreadyExists) => ErrorKind::AlreadyExists,
+            Some(CursedErrorType::AccessDenied) => ErrorKind::PermissionDenied,
+            Some(CursedErrorType::Interrupted) => ErrorKind::Interrupted,
+            Some(CursedErrorType::Timedout) => ErrorKind::TimedOut,
+            Some(CursedErrorType::NotFound) => ErrorKind::NotFound,
+            Some(CursedErrorType::Refused) => ErrorKind::ConnectionRefused,
+            Some(CursedErrorType::Reset) => ErrorKind::ConnectionReset,
+            Some(CursedErrorType::Aborted) => ErrorKind::ConnectionAborted,
+            Some(CursedErrorType::Invalid) => ErrorKind::InvalidInput,
+            Some(CursedErrorType::Parse) => ErrorKind::InvalidData,
+            Some(CursedErrorType::NotEnough | CursedErrorType::Overflow | CursedErrorType::Custom(_))
+            | None => ErrorKind::Other,
+        }
+    }
+}
+
+/// compares through the reverse [`ErrorKind`] mapping
+///
+/// The comparison is approximate where the mapping is lossy, e.g. every category
+/// with the `NotFound` type equals [`ErrorKind::NotFound`].
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::io::ErrorKind;
+///
+/// let error = CursedErrorHandle::new(CursedError::Io(CursedErrorType::NotFound), "config.toml".to_string());
+///
+/// assert_eq!(error.kind(), ErrorKind::NotFound);
+/// assert_eq!(CursedError::File(CursedErrorType::NotFound), ErrorKind::NotFound);
+/// assert_ne!(CursedError::File(CursedErrorType::NotFound), ErrorKind::PermissionDenied);
+/// assert_eq!(ErrorKind::TimedOut, CursedError::Call(CursedErrorType::Timedout));
+/// assert_eq!(CursedError::Unknown(None), ErrorKind::Other);
+/// ```
+impl PartialEq<ErrorKind> for CursedError {
+    fn eq(&self, other: &ErrorKind) -> bool {
+        ErrorKind::from(*self) == *other
+    }
+}
+
+impl PartialEq<CursedError> for ErrorKind {
+    fn eq(&self, other: &CursedError) -> bool {
+        other == self
+    }
+}
+
+/// whether a deserializer message is one of the standard `serde` errors about the shape
+/// of the data, as opposed to a syntax error, the message may be prefixed with the path
+/// of the value, e.g. `"server.port: invalid type: ..."`
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub(crate) fn is_semantic_error(message: &str) -> bool {
+    const PREFIXES: [&str; 7] = [
+        "missing field",
+        "unknown field",
+        "duplicate field",
+        "invalid type",
+        "invalid value",
+        "invalid length",
+        "unknown variant",
+    ];
+
+    let mut rest = message;
+    loop {
+        if PREFIXES.iter().any(|prefix| rest.starts_with(prefix)) {
+            return true
+        }
+        match rest.split_once(": ") {
+            Some((_, tail)) => rest = tail,
+            None => return false,
+        }
+    }
+}
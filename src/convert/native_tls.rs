@@ -0,0 +1,110 @@
+//! conversions of `native-tls` errors
+
+use crate::*;
+use crate::convert::Classification;
+
+/// handshake failures by the phrase [`native_tls_kind`] finds in them, listed by
+/// [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("native_tls::Error(untrusted certificate)", CursedCategory::Security, CursedErrorType::Invalid),
+    ("native_tls::Error(protocol version)", CursedCategory::Connection, CursedErrorType::Refused),
+    ("native_tls::Error(unexpected eof)", CursedCategory::Connection, CursedErrorType::Reset),
+];
+
+/// classified by the io error in the source chain, or else by the message of the
+/// platform library, the error is kept as the source
+///
+/// `native_tls::Error` is opaque, so the kind is picked by the first matching row.
+/// The phrases are the ones of OpenSSL, Schannel and Secure Transport.
+///
+/// | error | kind |
+/// |-|-|
+/// | io error of [`std::io::ErrorKind::UnexpectedEof`], message with "unexpected eof" | `Connection(Reset)` |
+/// | other io error | its [mapping](crate::error_kind_mappings) |
+/// | "certificate revoked", "certificate was revoked" | `Security(AccessDenied)` |
+/// | "certificate", "not trusted" | `Security(Invalid)` |
+/// | "protocol version", "wrong version number", "unsupported protocol", "no protocols available", "handshake failure", "no shared cipher" | `Connection(Refused)` |
+/// | "bad record mac", "decryption failed", "packet length too long", "record layer failure", "unexpected message" | `Data(Invalid)` |
+/// | anything else | `Unknown` |
+///
+/// [`CursedError::classify`] gives the same kind, so does [`wrap_any`].
+///
+/// The handshake errors of a live connection are tested in `tests/tls.rs`.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let Err(error) = native_tls::Certificate::from_pem(b"not a certificate") else { unreachable!() };
+/// let error = CursedErrorHandle::from(error);
+///
+/// assert!(std::error::Error::source(&error).unwrap().is::<native_tls::Error>());
+/// ```
+impl From<native_tls::Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: native_tls::Error) -> Self {
+        Self::new(native_tls_kind(&error), error.to_string()).with_source(error)
+    }
+}
+
+/// handshakes fail with [`native_tls::HandshakeError::Failure`], an interrupted handshake
+/// of a non-blocking stream becomes `Io(Interrupted)`
+///
+/// # Examples
+/// ```no_run
+/// use curerr::*;
+///
+/// fn connect(host: &str) -> CursedResult<native_tls::TlsStream<std::net::TcpStream>> {
+///     let stream = std::net::TcpStream::connect((host, 443))?;
+///     Ok(native_tls::TlsConnector::new()?.connect(host, stream)?)
+/// }
+/// ```
+impl<S> From<native_tls::HandshakeError<S>> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: native_tls::HandshakeError<S>) -> Self {
+        match error {
+            native_tls::HandshakeError::Failure(error) => error.into(),
+            native_tls::HandshakeError::WouldBlock(_) => {
+                Self::new(CursedError::Io(CursedErrorType::Interrupted), "TLS handshake would block".to_string())
+            },
+        }
+    }
+}
+
+pub(crate) fn native_tls_kind(error: &native_tls::Error) -> CursedError {
+    const PHRASES: [(&str, CursedError); 16] = [
+        ("unexpected eof", CursedError::Connection(CursedErrorType::Reset)),
+        ("certificate revoked", CursedError::Security(CursedErrorType::AccessDenied)),
+        ("certificate was revoked", CursedError::Security(CursedErrorType::AccessDenied)),
+        ("certificate", CursedError::Security(CursedErrorType::Invalid)),
+        ("not trusted", CursedError::Security(CursedErrorType::Invalid)),
+        ("protocol version", CursedError::Connection(CursedErrorType::Refused)),
+        ("wrong version number", CursedError::Connection(CursedErrorType::Refused)),
+        ("unsupported protocol", CursedError::Connection(CursedErrorType::Refused)),
+        ("no protocols available", CursedError::Connection(CursedErrorType::Refused)),
+        ("handshake failure", CursedError::Connection(CursedErrorType::Refused)),
+        ("no shared cipher", CursedError::Connection(CursedErrorType::Refused)),
+        ("bad record mac", CursedError::Data(CursedErrorType::Invalid)),
+        ("decryption failed", CursedError::Data(CursedErrorType::Invalid)),
+        ("packet length too long", CursedError::Data(CursedErrorType::Invalid)),
+        ("record layer failure", CursedError::Data(CursedErrorType::Invalid)),
+        ("unexpected message", CursedError::Data(CursedErrorType::Invalid)),
+    ];
+
+    let mut source = std::error::Error::source(error);
+    while let Some(error) = source {
+        if let Some(error) = error.downcast_ref::<std::io::Error>() {
+            match (error.kind(), CursedError::from(error.kind())) {
+                (std::io::ErrorKind::UnexpectedEof, _) => return CursedError::Connection(CursedErrorType::Reset),
+                (_, CursedError::Unknown(_)) => {},
+                (_, kind) => return kind,
+            }
+        }
+        source = error.source();
+    }
+
+    let message = error.to_string().to_lowercase();
+    PHRASES.iter()
+        .find(|(phrase, _)| message.contains(phrase))
+        .map_or(CursedError::Unknown(None), |(_, kind)| *kind)
+}
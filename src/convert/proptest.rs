@@ -0,0 +1,72 @@
+//! `proptest` strategies for the error kinds and handles
+
+use crate::*;
+use crate::convert::{ERROR_KIND_MAPPINGS, IO_ERROR_KINDS};
+use crate::kind::{ERROR_TYPES, TYPED_CATEGORIES};
+
+use proptest::prelude::*;
+use std::io::ErrorKind;
+
+/// names of the generated custom categories and types, a fixed set so generating
+/// doesn't fill the table of interned names
+const CUSTOM_CATEGORIES: [&str; 4] = ["billing", "cache", "queue", "auth"];
+const CUSTOM_TYPES: [&str; 4] = ["rate limited", "quota exceeded", "tampered", "stale"];
+
+/// predefined types and a few custom ones, shrinks toward the predefined types
+impl Arbitrary for CursedErrorType {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        prop_oneof![
+            9 => proptest::sample::select(ERROR_TYPES.to_vec()),
+            1 => proptest::sample::select(CUSTOM_TYPES.to_vec()).prop_map(CursedErrorType::Custom),
+        ].boxed()
+    }
+}
+
+/// every category with every type, `NoError`, `Unknown` with the io kinds the
+/// [`ErrorKind`] conversion keeps and custom categories, shrinks toward the
+/// predefined categories
+impl Arbitrary for CursedError {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let unmapped_kinds: Vec<ErrorKind> = IO_ERROR_KINDS.into_iter()
+            .filter(|kind| *kind != ErrorKind::Other && ERROR_KIND_MAPPINGS.iter().all(|(mapped, _)| mapped != kind))
+            .collect();
+
+        prop_oneof![
+            16 => (proptest::sample::select(TYPED_CATEGORIES.to_vec()), any::<CursedErrorType>())
+                .prop_map(|(category, error_type)| category.with_type(error_type)),
+            1 => Just(CursedError::NoError),
+            1 => proptest::option::of(proptest::sample::select(unmapped_kinds)).prop_map(CursedError::Unknown),
+            2 => (proptest::sample::select(CUSTOM_CATEGORIES.to_vec()), any::<CursedErrorType>())
+                .prop_map(|(category, kind)| CursedError::Custom { category, kind }),
+        ].boxed()
+    }
+}
+
+/// any [`CursedError`] with a printable reason of up to 64 characters, shrinks toward
+/// simpler kinds and shorter reasons
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use proptest::prelude::*;
+///
+/// proptest!(|(error in any::<CursedErrorHandle>())| {
+///     prop_assert!(error.to_string().starts_with(&error.get_error().to_string()));
+/// });
+/// ```
+impl Arbitrary for CursedErrorHandle {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (any::<CursedError>(), "\\PC{0,64}")
+            .prop_map(|(error, reason)| CursedErrorHandle::new(error, reason))
+            .boxed()
+    }
+}
@@ -0,0 +1,84 @@
+//! conversions of `regex` errors
+
+use crate::*;
+use crate::convert::Classification;
+use crate::handle::{format_size, Field};
+
+/// compile errors by variant, listed by [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("regex::Error(Syntax)", CursedCategory::Input, CursedErrorType::Parse),
+    ("regex::Error(CompiledTooBig)", CursedCategory::Memory, CursedErrorType::NotEnough),
+];
+
+/// maximum number of characters of a pattern kept by [`CursedErrorHandle::bad_pattern`]
+const MAX_PATTERN: usize = 64;
+
+fn regex_error(error: &regex::Error) -> (CursedError, String) {
+    match error {
+        // the message already shows the pattern with the offending span marked
+        regex::Error::Syntax(message) => (CursedError::Input(CursedErrorType::Parse), message.trim_end().to_string()),
+        regex::Error::CompiledTooBig(limit) => (
+            CursedError::Memory(CursedErrorType::NotEnough),
+            format!("compiled regex exceeds the size limit of {}", format_size(*limit as u64))
+        ),
+        _ => (CursedError::Data(CursedErrorType::Invalid), error.to_string()),
+    }
+}
+
+impl CursedErrorHandle {
+    /// same as the conversion from [`regex::Error`], but the offending pattern is kept
+    /// as the `pattern` field, cut after 64 characters with `…` appended
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let pattern = format!("{}(", "a".repeat(100));
+    /// let error = CursedErrorHandle::bad_pattern(&pattern, regex::Regex::new(&pattern).unwrap_err());
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Input(CursedErrorType::Parse));
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("pattern", format!("{}…", "a".repeat(64)).into())]);
+    ///
+    /// let error = CursedErrorHandle::bad_pattern("[z-a]", regex::Regex::new("[z-a]").unwrap_err());
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("pattern", "[z-a]".into())]);
+    /// ```
+    #[track_caller]
+    pub fn bad_pattern(pattern: &str, error: regex::Error) -> Self {
+        let pattern = match pattern.char_indices().nth(MAX_PATTERN) {
+            Some((end, _)) => format!("{}…", &pattern[..end]),
+            None => pattern.to_string(),
+        };
+
+        let mut handle = Self::from(error);
+        handle.inner_mut().fields.push(("pattern".into(), Field::Plain(pattern)));
+        handle
+    }
+}
+
+/// syntax errors become `Input(Parse)` with the marked span of the pattern in the reason,
+/// patterns over the size limit `Memory(NotEnough)` with the limit in the reason and
+/// unknown errors `Data(Invalid)`, the error is kept as the source
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedErrorHandle::from(regex::Regex::new(r"port=(\d+").unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Input(CursedErrorType::Parse));
+/// assert_eq!(error.get_reason(), "regex parse error:\n    port=(\\d+\n         ^\nerror: unclosed group");
+/// assert!(std::error::Error::source(&error).unwrap().is::<regex::Error>());
+///
+/// let error = regex::RegexBuilder::new(r"\w{100}").size_limit(1_024).build().unwrap_err();
+/// let error = CursedErrorHandle::from(error);
+///
+/// assert_eq!(error.get_error(), &CursedError::Memory(CursedErrorType::NotEnough));
+/// assert_eq!(error.get_reason(), "compiled regex exceeds the size limit of 1KiB (1024 bytes)");
+/// ```
+impl From<regex::Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: regex::Error) -> Self {
+        let (kind, reason) = regex_error(&error);
+        Self::new(kind, reason).with_source(error)
+    }
+}
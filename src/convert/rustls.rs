@@ -0,0 +1,123 @@
+//! conversions of `rustls` errors
+
+use crate::*;
+use crate::convert::Classification;
+
+use rustls::{AlertDescription, CertificateError, Error};
+
+/// a few of the errors [`rustls_kind`] maps, listed by [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("rustls::Error(InvalidCertificate(Revoked))", CursedCategory::Security, CursedErrorType::AccessDenied),
+    ("rustls::Error(InvalidCertificate(Expired))", CursedCategory::Security, CursedErrorType::Invalid),
+    ("rustls::Error(AlertReceived(HandshakeFailure))", CursedCategory::Connection, CursedErrorType::Refused),
+    ("rustls::Error(DecryptError)", CursedCategory::Data, CursedErrorType::Invalid),
+    ("rustls::Error(HandshakeNotComplete)", CursedCategory::Connection, CursedErrorType::NotImplemented),
+];
+
+/// certificate problems become `Security` errors, incompatible peers `Connection(Refused)`
+/// and malformed traffic `Data(Invalid)`, the error is kept as the source
+///
+/// | error | kind |
+/// |-|-|
+/// | `InvalidCertificate(Revoked)`, `NoCertificatesPresented` | `Security(AccessDenied)` |
+/// | other `InvalidCertificate`, `InvalidCertRevocationList` | `Security(Invalid)` |
+/// | `UnsupportedNameType` | `Security(NotSupported)` |
+/// | `AlertReceived` of a revoked, missing or denied certificate | `Security(AccessDenied)` |
+/// | `AlertReceived` of another certificate problem | `Security(Invalid)` |
+/// | `PeerIncompatible`, `NoApplicationProtocol`, `AlertReceived` of a version, cipher or protocol mismatch | `Connection(Refused)` |
+/// | other `AlertReceived` | `Connection(Aborted)` |
+/// | `InappropriateMessage`, `InappropriateHandshakeMessage`, `InvalidMessage`, `InvalidEncryptedClientHello`, `PeerMisbehaved`, `DecryptError` | `Data(Invalid)` |
+/// | `PeerSentOversizedRecord` | `Data(Overflow)` |
+/// | `HandshakeNotComplete` | `Connection(NotImplemented)`, like [`std::io::ErrorKind::NotConnected`] |
+/// | `BadMaxFragmentSize`, `InconsistentKeys` | `Input(Invalid)` |
+/// | anything else | `Unknown` |
+///
+/// [`CursedError::classify`] gives the same kind, so does [`wrap_any`].
+///
+/// rustls has no error for a connection closed during the handshake, the stream
+/// adapters return an io error of [`std::io::ErrorKind::UnexpectedEof`] instead.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use rustls::{AlertDescription, CertificateError, Error, PeerIncompatible};
+///
+/// let cases = [
+///     (Error::InvalidCertificate(CertificateError::UnknownIssuer), CursedError::Security(CursedErrorType::Invalid)),
+///     (Error::InvalidCertificate(CertificateError::Expired), CursedError::Security(CursedErrorType::Invalid)),
+///     (Error::InvalidCertificate(CertificateError::Revoked), CursedError::Security(CursedErrorType::AccessDenied)),
+///     (Error::NoCertificatesPresented, CursedError::Security(CursedErrorType::AccessDenied)),
+///     (Error::AlertReceived(AlertDescription::UnknownCA), CursedError::Security(CursedErrorType::Invalid)),
+///     (
+///         Error::PeerIncompatible(PeerIncompatible::ServerDoesNotSupportTls12Or13),
+///         CursedError::Connection(CursedErrorType::Refused)
+///     ),
+///     (Error::AlertReceived(AlertDescription::ProtocolVersion), CursedError::Connection(CursedErrorType::Refused)),
+///     (Error::AlertReceived(AlertDescription::InternalError), CursedError::Connection(CursedErrorType::Aborted)),
+///     (Error::DecryptError, CursedError::Data(CursedErrorType::Invalid)),
+///     (Error::PeerSentOversizedRecord, CursedError::Data(CursedErrorType::Overflow)),
+///     (Error::General("oops".to_string()), CursedError::Unknown(None)),
+/// ];
+///
+/// for (error, expected) in cases {
+///     assert_eq!(CursedErrorHandle::from(error.clone()).get_error(), &expected, "{:?}", error);
+/// }
+///
+/// let error = CursedErrorHandle::from(Error::InvalidCertificate(CertificateError::NotValidForName));
+///
+/// assert_eq!(error.get_reason(), "invalid peer certificate: NotValidForName");
+/// assert!(std::error::Error::source(&error).unwrap().is::<Error>());
+/// ```
+impl From<Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: Error) -> Self {
+        Self::new(rustls_kind(&error), error.to_string()).with_source(error)
+    }
+}
+
+pub(crate) fn rustls_kind(error: &Error) -> CursedError {
+    match error {
+        Error::InvalidCertificate(CertificateError::Revoked) | Error::NoCertificatesPresented => {
+            CursedError::Security(CursedErrorType::AccessDenied)
+        },
+        Error::InvalidCertificate(_) | Error::InvalidCertRevocationList(_) => {
+            CursedError::Security(CursedErrorType::Invalid)
+        },
+        Error::UnsupportedNameType => CursedError::Security(CursedErrorType::NotSupported),
+        Error::AlertReceived(alert) => alert_kind(*alert),
+        Error::PeerIncompatible(_) | Error::NoApplicationProtocol => CursedError::Connection(CursedErrorType::Refused),
+        Error::InappropriateMessage { .. }
+        | Error::InappropriateHandshakeMessage { .. }
+        | Error::InvalidMessage(_)
+        | Error::InvalidEncryptedClientHello(_)
+        | Error::PeerMisbehaved(_)
+        | Error::DecryptError => CursedError::Data(CursedErrorType::Invalid),
+        Error::PeerSentOversizedRecord => CursedError::Data(CursedErrorType::Overflow),
+        Error::HandshakeNotComplete => CursedError::Connection(CursedErrorType::NotImplemented),
+        Error::BadMaxFragmentSize | Error::InconsistentKeys(_) => CursedError::Input(CursedErrorType::Invalid),
+        _ => CursedError::Unknown(None),
+    }
+}
+
+fn alert_kind(alert: AlertDescription) -> CursedError {
+    match alert {
+        AlertDescription::CertificateRevoked
+        | AlertDescription::NoCertificate
+        | AlertDescription::CertificateRequired
+        | AlertDescription::AccessDenied => CursedError::Security(CursedErrorType::AccessDenied),
+        AlertDescription::BadCertificate
+        | AlertDescription::UnsupportedCertificate
+        | AlertDescription::CertificateExpired
+        | AlertDescription::CertificateUnknown
+        | AlertDescription::CertificateUnobtainable
+        | AlertDescription::UnknownCA
+        | AlertDescription::BadCertificateStatusResponse
+        | AlertDescription::BadCertificateHashValue => CursedError::Security(CursedErrorType::Invalid),
+        AlertDescription::ProtocolVersion
+        | AlertDescription::HandshakeFailure
+        | AlertDescription::InsufficientSecurity
+        | AlertDescription::InappropriateFallback
+        | AlertDescription::NoApplicationProtocol => CursedError::Connection(CursedErrorType::Refused),
+        _ => CursedError::Connection(CursedErrorType::Aborted),
+    }
+}
@@ -0,0 +1,156 @@
+//! `schemars` schemas of the `serde` form of the error kinds and handles
+
+use crate::*;
+use crate::kind::{ERROR_TYPES, TYPED_CATEGORIES};
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use std::borrow::Cow;
+
+/// one of the predefined ids, custom categories are any other string
+impl JsonSchema for CursedCategory {
+    fn schema_name() -> Cow<'static, str> {
+        "CursedCategory".into()
+    }
+    fn schema_id() -> Cow<'static, str> {
+        "curerr::CursedCategory".into()
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "category of the error, one of the predefined ids or the name of a custom category",
+            "anyOf": [
+                {"type": "string", "enum": category_ids()},
+                {"type": "string"},
+            ],
+        })
+    }
+}
+
+/// one of the predefined ids, custom types are any other string
+impl JsonSchema for CursedErrorType {
+    fn schema_name() -> Cow<'static, str> {
+        "CursedErrorType".into()
+    }
+    fn schema_id() -> Cow<'static, str> {
+        "curerr::CursedErrorType".into()
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "type of the error, one of the predefined ids or the phrase of a custom type",
+            "anyOf": [
+                {"type": "string", "enum": ERROR_TYPES.iter().map(CursedErrorType::to_id).collect::<Vec<_>>()},
+                {"type": "string"},
+            ],
+        })
+    }
+}
+
+/// the `{"category": ..., "type": ...}` object, typed categories have a type,
+/// `no_error` and `unknown` have `null` and custom categories are marked with
+/// `"custom": true`
+impl JsonSchema for CursedError {
+    fn schema_name() -> Cow<'static, str> {
+        "CursedError".into()
+    }
+    fn schema_id() -> Cow<'static, str> {
+        "curerr::CursedError".into()
+    }
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let error_type = generator.subschema_for::<CursedErrorType>();
+
+        json_schema!({
+            "description": "kind of an error",
+            "type": "object",
+            "oneOf": [
+                {
+                    "properties": {
+                        "category": {"enum": TYPED_CATEGORIES.iter().map(CursedCategory::to_id).collect::<Vec<_>>()},
+                        "type": error_type,
+                        "custom": {"const": false},
+                    },
+                },
+                {
+                    "properties": {
+                        "category": {"enum": [CursedCategory::NoError.to_id(), CursedCategory::Unknown.to_id()]},
+                        "type": {"type": "null"},
+                        "custom": {"const": false},
+                        "io_kind": {"type": "string", "description": "`Debug` name of the converted io::ErrorKind"},
+                    },
+                },
+                {
+                    "properties": {
+                        "category": {"type": "string"},
+                        "type": error_type,
+                        "custom": {"const": true},
+                    },
+                    "required": ["custom"],
+                },
+            ],
+            "required": ["category", "type"],
+        })
+    }
+}
+
+/// the error object with the `code`, `reason`, `op`, `namespace`, `os_code`,
+/// `retry_after_ms` and `fields` of the handle
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use serde_json::json;
+///
+/// let schema = schemars::schema_for!(CursedErrorHandle);
+/// let validator = jsonschema::validator_for(schema.as_value()).unwrap();
+///
+/// let handles = [
+///     std::io::Error::from_raw_os_error(13).into(),
+///     CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Custom("rate limited")), "slow down".to_string())
+///         .with_op("fetch")
+///         .with_namespace("fetcher"),
+///     CursedErrorHandle::new(CursedError::Custom { category: "queue", kind: CursedErrorType::Refused }, String::new())
+///         .with_field("host", "db.local"),
+///     CursedErrorHandle::from(std::io::Error::from(std::io::ErrorKind::FileTooLarge)),
+///     CursedErrorHandle::new(CursedError::NoError, "fine".to_string()),
+///     CursedErrorHandle::from_http_status(429, "slow down".to_string(), Some("30")),
+/// ];
+/// for handle in handles {
+///     let json = serde_json::to_value(&handle).unwrap();
+///     assert!(validator.is_valid(&json), "{}", json);
+/// }
+///
+/// assert!(!validator.is_valid(&json!({"category": "file", "type": "not_found", "code": "FILE_NOT_FOUND"})));
+/// assert!(!validator.is_valid(&json!({"category": "file", "type": null, "code": "FILE", "reason": ""})));
+/// assert!(!validator.is_valid(&json!({"category": "queue", "type": "refused", "code": "", "reason": ""})));
+/// assert!(!validator.is_valid(&json!({"category": "call", "type": "not_enough", "code": "CALL_NOT_ENOUGH", "reason": "", "retry_after_ms": "30s"})));
+/// ```
+impl JsonSchema for CursedErrorHandle {
+    fn schema_name() -> Cow<'static, str> {
+        "CursedErrorHandle".into()
+    }
+    fn schema_id() -> Cow<'static, str> {
+        "curerr::CursedErrorHandle".into()
+    }
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "an error with its reason and details, sources are not included",
+            "type": "object",
+            "allOf": [generator.subschema_for::<CursedError>()],
+            "properties": {
+                "code": {"type": "string", "description": "machine readable code, e.g. `CONNECTION_REFUSED`"},
+                "reason": {"type": "string"},
+                "op": {"type": "string"},
+                "namespace": {"type": "string"},
+                "os_code": {"type": "integer", "format": "int32"},
+                "retry_after_ms": {"type": "integer", "format": "uint64", "minimum": 0},
+                "fields": {"type": "object", "additionalProperties": {"type": "string"}},
+            },
+            "required": ["code", "reason"],
+        })
+    }
+}
+
+fn category_ids() -> Vec<&'static str> {
+    TYPED_CATEGORIES.iter()
+        .chain(&[CursedCategory::NoError, CursedCategory::Unknown])
+        .map(CursedCategory::to_id)
+        .collect()
+}
@@ -0,0 +1,218 @@
+//! `serde` impls for the error kinds and handles
+
+use crate::*;
+use crate::handle::{Field, Inner};
+use crate::repr::{ErrorRepr, HandleRepr};
+use crate::convert::io_kind_from_name;
+use crate::kind::{intern_name, MAX_NAMES};
+
+use std::borrow::Cow;
+
+impl serde::Serialize for CursedCategory {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_id())
+    }
+}
+
+/// unrecognized ids become [`CursedCategory::Unknown`]
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// assert_eq!(serde_json::from_str::<CursedCategory>(r#""file""#).unwrap(), CursedCategory::File);
+/// assert_eq!(serde_json::from_str::<CursedCategory>(r#""quantum flux""#).unwrap(), CursedCategory::Unknown);
+/// ```
+impl<'de> serde::Deserialize<'de> for CursedCategory {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = Cow::<str>::deserialize(deserializer)?;
+        Ok(CursedCategory::from_id(&id).unwrap_or(CursedCategory::Unknown))
+    }
+}
+
+impl serde::Serialize for CursedErrorType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_id())
+    }
+}
+
+/// unrecognized ids become [`CursedErrorType::Custom`] types, new ones are refused once 256
+/// custom names are kept
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error_type = CursedErrorType::Custom("rate limited");
+/// let json = serde_json::to_string(&error_type).unwrap();
+///
+/// assert_eq!(json, r#""rate limited""#);
+/// assert_eq!(serde_json::from_str::<CursedErrorType>(&json).unwrap(), error_type);
+/// ```
+impl<'de> serde::Deserialize<'de> for CursedErrorType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = Cow::<str>::deserialize(deserializer)?;
+        CursedErrorType::from_id(&id).or_else(|| intern_name(&id).map(CursedErrorType::Custom)).ok_or_else(|| {
+            serde::de::Error::custom(format_args!("`{}` is a new custom type and {} names are already kept", id, MAX_NAMES))
+        })
+    }
+}
+
+impl ErrorRepr<'_> {
+    /// returns the error and, when it is unknown because of a category or type that is
+    /// unrecognized or can't be kept, a field with that name
+    fn into_error(self) -> (CursedError, Option<(&'static str, String)>) {
+        let category = match self.custom {
+            true => intern_name(&self.category).map(CursedCategory::Custom),
+            false => CursedCategory::from_id(&self.category),
+        };
+        let Some(category) = category else {
+            return (CursedError::Unknown(None), Some(("unknown_category", self.category.into_owned())))
+        };
+
+        let error = match (category, self.error_type.as_deref()) {
+            (CursedCategory::NoError, _) => CursedError::NoError,
+            (category, Some(id)) if category != CursedCategory::Unknown => {
+                match CursedErrorType::from_id(id).or_else(|| intern_name(id).map(CursedErrorType::Custom)) {
+                    Some(error_type) => category.with_type(error_type),
+                    None => return (CursedError::Unknown(None), Some(("unknown_type", id.to_string()))),
+                }
+            },
+            _ => CursedError::Unknown(self.io_kind.as_deref().and_then(io_kind_from_name)),
+        };
+        (error, None)
+    }
+}
+
+/// serialized as `{"category": "file", "type": "not_found"}`, custom categories
+/// additionally have `"custom": true` and unknown errors keep a converted
+/// [`std::io::ErrorKind`] in `"io_kind"`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedError::Custom { category: "queue", kind: CursedErrorType::Refused };
+/// let json = serde_json::to_string(&error).unwrap();
+///
+/// assert_eq!(json, r#"{"category":"queue","type":"refused","custom":true}"#);
+/// assert_eq!(serde_json::from_str::<CursedError>(&json).unwrap(), error);
+///
+/// let future = r#"{"category":"quantum flux","type":"refused"}"#;
+/// assert_eq!(serde_json::from_str::<CursedError>(future).unwrap(), CursedError::Unknown(None));
+///
+/// let error = CursedError::from(std::io::ErrorKind::FileTooLarge);
+/// let json = serde_json::to_string(&error).unwrap();
+///
+/// assert_eq!(json, r#"{"category":"unknown","type":null,"io_kind":"FileTooLarge"}"#);
+/// assert_eq!(serde_json::from_str::<CursedError>(&json).unwrap(), error);
+///
+/// let future = r#"{"category":"file","type":"quantum flux error"}"#;
+/// assert_eq!(
+///     serde_json::from_str::<CursedError>(future).unwrap(),
+///     CursedError::File(CursedErrorType::Custom("quantum flux error"))
+/// );
+/// ```
+impl serde::Serialize for CursedError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorRepr::new(self).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CursedError {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(ErrorRepr::deserialize(deserializer)?.into_error().0)
+    }
+}
+
+/// always serialized as `"[REDACTED]"`
+impl<T> serde::Serialize for Sensitive<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("[REDACTED]")
+    }
+}
+
+/// serialized as `{"category": "file", "type": "not_found", "code": "FILE_NOT_FOUND",
+/// "reason": "...", "os_code": 2, "retry_after_ms": 500, "fields": {"key": "value"}}`, sources are
+/// not serialized but their [retry hint](CursedErrorHandle::retry_after) is, sensitive fields are
+/// always redacted and the code is ignored when deserializing
+///
+/// Fields keep the order they were attached in, [`CursedErrorHandle::canonical_json`] is the
+/// same JSON with every key sorted and duplicate fields dropped.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error: CursedErrorHandle = std::io::Error::from_raw_os_error(13).into();
+/// let json = serde_json::to_value(&error).unwrap();
+///
+/// assert_eq!(json["category"], "io");
+/// assert_eq!(json["type"], "access_denied");
+/// assert_eq!(json["code"], "IO_ACCESS_DENIED");
+/// assert_eq!(json["os_code"], 13);
+/// assert!(json.get("op").is_none());
+///
+/// let error: CursedErrorHandle = serde_json::from_value(json).unwrap();
+///
+/// assert_eq!(error.get_error(), &CursedError::Io(CursedErrorType::AccessDenied));
+/// assert_eq!(error.os_code(), Some(13));
+///
+/// reveal_sensitive(true);
+/// let error = error.with_field("user", "admin").with_field_sensitive("password", "hunter2");
+/// let json = serde_json::to_value(&error).unwrap();
+/// reveal_sensitive(false);
+///
+/// assert_eq!(json["fields"], serde_json::json!({"user": "admin", "password": "[REDACTED]"}));
+/// assert_eq!(serde_json::to_string(&Sensitive("hunter2")).unwrap(), "\"[REDACTED]\"");
+///
+/// let future = r#"{"category":"quantum flux","type":"collapsed","reason":"cat is both"}"#;
+/// let error: CursedErrorHandle = serde_json::from_str(future).unwrap();
+///
+/// assert_eq!(error.get_error(), &CursedError::Unknown(None));
+/// assert_eq!(error.get_reason(), "cat is both");
+/// assert_eq!(error.fields().collect::<Vec<_>>(), [("unknown_category", "quantum flux".into())]);
+///
+/// let json = serde_json::to_value(error.with_op("load_config")).unwrap();
+/// assert_eq!(json["op"], "load_config");
+/// assert_eq!(serde_json::from_value::<CursedErrorHandle>(json).unwrap().op(), Some("load_config"));
+///
+/// let json = serde_json::to_value(CursedErrorHandle::new(CursedError::NoError, String::new()).with_namespace("cache")).unwrap();
+/// assert_eq!(json["namespace"], "cache");
+/// assert_eq!(serde_json::from_value::<CursedErrorHandle>(json).unwrap().namespace(), Some("cache"));
+///
+/// let error = CursedErrorHandle::from_http_status(429, "slow down".to_string(), Some("2"));
+/// let json = serde_json::to_value(error).unwrap();
+/// assert_eq!(json["retry_after_ms"], 2_000);
+/// assert_eq!(
+///     serde_json::from_value::<CursedErrorHandle>(json).unwrap().retry_after(),
+///     Some(std::time::Duration::from_secs(2))
+/// );
+/// ```
+impl serde::Serialize for CursedErrorHandle {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        HandleRepr::new(self).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CursedErrorHandle {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = HandleRepr::deserialize(deserializer)?;
+        let (error, unknown) = repr.error.into_error();
+
+        let mut inner = Inner::new(error, repr.reason.into_owned());
+        inner.os_code = repr.os_code;
+        inner.retry_after = repr.retry_after_ms.map(std::time::Duration::from_millis);
+        // after the `reason_len` field of a cut reason, which replaces the one of a reason
+        // cut again by a smaller cap
+        let cut = inner.fields.iter().any(|(key, _)| key == "reason_len");
+        inner.fields.extend(repr.fields.into_iter()
+            .filter(|(key, _)| !cut || key != "reason_len")
+            .map(|(key, value)| (Cow::Owned(key.into_owned()), Field::Plain(value.into_owned()))));
+        inner.set_read_names(repr.op.as_deref(), repr.namespace.as_deref());
+        if let Some((key, name)) = unknown {
+            inner.fields.push((key.into(), Field::Plain(name)));
+        }
+        Ok(CursedErrorHandle::from_inner(inner))
+    }
+}
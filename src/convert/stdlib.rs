@@ -0,0 +1,266 @@
+//! `From` impls for std error types, behind the `std-conversions` feature
+
+use crate::*;
+use crate::convert::{parse_int_kind, wrap_foreign};
+use crate::handle::Inner;
+use std::io::ErrorKind;
+
+/// boxed errors are classified by [`CursedError::classify`] and kept as the source,
+/// boxed handles are unwrapped
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::error::Error;
+///
+/// let error: Box<dyn Error + Send + Sync> = "x".parse::<u8>().unwrap_err().into();
+/// let error = CursedErrorHandle::from(error);
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert_eq!(error.get_reason(), "invalid digit found in string");
+/// assert!(error.source().unwrap().is::<std::num::ParseIntError>());
+///
+/// let handle = CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), "a.txt".to_string());
+/// let error: Box<dyn Error + Send + Sync> = handle.into();
+///
+/// assert_eq!(CursedErrorHandle::from(error).get_reason(), "a.txt");
+/// ```
+impl From<Box<dyn std::error::Error + Send + Sync>> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        match error.downcast::<CursedErrorHandle>() {
+            Ok(handle) => *handle,
+            Err(error) => {
+                let reason = error.to_string();
+                wrap_foreign(error, reason)
+            },
+        }
+    }
+}
+
+/// boxed errors are classified by [`CursedError::classify`], but can't be kept as the
+/// source since they may not be [`Send`]
+impl From<Box<dyn std::error::Error>> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: Box<dyn std::error::Error>) -> Self {
+        match error.downcast::<CursedErrorHandle>() {
+            Ok(handle) => *handle,
+            Err(error) => Self::new(CursedError::classify(&*error), error.to_string()),
+        }
+    }
+}
+
+/// parse failures of std types are data parse errors, the source message becomes the reason
+///
+/// Integers are classified by their [`std::num::IntErrorKind`] instead, like by
+/// [`CursedError::classify`]: values out of range of the type are `Data(Overflow)`,
+/// empty strings are `Input(NotEnough)` and anything else is `Data(Parse)`.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let errors: [CursedErrorHandle; 5] = [
+///     "x".parse::<i32>().unwrap_err().into(),
+///     "x".parse::<f64>().unwrap_err().into(),
+///     "x".parse::<bool>().unwrap_err().into(),
+///     "xy".parse::<char>().unwrap_err().into(),
+///     u8::try_from('\u{1F980}').unwrap_err().into(),
+/// ];
+///
+/// for error in errors {
+///     assert!(matches!(error.get_error(), CursedError::Data(CursedErrorType::Parse)));
+/// }
+///
+/// let error: CursedErrorHandle = "yes".parse::<bool>().unwrap_err().into();
+/// assert_eq!(error.get_reason(), "provided string was not `true` or `false`");
+///
+/// let cases = [
+///     ("70000", CursedError::Data(CursedErrorType::Overflow), "number too large to fit in target type"),
+///     ("-1", CursedError::Data(CursedErrorType::Parse), "invalid digit found in string"),
+///     ("", CursedError::Input(CursedErrorType::NotEnough), "cannot parse integer from empty string"),
+///     ("8o", CursedError::Data(CursedErrorType::Parse), "invalid digit found in string"),
+/// ];
+/// for (value, kind, reason) in cases {
+///     let error: CursedErrorHandle = value.parse::<u16>().unwrap_err().into();
+///     assert_eq!((error.get_error(), error.get_reason()), (&kind, reason), "{:?}", value);
+/// }
+///
+/// let error: CursedErrorHandle = "-129".parse::<i8>().unwrap_err().into();
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Overflow));
+/// assert_eq!(error.get_reason(), "number too small to fit in target type");
+///
+/// let error: CursedErrorHandle = "0".parse::<std::num::NonZeroU8>().unwrap_err().into();
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+///
+/// let error: Box<dyn std::error::Error> = "70000".parse::<u16>().unwrap_err().into();
+/// assert_eq!(CursedError::classify(&*error), CursedError::Data(CursedErrorType::Overflow));
+/// ```
+impl From<std::num::ParseIntError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: std::num::ParseIntError) -> Self {
+        Self::new(parse_int_kind(&error), error.to_string())
+    }
+}
+
+impl From<std::num::ParseFloatError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: std::num::ParseFloatError) -> Self {
+        Self::new(CursedError::Data(CursedErrorType::Parse), error.to_string())
+    }
+}
+
+impl From<std::str::ParseBoolError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: std::str::ParseBoolError) -> Self {
+        Self::new(CursedError::Data(CursedErrorType::Parse), error.to_string())
+    }
+}
+
+impl From<std::char::ParseCharError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: std::char::ParseCharError) -> Self {
+        Self::new(CursedError::Data(CursedErrorType::Parse), error.to_string())
+    }
+}
+
+impl From<std::char::TryFromCharError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: std::char::TryFromCharError) -> Self {
+        Self::new(CursedError::Data(CursedErrorType::Parse), error.to_string())
+    }
+}
+/// layout errors are `Memory(Invalid)`, the size or alignment is bad rather than the
+/// memory exhausted
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::alloc::Layout;
+///
+/// let error: CursedErrorHandle = Layout::array::<u64>(usize::MAX).unwrap_err().into();
+///
+/// assert_eq!(error.get_error(), &CursedError::Memory(CursedErrorType::Invalid));
+/// assert_eq!(error.get_reason(), "invalid parameters to Layout::from_size_align");
+///
+/// let error: CursedErrorHandle = Layout::from_size_align(8, 3).unwrap_err().into();
+/// assert_eq!(error.get_error(), &CursedError::Memory(CursedErrorType::Invalid));
+/// ```
+impl From<std::alloc::LayoutError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: std::alloc::LayoutError) -> Self {
+        Self::new(CursedError::Memory(CursedErrorType::Invalid), error.to_string())
+    }
+}
+
+/// io errors are classified by their [`ErrorKind`], the error message becomes the reason
+///
+/// The io error is kept as the source, [`CursedErrorHandle::find_source`] returns it with
+/// the same kind and OS code and [`CursedErrorHandle::into_io`] gives it back. Reports
+/// don't repeat its message as a cause.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error: CursedErrorHandle = std::fs::File::open("/definitely/not/here").unwrap_err().into();
+///
+/// assert_eq!(error.get_error(), &CursedError::Io(CursedErrorType::NotFound));
+/// # #[cfg(unix)]
+/// assert_eq!(error.to_string(), "io not found error: \"No such file or directory (os error 2)\"");
+///
+/// let io = error.find_source::<std::io::Error>().unwrap();
+/// assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+/// assert_eq!(io.raw_os_error(), error.os_code());
+/// assert!(!error.report().to_string().contains("caused by"));
+/// ```
+impl From<std::io::Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: std::io::Error) -> Self {
+        let mut inner = Inner::new(CursedError::from(error.kind()), error.to_string());
+        inner.os_code = error.raw_os_error();
+        inner.source = Some(std::sync::Arc::new(error));
+        Self::from_inner(inner)
+    }
+}
+
+/// converts back into an io error
+///
+/// Handles converted from an io error and carrying nothing beyond it give back the
+/// original, or an io error of the same OS code, so the exact code and kind survive the
+/// round trip, see [`CursedErrorHandle::into_io`]. The others get the kind picked by the
+/// reverse [`ErrorKind`] mapping with the handle as the inner error, so their contexts
+/// and fields are kept.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error: CursedErrorHandle = std::io::Error::from_raw_os_error(13).into();
+/// let error: std::io::Error = error.into();
+///
+/// assert_eq!(error.raw_os_error(), Some(13));
+///
+/// let error = CursedErrorHandle::new(
+///     CursedError::File(CursedErrorType::NotFound),
+///     "config.toml".to_string()
+/// );
+/// let error: std::io::Error = error.into();
+///
+/// assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+/// assert_eq!(error.to_string(), "file not found error: \"config.toml\"");
+/// ```
+impl From<CursedErrorHandle> for std::io::Error {
+    fn from(error: CursedErrorHandle) -> Self {
+        error.into_io().unwrap_or_else(|error| std::io::Error::new(ErrorKind::from(*error.get_error()), error))
+    }
+}
+
+/// exits with [`CursedErrorHandle::exit_code`]
+impl From<CursedErrorHandle> for std::process::ExitCode {
+    fn from(error: CursedErrorHandle) -> Self {
+        std::process::ExitCode::from(error.exit_code())
+    }
+}
+
+/// failed [`std::io::BufWriter::into_inner`] calls are classified by the inner io error
+///
+/// The recovered writer is dropped together with the data still sitting in its buffer,
+/// so the reason notes that buffered data may not have been flushed.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::io::{BufWriter, Write};
+///
+/// #[derive(Debug)]
+/// struct Broken;
+///
+/// impl Write for Broken {
+///     fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+///         Err(std::io::ErrorKind::TimedOut.into())
+///     }
+///     fn flush(&mut self) -> std::io::Result<()> {
+///         Err(std::io::ErrorKind::TimedOut.into())
+///     }
+/// }
+///
+/// let mut writer = BufWriter::new(Broken);
+/// writer.write_all(b"data").unwrap();
+///
+/// let error: CursedErrorHandle = writer.into_inner().unwrap_err().into();
+///
+/// assert!(matches!(error.get_error(), CursedError::Call(CursedErrorType::Timedout)));
+/// assert!(error.get_reason().ends_with("buffered data may not have been flushed"));
+/// ```
+impl<W> From<std::io::IntoInnerError<W>> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: std::io::IntoInnerError<W>) -> Self {
+        let error = error.into_error();
+
+        Self::new(
+            CursedError::from(error.kind()),
+            format!("{}, buffered data may not have been flushed", error)
+        )
+    }
+}
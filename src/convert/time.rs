@@ -0,0 +1,76 @@
+//! conversions of `time` errors
+
+use crate::*;
+use crate::convert::Classification;
+
+/// parse errors by their inner error and the component and format errors, listed by
+/// [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("time::error::Parse(ComponentRange)", CursedCategory::Data, CursedErrorType::Invalid),
+    ("time::error::Parse(ParseFromDescription)", CursedCategory::Data, CursedErrorType::Parse),
+    ("time::error::ComponentRange", CursedCategory::Data, CursedErrorType::Invalid),
+    ("time::error::Format", CursedCategory::Other, CursedErrorType::Invalid),
+];
+
+/// out of range components become `Data(Invalid)`, other failures `Data(Parse)`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use time::macros::format_description;
+///
+/// let format = format_description!("[year]-[month]-[day]");
+///
+/// let error = CursedErrorHandle::from(time::Date::parse("2024-xx-01", &format).unwrap_err());
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+///
+/// let error = CursedErrorHandle::from(time::Date::parse("2024-02-30", &format).unwrap_err());
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+/// ```
+impl From<time::error::Parse> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: time::error::Parse) -> Self {
+        let kind = match error {
+            time::error::Parse::TryFromParsed(time::error::TryFromParsed::ComponentRange(_)) => {
+                CursedError::Data(CursedErrorType::Invalid)
+            },
+            _ => CursedError::Data(CursedErrorType::Parse),
+        };
+
+        Self::new(kind, error.to_string())
+    }
+}
+
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedErrorHandle::from(
+///     time::Date::from_calendar_date(2024, time::Month::February, 30).unwrap_err()
+/// );
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+/// assert_eq!(error.get_reason(), "day was not in range");
+/// ```
+impl From<time::error::ComponentRange> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: time::error::ComponentRange) -> Self {
+        Self::new(CursedError::Data(CursedErrorType::Invalid), error.to_string())
+    }
+}
+
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use time::macros::{date, format_description};
+///
+/// let error = CursedErrorHandle::from(date!(2024-01-01).format(format_description!("[hour]")).unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Other(CursedErrorType::Invalid));
+/// ```
+impl From<time::error::Format> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: time::error::Format) -> Self {
+        Self::new(CursedError::Other(CursedErrorType::Invalid), error.to_string())
+    }
+}
@@ -0,0 +1,107 @@
+//! conversions of `tokio` errors
+
+use crate::*;
+use crate::convert::Classification;
+use crate::deferred::panic_message;
+
+/// join errors by outcome and the timer and semaphore errors, listed by
+/// [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("tokio::task::JoinError(Cancelled)", CursedCategory::Call, CursedErrorType::Aborted),
+    ("tokio::task::JoinError(Panic)", CursedCategory::Call, CursedErrorType::Custom("panicked")),
+    ("tokio::time::error::Elapsed", CursedCategory::Call, CursedErrorType::Timedout),
+    ("tokio::sync::AcquireError", CursedCategory::Connection, CursedErrorType::Aborted),
+];
+
+/// cancelled tasks become `Call(Aborted)`, panicked ones `Call(panicked)` with the panic
+/// message when it is a string
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// async fn run() -> CursedResult<u32> {
+///     let task = tokio::spawn(async { CursedResult::Ok(4) });
+///     Ok(task.await??)
+/// }
+/// assert_eq!(run().await.unwrap(), 4);
+///
+/// let task = tokio::spawn(std::future::pending::<()>());
+/// task.abort();
+/// let error = CursedErrorHandle::from(task.await.unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Aborted));
+/// assert_eq!(error.get_reason(), "task was cancelled");
+///
+/// let task = tokio::spawn(async { panic!("worker {} crashed", 3) });
+/// let error = CursedErrorHandle::from(task.await.unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Custom("panicked")));
+/// assert_eq!(error.get_reason(), "task panicked: worker 3 crashed");
+/// # });
+/// ```
+impl From<tokio::task::JoinError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: tokio::task::JoinError) -> Self {
+        let payload = match error.try_into_panic() {
+            Ok(payload) => payload,
+            Err(_) => return Self::new(
+                CursedError::Call(CursedErrorType::Aborted),
+                "task was cancelled".to_string()
+            ),
+        };
+
+        let reason = match panic_message(&*payload) {
+            Some(message) => format!("task panicked: {}", message),
+            None => "task panicked".to_string(),
+        };
+
+        Self::new(CursedError::Call(CursedErrorType::Custom("panicked")), reason)
+    }
+}
+
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::time::Duration;
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// async fn run() -> CursedResult<()> {
+///     tokio::time::timeout(Duration::from_millis(1), std::future::pending::<()>()).await?;
+///     Ok(())
+/// }
+/// let error = run().await.unwrap_err();
+///
+/// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Timedout));
+/// assert_eq!(error.get_reason(), "deadline has elapsed");
+/// # });
+/// ```
+impl From<tokio::time::error::Elapsed> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: tokio::time::error::Elapsed) -> Self {
+        Self::new(CursedError::Call(CursedErrorType::Timedout), error.to_string())
+    }
+}
+
+/// acquiring from a closed semaphore becomes `Connection(Aborted)`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let semaphore = tokio::sync::Semaphore::new(1);
+/// semaphore.close();
+/// let error = CursedErrorHandle::from(semaphore.acquire().await.unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Aborted));
+/// assert_eq!(error.get_reason(), "semaphore closed");
+/// # });
+/// ```
+impl From<tokio::sync::AcquireError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: tokio::sync::AcquireError) -> Self {
+        Self::new(CursedError::Connection(CursedErrorType::Aborted), error.to_string())
+    }
+}
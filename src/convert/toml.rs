@@ -0,0 +1,77 @@
+//! conversions of `toml` errors
+
+use crate::*;
+use crate::convert::{is_semantic_error, Classification};
+
+/// deserialize errors by shape and the serialize error, listed by [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("toml::de::Error(syntax)", CursedCategory::Data, CursedErrorType::Parse),
+    ("toml::de::Error(invalid type)", CursedCategory::Data, CursedErrorType::Invalid),
+    ("toml::ser::Error", CursedCategory::Data, CursedErrorType::Invalid),
+];
+
+/// syntax errors become `Data(Parse)` and documents of the wrong shape `Data(Invalid)`,
+/// the reason keeps the line and column and the error is kept as the source
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::error::Error;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Config {
+///     #[allow(dead_code)]
+///     port: u16,
+/// }
+///
+/// let error = CursedErrorHandle::from(toml::from_str::<Config>("name = \"app\"\nport = = 80").unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert!(error.get_reason().starts_with("TOML parse error at line 2, column 8: "));
+/// assert!(error.source().unwrap().is::<toml::de::Error>());
+///
+/// let error = CursedErrorHandle::from(toml::from_str::<Config>("\nport = \"http\"").unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+/// assert_eq!(
+///     error.get_reason(),
+///     "TOML parse error at line 2, column 8: invalid type: string \"http\", expected u16"
+/// );
+/// ```
+impl From<toml::de::Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: toml::de::Error) -> Self {
+        let kind = match is_semantic_error(error.message()) {
+            true => CursedError::Data(CursedErrorType::Invalid),
+            false => CursedError::Data(CursedErrorType::Parse),
+        };
+
+        // the display form continues with a snippet of the document, only the location is kept
+        let display = error.to_string();
+        let reason = match display.lines().next() {
+            Some(location) if location.starts_with("TOML parse error at") => {
+                format!("{}: {}", location, error.message().trim_end())
+            },
+            _ => display.trim_end().to_string(),
+        };
+
+        Self::new(kind, reason).with_source(error)
+    }
+}
+
+/// values that can't be represented in TOML become `Data(Invalid)`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedErrorHandle::from(toml::to_string(&Some(1)).unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+/// ```
+impl From<toml::ser::Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: toml::ser::Error) -> Self {
+        Self::new(CursedError::Data(CursedErrorType::Invalid), error.to_string()).with_source(error)
+    }
+}
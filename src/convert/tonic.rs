@@ -0,0 +1,185 @@
+//! conversions between handles and `tonic` statuses
+
+use crate::*;
+
+use tonic::Code;
+
+/// metadata key carrying the [code](CursedErrorHandle::code) of the error
+const CODE_KEY: &str = "curerr-code";
+
+/// the kind of every code with one, as read by [`CursedErrorHandle::from_status`], listed
+/// by [`crate::compat::classifications`]
+pub(crate) fn classifications() -> impl Iterator<Item = (std::borrow::Cow<'static, str>, CursedCategory, CursedErrorType)> {
+    (0..=16).map(Code::from).filter_map(|code| {
+        let error = CursedError::from_grpc_code(code);
+        Some((format!("tonic::Status({:?})", code).into(), error.category(), error.get_type()?))
+    })
+}
+
+impl CursedError {
+    /// gRPC status code of the error
+    ///
+    /// | error | code |
+    /// |-|-|
+    /// | `NoError` | `Ok` |
+    /// | `Unknown` | `Unknown` |
+    /// | any `NotFound` | `NotFound` |
+    /// | any `AccessDenied` | `PermissionDenied` |
+    /// | any `Timedout` | `DeadlineExceeded` |
+    /// | any `AlreadyExists` | `AlreadyExists` |
+    /// | any `Refused`, `Reset` or `Interrupted` | `Unavailable` |
+    /// | any `Aborted` | `Aborted` |
+    /// | any `NotImplemented` or `NotSupported` | `Unimplemented` |
+    /// | other `Input` errors | `InvalidArgument` |
+    /// | other `NotEnough` errors | `ResourceExhausted` |
+    /// | everything else | `Internal` |
+    pub fn grpc_code(&self) -> Code {
+        match (self, self.get_type()) {
+            (CursedError::NoError, _) => Code::Ok,
+            (CursedError::Unknown(_), _) => Code::Unknown,
+            (_, Some(CursedErrorType::NotFound)) => Code::NotFound,
+            (_, Some(CursedErrorType::AccessDenied)) => Code::PermissionDenied,
+            (_, Some(CursedErrorType::Timedout)) => Code::DeadlineExceeded,
+            (_, Some(CursedErrorType::AlreadyExists)) => Code::AlreadyExists,
+            (_, Some(CursedErrorType::Refused | CursedErrorType::Reset | CursedErrorType::Interrupted)) => Code::Unavailable,
+            (_, Some(CursedErrorType::Aborted)) => Code::Aborted,
+            (_, Some(CursedErrorType::NotImplemented | CursedErrorType::NotSupported)) => Code::Unimplemented,
+            (CursedError::Input(_), _) => Code::InvalidArgument,
+            (_, Some(CursedErrorType::NotEnough)) => Code::ResourceExhausted,
+            _ => Code::Internal,
+        }
+    }
+    /// closest error for a gRPC status code, the codes without a counterpart
+    /// become [`CursedError::Unknown`]
+    ///
+    /// Missing, denied, existing and exhausted resources get the `Io` category like the
+    /// converted io errors, e.g. `NotFound` becomes `Io(NotFound)`.
+    pub fn from_grpc_code(code: Code) -> CursedError {
+        match code {
+            Code::Ok => CursedError::NoError,
+            Code::NotFound => CursedError::Io(CursedErrorType::NotFound),
+            Code::PermissionDenied | Code::Unauthenticated => CursedError::Io(CursedErrorType::AccessDenied),
+            Code::DeadlineExceeded => CursedError::Call(CursedErrorType::Timedout),
+            Code::AlreadyExists => CursedError::Io(CursedErrorType::AlreadyExists),
+            Code::Unavailable => CursedError::Connection(CursedErrorType::Refused),
+            Code::Aborted | Code::Cancelled => CursedError::Call(CursedErrorType::Aborted),
+            Code::Unimplemented => CursedError::Call(CursedErrorType::NotImplemented),
+            Code::InvalidArgument => CursedError::Input(CursedErrorType::Invalid),
+            Code::OutOfRange => CursedError::Input(CursedErrorType::Overflow),
+            Code::ResourceExhausted => CursedError::Io(CursedErrorType::NotEnough),
+            Code::Unknown | Code::Internal | Code::DataLoss | Code::FailedPrecondition => CursedError::Unknown(None),
+        }
+    }
+}
+
+impl CursedErrorHandle {
+    /// error for a status received from a remote service
+    ///
+    /// The kind is restored from the `curerr-code` metadata when the status was created
+    /// from a handle, otherwise it is picked by [`CursedError::from_grpc_code`].
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let status = tonic::Status::already_exists("user exists");
+    /// let error = CursedErrorHandle::from_status(&status);
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Io(CursedErrorType::AlreadyExists));
+    /// assert_eq!(error.get_reason(), "user exists");
+    ///
+    /// let status = tonic::Status::from(CursedErrorHandle::new(
+    ///     CursedError::File(CursedErrorType::NotFound),
+    ///     "avatar.png".to_string()
+    /// ));
+    /// let error = CursedErrorHandle::from_status(&status);
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::File(CursedErrorType::NotFound));
+    /// assert_eq!(error.get_reason(), "avatar.png");
+    /// ```
+    #[track_caller]
+    pub fn from_status(status: &tonic::Status) -> Self {
+        let error = status.metadata()
+            .get(CODE_KEY)
+            .and_then(|code| code.to_str().ok())
+            .map(CursedError::from_code)
+            .filter(|error| !matches!(error, CursedError::Unknown(_)))
+            .unwrap_or_else(|| CursedError::from_grpc_code(status.code()));
+
+        Self::new(error, status.message().to_string())
+    }
+}
+
+/// uses [`CursedError::grpc_code`] and the public message, server errors without one
+/// get the generic description of the code
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use tonic::Code;
+///
+/// let cases = [
+///     (CursedError::NoError, Code::Ok),
+///     (CursedError::Unknown(None), Code::Unknown),
+///     (CursedError::File(CursedErrorType::NotFound), Code::NotFound),
+///     (CursedError::Other(CursedErrorType::AccessDenied), Code::PermissionDenied),
+///     (CursedError::Call(CursedErrorType::Timedout), Code::DeadlineExceeded),
+///     (CursedError::Other(CursedErrorType::AlreadyExists), Code::AlreadyExists),
+///     (CursedError::Connection(CursedErrorType::Refused), Code::Unavailable),
+///     (CursedError::Connection(CursedErrorType::Reset), Code::Unavailable),
+///     (CursedError::Other(CursedErrorType::Interrupted), Code::Unavailable),
+///     (CursedError::Call(CursedErrorType::Aborted), Code::Aborted),
+///     (CursedError::Call(CursedErrorType::NotImplemented), Code::Unimplemented),
+///     (CursedError::Other(CursedErrorType::NotSupported), Code::Unimplemented),
+///     (CursedError::Input(CursedErrorType::Invalid), Code::InvalidArgument),
+///     (CursedError::Input(CursedErrorType::NotEnough), Code::InvalidArgument),
+///     (CursedError::Memory(CursedErrorType::NotEnough), Code::ResourceExhausted),
+///     (CursedError::Data(CursedErrorType::Invalid), Code::Internal),
+/// ];
+/// for (error, code) in cases {
+///     assert_eq!(error.grpc_code(), code, "{}", error);
+/// }
+///
+/// let cases = [
+///     (Code::Ok, CursedError::NoError),
+///     (Code::Cancelled, CursedError::Call(CursedErrorType::Aborted)),
+///     (Code::Unknown, CursedError::Unknown(None)),
+///     (Code::InvalidArgument, CursedError::Input(CursedErrorType::Invalid)),
+///     (Code::DeadlineExceeded, CursedError::Call(CursedErrorType::Timedout)),
+///     (Code::NotFound, CursedError::Io(CursedErrorType::NotFound)),
+///     (Code::AlreadyExists, CursedError::Io(CursedErrorType::AlreadyExists)),
+///     (Code::PermissionDenied, CursedError::Io(CursedErrorType::AccessDenied)),
+///     (Code::ResourceExhausted, CursedError::Io(CursedErrorType::NotEnough)),
+///     (Code::FailedPrecondition, CursedError::Unknown(None)),
+///     (Code::Aborted, CursedError::Call(CursedErrorType::Aborted)),
+///     (Code::OutOfRange, CursedError::Input(CursedErrorType::Overflow)),
+///     (Code::Unimplemented, CursedError::Call(CursedErrorType::NotImplemented)),
+///     (Code::Internal, CursedError::Unknown(None)),
+///     (Code::Unavailable, CursedError::Connection(CursedErrorType::Refused)),
+///     (Code::DataLoss, CursedError::Unknown(None)),
+///     (Code::Unauthenticated, CursedError::Io(CursedErrorType::AccessDenied)),
+/// ];
+/// for (code, error) in cases {
+///     assert_eq!(CursedError::from_grpc_code(code), error, "{:?}", code);
+/// }
+///
+/// let status = tonic::Status::from(CursedErrorHandle::new(
+///     CursedError::Connection(CursedErrorType::Refused),
+///     "postgres at 10.0.0.3:5432 refused".to_string()
+/// ));
+///
+/// assert_eq!(status.code(), Code::Unavailable);
+/// assert_eq!(status.message(), "The service is currently unavailable");
+/// assert_eq!(status.metadata().get("curerr-code").unwrap(), "CONNECTION_REFUSED");
+/// ```
+impl From<CursedErrorHandle> for tonic::Status {
+    fn from(error: CursedErrorHandle) -> Self {
+        let code = error.get_error().grpc_code();
+        let mut status = tonic::Status::new(code, error.safe_message(code.description()));
+
+        if let Ok(value) = error.code().parse() {
+            status.metadata_mut().insert(CODE_KEY, value);
+        }
+        status
+    }
+}
@@ -0,0 +1,86 @@
+//! conversions of `url` parse errors
+
+use crate::*;
+use crate::convert::Classification;
+
+/// a parse error of each kind [`url_error_kind`] picks, listed by [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("url::ParseError(InvalidPort)", CursedCategory::Address, CursedErrorType::Invalid),
+    ("url::ParseError(RelativeUrlWithoutBase)", CursedCategory::Input, CursedErrorType::Invalid),
+    ("url::ParseError(InvalidDomainCharacter)", CursedCategory::Data, CursedErrorType::Parse),
+];
+
+fn url_error_kind(error: url::ParseError) -> CursedError {
+    match error {
+        url::ParseError::EmptyHost
+        | url::ParseError::InvalidIpv4Address
+        | url::ParseError::InvalidIpv6Address
+        | url::ParseError::InvalidPort => CursedError::Address(CursedErrorType::Invalid),
+        url::ParseError::RelativeUrlWithoutBase
+        | url::ParseError::RelativeUrlWithCannotBeABaseBase
+        | url::ParseError::SetHostOnCannotBeABaseUrl => CursedError::Input(CursedErrorType::Invalid),
+        _ => CursedError::Data(CursedErrorType::Parse),
+    }
+}
+
+impl CursedErrorHandle {
+    /// same as the conversion from [`url::ParseError`], but the reason also contains
+    /// the offending input, cut after 64 characters
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let input = "https://example.com:99999/";
+    /// let error = CursedErrorHandle::bad_url(input, url::Url::parse(input).unwrap_err());
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Address(CursedErrorType::Invalid));
+    /// assert_eq!(error.get_reason(), "invalid URL `https://example.com:99999/`: invalid port number");
+    ///
+    /// let input = format!("http://[{}", "1".repeat(100));
+    /// let error = CursedErrorHandle::bad_url(&input, url::Url::parse(&input).unwrap_err());
+    ///
+    /// assert_eq!(error.get_reason(), format!("invalid URL `http://[{}...`: invalid IPv6 address", "1".repeat(56)));
+    /// ```
+    #[track_caller]
+    pub fn bad_url(input: &str, error: url::ParseError) -> Self {
+        const MAX_INPUT: usize = 64;
+
+        let reason = match input.char_indices().nth(MAX_INPUT) {
+            Some((end, _)) => format!("invalid URL `{}...`: {}", &input[..end], error),
+            None => format!("invalid URL `{}`: {}", input, error),
+        };
+
+        Self::new(url_error_kind(error), reason)
+    }
+}
+
+/// bad hosts and ports become `Address(Invalid)`, relative URLs without a usable base
+/// `Input(Invalid)` and everything else `Data(Parse)`
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let cases = [
+///     ("http://", CursedError::Address(CursedErrorType::Invalid)),
+///     ("http://999.1.1.1/", CursedError::Address(CursedErrorType::Invalid)),
+///     ("http://[::1/", CursedError::Address(CursedErrorType::Invalid)),
+///     ("/relative/path", CursedError::Input(CursedErrorType::Invalid)),
+///     ("http://exa mple.com/", CursedError::Data(CursedErrorType::Parse)),
+/// ];
+///
+/// for (input, expected) in cases {
+///     let error = CursedErrorHandle::from(url::Url::parse(input).unwrap_err());
+///     assert_eq!(error.get_error(), &expected, "{}", input);
+/// }
+///
+/// let error = CursedErrorHandle::from(url::Url::parse("/relative").unwrap_err());
+/// assert_eq!(error.get_reason(), "relative URL without a base");
+/// ```
+impl From<url::ParseError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: url::ParseError) -> Self {
+        Self::new(url_error_kind(error), error.to_string())
+    }
+}
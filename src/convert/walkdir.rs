@@ -0,0 +1,119 @@
+//! conversions of `walkdir` errors
+
+use crate::*;
+use crate::convert::{file_error, Classification};
+use crate::handle::{Field, Inner};
+
+/// a missing root and a symlink loop, listed by [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("walkdir::Error(NotFound)", CursedCategory::File, CursedErrorType::NotFound),
+    ("walkdir::Error(loop)", CursedCategory::Path, CursedErrorType::Invalid),
+];
+
+/// io errors are classified by the [`std::io::ErrorKind`] mapping with the `File`
+/// category instead of `Io`, like [`CursedErrorHandle::open_err`], and symlink loops
+/// become `Path(Invalid)`, the error is kept as the source
+///
+/// The path of the failed entry, when there is one, is kept as
+/// [`CursedErrorHandle::path`], the depth of the entry below the
+/// root as the `depth` field and for loops the ancestor the link points to as the
+/// `loop_ancestor` field.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = walkdir::WalkDir::new("/definitely/not/here").into_iter().next().unwrap().unwrap_err();
+/// let error = CursedErrorHandle::from(error);
+///
+/// assert_eq!(error.get_error(), &CursedError::File(CursedErrorType::NotFound));
+/// assert!(error.get_reason().starts_with("IO error for operation on /definitely/not/here: "));
+/// assert_eq!(error.path(), Some(std::path::Path::new("/definitely/not/here")));
+/// assert_eq!(error.fields().collect::<Vec<_>>(), [("depth", "0".into())]);
+/// assert!(std::error::Error::source(&error).unwrap().is::<walkdir::Error>());
+/// # #[cfg(unix)]
+/// assert_eq!(error.os_code(), Some(2));
+/// ```
+///
+/// A link to an ancestor is a loop when links are followed.
+///
+/// ```
+/// # #[cfg(unix)] {
+/// use curerr::*;
+///
+/// let root = std::env::temp_dir().join(format!("curerr-walkdir-loop-{}", std::process::id()));
+/// std::fs::create_dir_all(root.join("a")).unwrap();
+/// std::os::unix::fs::symlink(&root, root.join("a/up")).unwrap();
+///
+/// let errors: Vec<_> = walkdir::WalkDir::new(&root)
+///     .follow_links(true)
+///     .into_iter()
+///     .filter_map(Result::err)
+///     .map(CursedErrorHandle::from)
+///     .collect();
+/// std::fs::remove_dir_all(&root).unwrap();
+///
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].get_error(), &CursedError::Path(CursedErrorType::Invalid));
+/// assert_eq!(errors[0].path(), Some(root.join("a/up").as_path()));
+/// assert_eq!(errors[0].fields().collect::<Vec<_>>(), [
+///     ("depth", "2".into()),
+///     ("loop_ancestor", root.display().to_string().into()),
+/// ]);
+/// # }
+/// ```
+///
+/// Reading a directory without permission fails while walking. Root can read it anyway,
+/// the example checks nothing then and says so.
+///
+/// ```
+/// # #[cfg(unix)] {
+/// use curerr::*;
+/// use std::os::unix::fs::PermissionsExt;
+///
+/// let root = std::env::temp_dir().join(format!("curerr-walkdir-denied-{}", std::process::id()));
+/// std::fs::create_dir_all(root.join("locked/inner")).unwrap();
+/// std::fs::set_permissions(root.join("locked"), std::fs::Permissions::from_mode(0o000)).unwrap();
+/// let readable = std::fs::read_dir(root.join("locked")).is_ok();
+///
+/// let errors: Vec<_> = walkdir::WalkDir::new(&root)
+///     .into_iter()
+///     .filter_map(Result::err)
+///     .map(CursedErrorHandle::from)
+///     .collect();
+///
+/// std::fs::set_permissions(root.join("locked"), std::fs::Permissions::from_mode(0o755)).unwrap();
+/// std::fs::remove_dir_all(&root).unwrap();
+///
+/// if readable {
+///     println!("skipped: the locked directory is readable, e.g. by root");
+/// } else {
+///     assert_eq!(errors.len(), 1);
+///     assert_eq!(errors[0].get_error(), &CursedError::File(CursedErrorType::AccessDenied));
+///     assert_eq!(errors[0].path(), Some(root.join("locked").as_path()));
+///     assert_eq!(errors[0].fields().collect::<Vec<_>>(), [("depth", "1".into())]);
+///     assert_eq!(errors[0].os_code(), Some(13));
+/// }
+/// # }
+/// ```
+impl From<walkdir::Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: walkdir::Error) -> Self {
+        let kind = match (error.loop_ancestor(), error.io_error()) {
+            (Some(_), _) => CursedError::Path(CursedErrorType::Invalid),
+            (None, Some(io)) => file_error(io.kind().into()),
+            (None, None) => CursedError::Unknown(None),
+        };
+
+        let mut inner = Inner::new(kind, error.to_string());
+        inner.path = error.path().map(std::path::Path::to_path_buf);
+        inner.fields.push(("depth".into(), Field::Plain(error.depth().to_string())));
+        if let Some(ancestor) = error.loop_ancestor() {
+            inner.fields.push(("loop_ancestor".into(), Field::Plain(ancestor.to_string_lossy().into_owned())));
+        }
+        inner.os_code = error.io_error().and_then(std::io::Error::raw_os_error);
+        inner.source = Some(std::sync::Arc::new(error));
+
+        Self::from_inner(inner)
+    }
+}
@@ -0,0 +1,48 @@
+//! conversions of `serde_yaml` errors
+
+use crate::*;
+use crate::convert::{is_semantic_error, Classification};
+
+/// syntax and shape errors, listed by [`crate::compat::classifications`]
+pub(crate) const CLASSIFICATIONS: &[Classification] = &[
+    ("serde_yaml::Error(syntax)", CursedCategory::Data, CursedErrorType::Parse),
+    ("serde_yaml::Error(invalid type)", CursedCategory::Data, CursedErrorType::Invalid),
+];
+
+/// syntax errors become `Data(Parse)` and documents of the wrong shape `Data(Invalid)`,
+/// the reason keeps the line and column and the error is kept as the source
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::error::Error;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Config {
+///     #[allow(dead_code)]
+///     port: u16,
+/// }
+///
+/// let error = CursedErrorHandle::from(serde_yaml::from_str::<Config>("name: app\nport: : 80").unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert!(error.get_reason().contains("line 2"));
+/// assert!(error.source().unwrap().is::<serde_yaml::Error>());
+///
+/// let error = CursedErrorHandle::from(serde_yaml::from_str::<Config>("name: app\nport: http").unwrap_err());
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+/// assert!(error.get_reason().contains("line 2"));
+/// ```
+impl From<serde_yaml::Error> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: serde_yaml::Error) -> Self {
+        let reason = error.to_string();
+        let kind = match is_semantic_error(&reason) {
+            true => CursedError::Data(CursedErrorType::Invalid),
+            false => CursedError::Data(CursedErrorType::Parse),
+        };
+
+        Self::new(kind, reason).with_source(error)
+    }
+}
@@ -0,0 +1,107 @@
+//! constructors for the failures every database driver has, so services map the errors
+//! of their drivers to the same kinds without curerr depending on the drivers
+
+use crate::*;
+use crate::handle::{Field, Inner};
+
+impl CursedErrorHandle {
+    /// creates error for a database that can't be reached or doesn't accept connections,
+    /// e.g. a refused connection or an exhausted pool, the op is `db`
+    ///
+    /// The error is `Database(Refused)`, [retryable](CursedError::is_retryable) and
+    /// [maps](CursedError::http_status) to 503.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::db_unavailable("primary is down for maintenance");
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Database(CursedErrorType::Refused));
+    /// assert_eq!(error.get_reason(), "primary is down for maintenance");
+    /// assert!(error.get_error().is_retryable());
+    /// assert_eq!(error.get_error().http_status(), 503);
+    /// ```
+    #[track_caller]
+    pub fn db_unavailable(reason: impl Into<String>) -> Self {
+        Self::from_inner(db_error(CursedErrorType::Refused, reason.into()))
+    }
+    /// creates error for a write that violated the `constraint`, e.g. a unique index,
+    /// the name is kept as the `constraint` field and the op is `db`
+    ///
+    /// The error is `Database(AlreadyExists)`, isn't retryable because the same write
+    /// fails again and maps to 409.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::db_constraint_violation("users_email_key");
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Database(CursedErrorType::AlreadyExists));
+    /// assert_eq!(error.get_reason(), "violated constraint users_email_key");
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("constraint", "users_email_key".into())]);
+    /// assert!(!error.get_error().is_retryable());
+    /// assert_eq!(error.get_error().http_status(), 409);
+    /// ```
+    #[track_caller]
+    pub fn db_constraint_violation(constraint: &str) -> Self {
+        let mut inner = db_error(CursedErrorType::AlreadyExists, format!("violated constraint {}", constraint));
+        inner.fields.push(("constraint".into(), Field::Plain(constraint.to_string())));
+        Self::from_inner(inner)
+    }
+    /// creates error for a query that ran out of time, `query_hint` names the query
+    /// without its parameters and is kept as the `query` field, the op is `db`
+    ///
+    /// The error is `Database(Timedout)`, retryable and maps to 504.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::db_timeout("load_user_orders");
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Database(CursedErrorType::Timedout));
+    /// assert_eq!(error.get_reason(), "query load_user_orders timed out");
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("query", "load_user_orders".into())]);
+    /// assert!(error.get_error().is_retryable());
+    /// assert_eq!(error.get_error().http_status(), 504);
+    /// ```
+    #[track_caller]
+    pub fn db_timeout(query_hint: &str) -> Self {
+        let mut inner = db_error(CursedErrorType::Timedout, format!("query {} timed out", query_hint));
+        inner.fields.push(("query".into(), Field::Plain(query_hint.to_string())));
+        Self::from_inner(inner)
+    }
+    /// creates error for a transaction the database rolled back because it conflicted
+    /// with a concurrent one, like a serialization failure or a deadlock, the op is `db`
+    ///
+    /// The error is `Database(Aborted)`, retryable by running the whole transaction
+    /// again and maps to 409.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::db_serialization_failure();
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Database(CursedErrorType::Aborted));
+    /// assert_eq!(error.get_reason(), "transaction failed to serialize with a concurrent one");
+    /// assert!(error.get_error().is_retryable());
+    /// assert_eq!(error.get_error().http_status(), 409);
+    /// ```
+    #[track_caller]
+    pub fn db_serialization_failure() -> Self {
+        Self::from_inner(db_error(
+            CursedErrorType::Aborted,
+            "transaction failed to serialize with a concurrent one".to_string(),
+        ))
+    }
+}
+
+#[track_caller]
+fn db_error(error_type: CursedErrorType, reason: String) -> Inner {
+    let mut inner = Inner::new(CursedError::Database(error_type), reason);
+    inner.op = Some("db");
+    inner
+}
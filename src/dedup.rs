@@ -0,0 +1,90 @@
+//! marking of errors that repeat the previous error of the thread, see [`set_dedup_window`]
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// the window in nanoseconds, `0` while disabled
+static WINDOW: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// fingerprint, creation time and repeat count of the previous error of the thread
+    static PREVIOUS: Cell<Option<(u64, Instant, u32)>> = const { Cell::new(None) };
+}
+
+/// marks errors with the same [fingerprint](crate::CursedErrorHandle::fingerprint) as the
+/// previous error of the thread, if that one was created less than `window` ago
+///
+/// Marked errors return `true` from [`CursedErrorHandle::is_duplicate`] and count the
+/// repeats in [`CursedErrorHandle::repeat_count`], so hooks and reporters can skip or
+/// summarize a burst. An error with another fingerprint or one after a longer pause
+/// starts over. Each thread compares with its own previous error only, so there is no
+/// locking.
+///
+/// Disabled by default and by [`Duration::ZERO`]. While disabled, no fingerprint is
+/// computed and no previous error is kept. While enabled, the fingerprint is taken on
+/// creation, so the reason counts but fields and contexts added later don't.
+///
+/// [`CursedErrorHandle::is_duplicate`]: crate::CursedErrorHandle::is_duplicate
+/// [`CursedErrorHandle::repeat_count`]: crate::CursedErrorHandle::repeat_count
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::time::Duration;
+///
+/// let refused = |port: u16| CursedErrorHandle::new(
+///     CursedError::Connection(CursedErrorType::Refused),
+///     format!("127.0.0.1:{} refused the connection", port)
+/// );
+///
+/// set_dedup_window(Duration::from_secs(60));
+///
+/// let burst: Vec<_> = (0..5).map(|_| refused(6379)).collect();
+///
+/// assert!(!burst[0].is_duplicate());
+/// assert!(burst[1..].iter().all(CursedErrorHandle::is_duplicate));
+/// assert_eq!(burst.iter().map(CursedErrorHandle::repeat_count).collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+///
+/// // the numbers are normalized away, another kind starts over
+/// assert_eq!(refused(6380).repeat_count(), 5);
+/// assert!(!CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "timed out".to_string()).is_duplicate());
+/// assert!(!refused(6379).is_duplicate());
+///
+/// // other threads don't see the errors of this one
+/// assert!(!std::thread::spawn(move || refused(6379).is_duplicate()).join().unwrap());
+/// assert!(refused(6379).is_duplicate());
+///
+/// set_dedup_window(Duration::from_millis(10));
+/// std::thread::sleep(Duration::from_millis(20));
+/// assert!(!refused(6379).is_duplicate());
+///
+/// set_dedup_window(Duration::ZERO);
+/// assert!(!refused(6379).is_duplicate());
+/// ```
+pub fn set_dedup_window(window: Duration) {
+    let nanos = u64::try_from(window.as_nanos()).unwrap_or(u64::MAX);
+    WINDOW.store(nanos, Ordering::Relaxed);
+}
+
+/// whether errors are compared at all, checked before computing the fingerprint
+pub(crate) fn enabled() -> bool {
+    WINDOW.load(Ordering::Relaxed) != 0
+}
+
+/// number of errors with `fingerprint` created right before this one on the thread
+pub(crate) fn repeat_count(fingerprint: u64) -> u32 {
+    let window = Duration::from_nanos(WINDOW.load(Ordering::Relaxed));
+    let now = Instant::now();
+
+    PREVIOUS.with(|previous| {
+        let repeats = match previous.get() {
+            Some((previous, created, repeats)) if previous == fingerprint && now.duration_since(created) < window => {
+                repeats.saturating_add(1)
+            },
+            _ => 0,
+        };
+        previous.set(Some((fingerprint, now, repeats)));
+        repeats
+    })
+}
@@ -0,0 +1,145 @@
+//! errors of cleanup code in `Drop` impls, collected until a point that can handle them
+
+use crate::*;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// collects the errors of cleanup code that has no caller to return them to, like
+/// `Drop` impls, until [`DeferredErrors::drain`] is called at a safe point
+///
+/// Clones share the errors, so each guard or resource keeps its own clone.
+/// [`DeferredErrors::record`] never panics, also not while the thread is unwinding. When
+/// there is no memory left for another error it is dropped and counted instead.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// struct TempFile {
+///     path: std::path::PathBuf,
+///     errors: DeferredErrors,
+/// }
+///
+/// impl Drop for TempFile {
+///     fn drop(&mut self) {
+///         if let Err(error) = std::fs::remove_file(&self.path) {
+///             self.errors.record(CursedErrorHandle::open_err(&self.path, error));
+///         }
+///     }
+/// }
+///
+/// let errors = DeferredErrors::new();
+/// {
+///     let _file = TempFile { path: "/definitely/not/here.tmp".into(), errors: errors.clone() };
+/// }
+///
+/// let drained = errors.drain();
+/// assert_eq!(drained.len(), 1);
+/// assert_eq!(drained.errors()[0].get_error(), &CursedError::File(CursedErrorType::NotFound));
+/// assert!(errors.drain().is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DeferredErrors {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    errors: Mutex<Vec<CursedErrorHandle>>,
+    lost: AtomicU64,
+}
+
+impl DeferredErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// keeps `error` until the next [`DeferredErrors::drain`], or counts it as lost if
+    /// there is no memory for it
+    pub fn record(&self, error: CursedErrorHandle) {
+        let mut errors = self.shared.errors.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match errors.try_reserve(1) {
+            Ok(()) => errors.push(error),
+            Err(_) => {
+                self.shared.lost.fetch_add(1, Ordering::Relaxed);
+            },
+        }
+    }
+    /// takes the recorded errors, in the order they were recorded
+    ///
+    /// Lost errors are reported by a last `Memory(NotEnough)` error with their number.
+    pub fn drain(&self) -> MultiError {
+        let errors = std::mem::take(&mut *self.shared.errors.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+        let mut errors = MultiError::from(errors);
+
+        let lost = self.shared.lost.swap(0, Ordering::Relaxed);
+        if lost > 0 {
+            errors.push(CursedErrorHandle::new(
+                CursedError::Memory(CursedErrorType::NotEnough),
+                format!("{} deferred {} lost", lost, if lost == 1 { "error was" } else { "errors were" })
+            ));
+        }
+        errors
+    }
+    /// number of errors dropped by [`DeferredErrors::record`] since the last drain
+    pub fn lost(&self) -> u64 {
+        self.shared.lost.load(Ordering::Relaxed)
+    }
+    /// runs `cleanup` when the returned guard is dropped and records its error, see
+    /// [`defer_errors!`]
+    pub fn defer<F: FnOnce() -> CursedResult<()>>(&self, cleanup: F) -> DeferGuard<F> {
+        DeferGuard { errors: self.clone(), cleanup: Some(cleanup) }
+    }
+}
+
+/// guard returned by [`DeferredErrors::defer`] and [`defer_errors!`], runs the cleanup
+/// when dropped
+///
+/// Errors of the cleanup are recorded, a panic is caught and recorded as a
+/// `Call(Custom("panicked"))` error, so the guard is safe to drop while unwinding.
+#[must_use = "the cleanup runs when the guard is dropped"]
+pub struct DeferGuard<F: FnOnce() -> CursedResult<()>> {
+    errors: DeferredErrors,
+    cleanup: Option<F>,
+}
+
+impl<F: FnOnce() -> CursedResult<()>> DeferGuard<F> {
+    /// drops the guard without running the cleanup
+    pub fn dismiss(mut self) {
+        self.cleanup = None;
+    }
+}
+
+impl<F: FnOnce() -> CursedResult<()>> Drop for DeferGuard<F> {
+    fn drop(&mut self) {
+        let Some(cleanup) = self.cleanup.take() else {
+            return
+        };
+
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(cleanup)) {
+            Ok(Ok(())) => {},
+            Ok(Err(error)) => self.errors.record(error),
+            Err(payload) => {
+                let reason = match panic_message(&*payload) {
+                    Some(message) => format!("cleanup panicked: {}", message),
+                    None => "cleanup panicked".to_string(),
+                };
+                self.errors.record(CursedErrorHandle::new(CursedError::Call(CursedErrorType::Custom("panicked")), reason));
+            },
+        }
+    }
+}
+
+/// the message of a panic payload, given by `panic!` as a `&str` or a `String`
+pub(crate) fn panic_message(payload: &dyn std::any::Any) -> Option<&str> {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => Some(message),
+        None => payload.downcast_ref::<String>().map(String::as_str),
+    }
+}
+
+impl<F: FnOnce() -> CursedResult<()>> std::fmt::Debug for DeferGuard<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeferGuard").field("armed", &self.cleanup.is_some()).finish()
+    }
+}
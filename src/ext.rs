@@ -0,0 +1,330 @@
+//! extension traits for results and iterators of results
+
+use crate::*;
+use crate::convert::wrap_foreign;
+use crate::typed::take_typed;
+
+/// extension methods for results
+pub trait ResultExt<T> {
+    /// adds context to the error
+    ///
+    /// A [`CursedErrorHandle`] gets the context pushed onto its
+    /// [context stack](CursedErrorHandle::push_context), any other error is wrapped
+    /// as the source of a new handle [classified](CursedError::classify) by it, with the
+    /// context as reason.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::error::Error;
+    ///
+    /// let result: CursedResult<()> = Err(CursedErrorHandle::new(
+    ///     CursedError::Data(CursedErrorType::Parse),
+    ///     "bad number".to_string()
+    /// ));
+    /// let error = result.context("while parsing port").unwrap_err();
+    ///
+    /// assert_eq!(error.contexts(), ["while parsing port"]);
+    /// assert!(error.source().is_none());
+    ///
+    /// let result: Result<(), std::fmt::Error> = Err(std::fmt::Error);
+    /// let error = result.context("while formatting").unwrap_err();
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Unknown(None));
+    /// assert_eq!(error.get_reason(), "while formatting");
+    /// assert!(error.source().unwrap().is::<std::fmt::Error>());
+    /// ```
+    fn context(self, context: impl Into<String>) -> CursedResult<T>;
+    /// attaches [help](CursedErrorHandle::help) to the error
+    ///
+    /// Any other error than [`CursedErrorHandle`] is wrapped as the source of a new
+    /// handle [classified](CursedError::classify) by it, with the same reason.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let result: Result<(), std::fmt::Error> = Err(std::fmt::Error);
+    /// let error = result.with_help("check the template").unwrap_err();
+    ///
+    /// assert_eq!(error.get_reason(), "an error occurred when formatting an argument");
+    /// assert_eq!(error.get_help(), Some("check the template"));
+    /// ```
+    fn with_help(self, help: impl Into<std::borrow::Cow<'static, str>>) -> CursedResult<T>;
+    /// converts the error with [`wrap_any`]
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// fn parse_port(port: &str) -> CursedResult<u16> {
+    ///     let port = port.parse::<std::num::NonZeroU16>().wrap_any()?;
+    ///     Ok(port.get())
+    /// }
+    ///
+    /// assert_eq!(parse_port("8080").unwrap(), 8080);
+    /// assert_eq!(parse_port("0").unwrap_err().get_error(), &CursedError::Data(CursedErrorType::Parse));
+    /// ```
+    fn wrap_any(self) -> CursedResult<T>;
+    /// tags the error with the name of the failed operation, see [`CursedErrorHandle::with_op`]
+    ///
+    /// Any other error than [`CursedErrorHandle`] is converted with [`wrap_any`] first.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// fn load_config() -> CursedResult<u16> {
+    ///     let port = "http".parse::<u16>().op("parse_port")?;
+    ///     Ok(port)
+    /// }
+    ///
+    /// let error = load_config().op("load_config").unwrap_err();
+    ///
+    /// assert_eq!(error.op(), Some("load_config"));
+    /// assert_eq!(error.to_string(), "load_config: data parse error: \"invalid digit found in string\"");
+    /// ```
+    fn op(self, op: &'static str) -> CursedResult<T>;
+    /// replaces the kind of the error, see [`CursedErrorHandle::map_kind`]
+    ///
+    /// Any other error than [`CursedErrorHandle`] is converted with [`wrap_any`] first.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let result: Result<(), _> = Err(std::io::Error::from(std::io::ErrorKind::NotFound));
+    /// let error = result
+    ///     .map_kind(|kind| match kind {
+    ///         CursedError::Io(error_type) => CursedError::Data(error_type),
+    ///         kind => kind,
+    ///     })
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::NotFound));
+    /// assert_eq!(error.get_reason(), "entity not found");
+    /// ```
+    fn map_kind(self, f: impl FnOnce(CursedError) -> CursedError) -> CursedResult<T>;
+}
+
+/// returns the handle if `error` is one or one of the [typed errors](TimeoutError),
+/// otherwise the error itself
+pub(crate) fn downcast_handle<E: std::error::Error + Send + Sync + 'static>(error: E) -> Result<CursedErrorHandle, E> {
+    let mut error = Some(error);
+
+    let any = &mut error as &mut dyn std::any::Any;
+    if let Some(handle) = any.downcast_mut::<Option<CursedErrorHandle>>() {
+        return Ok(handle.take().expect("error is taken only once"))
+    }
+    match take_typed(any) {
+        Some(handle) => Ok(handle),
+        None => Err(error.take().expect("error is taken only once")),
+    }
+}
+
+impl<T, E: std::error::Error + Send + Sync + 'static> ResultExt<T> for Result<T, E> {
+    // matches instead of `map_err` closures, which would hide the caller from `#[track_caller]`
+    #[track_caller]
+    fn context(self, context: impl Into<String>) -> CursedResult<T> {
+        let error = match self {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        match downcast_handle(error) {
+            Ok(mut handle) => {
+                handle.push_context(context);
+                Err(handle)
+            },
+            Err(error) => {
+                let mut handle = wrap_foreign(Box::new(error), context.into());
+                handle.inner_mut().push_trace();
+                Err(handle)
+            },
+        }
+    }
+    #[track_caller]
+    fn with_help(self, help: impl Into<std::borrow::Cow<'static, str>>) -> CursedResult<T> {
+        let error = match self {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        match downcast_handle(error) {
+            Ok(handle) => Err(handle.help(help)),
+            Err(error) => {
+                let reason = error.to_string();
+                Err(wrap_foreign(Box::new(error), reason).help(help))
+            },
+        }
+    }
+    #[track_caller]
+    fn wrap_any(self) -> CursedResult<T> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(wrap_any(error)),
+        }
+    }
+    #[track_caller]
+    fn op(self, op: &'static str) -> CursedResult<T> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(wrap_any(error).with_op(op)),
+        }
+    }
+    #[track_caller]
+    fn map_kind(self, f: impl FnOnce(CursedError) -> CursedError) -> CursedResult<T> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(wrap_any(error).map_kind(f)),
+        }
+    }
+}
+
+/// conversion into a handle, for generic code that bounds on one trait and for error
+/// types that don't implement [`std::error::Error`]
+///
+/// Every type with a `From` conversion into [`CursedErrorHandle`] implements it, like
+/// [`CursedError`], [`MultiError`] and the std errors of the `std-conversions` feature.
+/// Other error types implement it by hand, usually with a match arm per variant.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// enum StoreError {
+///     Missing(String),
+///     Locked,
+/// }
+///
+/// impl IntoCursed for StoreError {
+///     fn into_cursed(self) -> CursedErrorHandle {
+///         match self {
+///             StoreError::Missing(key) => CursedError::Other(CursedErrorType::NotFound).with_reason(format!("no key {}", key)),
+///             StoreError::Locked => CursedError::Other(CursedErrorType::AccessDenied).with_reason("store is locked"),
+///         }
+///     }
+/// }
+///
+/// fn kinds<E: IntoCursed>(errors: Vec<E>) -> Vec<CursedError> {
+///     errors.into_iter().map(|error| *error.into_cursed().get_error()).collect()
+/// }
+///
+/// assert_eq!(kinds(vec![StoreError::Locked]), [CursedError::Other(CursedErrorType::AccessDenied)]);
+/// assert_eq!(kinds(vec![CursedError::Data(CursedErrorType::Parse)]), [CursedError::Data(CursedErrorType::Parse)]);
+/// ```
+pub trait IntoCursed {
+    fn into_cursed(self) -> CursedErrorHandle;
+}
+
+impl<E: Into<CursedErrorHandle>> IntoCursed for E {
+    #[track_caller]
+    fn into_cursed(self) -> CursedErrorHandle {
+        self.into()
+    }
+}
+
+/// converts the error of a result with [`IntoCursed`], so `?` works in functions returning
+/// a [`CursedResult`] for errors that only implement the trait
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// struct Busy;
+///
+/// impl IntoCursed for Busy {
+///     fn into_cursed(self) -> CursedErrorHandle {
+///         CursedError::Call(CursedErrorType::Interrupted).with_reason("worker is busy")
+///     }
+/// }
+///
+/// fn schedule(busy: bool) -> Result<u32, Busy> {
+///     if busy { Err(Busy) } else { Ok(7) }
+/// }
+///
+/// fn run(busy: bool) -> CursedResult<u32> {
+///     let job = schedule(busy).into_cursed_err()?;
+///     Ok(job)
+/// }
+///
+/// assert_eq!(run(false).unwrap(), 7);
+/// assert_eq!(run(true).unwrap_err().to_string(), "call interrupted error: \"worker is busy\"");
+/// ```
+pub trait CursedResultBridge<T> {
+    fn into_cursed_err(self) -> CursedResult<T>;
+}
+
+impl<T, E: IntoCursed> CursedResultBridge<T> for Result<T, E> {
+    #[track_caller]
+    fn into_cursed_err(self) -> CursedResult<T> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(error.into_cursed()),
+        }
+    }
+}
+
+/// extension methods for iterators of results, for batches that should report every
+/// failure instead of stopping at the first one
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let parse = |value: &str| value.parse::<u16>().context(format!("while parsing {:?}", value));
+///
+/// let (ports, errors) = ["80", "http", "443", "", "8080"].into_iter().map(parse).index_errors().collect_cursed();
+///
+/// assert_eq!(ports, [80, 443, 8080]);
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors.errors()[0].get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert_eq!(errors.errors()[0].fields().collect::<Vec<_>>(), [("index", "1".into())]);
+/// assert_eq!(errors.errors()[1].get_error(), &CursedError::Input(CursedErrorType::NotEnough));
+/// assert_eq!(errors.errors()[1].fields().collect::<Vec<_>>(), [("index", "3".into())]);
+///
+/// let ports = ["80", "443"].into_iter().map(parse).collect_or_cursed().unwrap();
+/// assert_eq!(ports, [80, 443]);
+///
+/// let error = ["80", "http", "-1"].into_iter().map(parse).collect_or_cursed().unwrap_err();
+/// let errors = std::error::Error::source(&error).unwrap().downcast_ref::<MultiError>().unwrap();
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+/// assert_eq!(errors.len(), 2);
+///
+/// // a single failure is returned as it is
+/// let error = ["80", "http"].into_iter().map(parse).collect_or_cursed().unwrap_err();
+/// assert_eq!(error.get_reason(), "while parsing \"http\"");
+/// ```
+pub trait CursedIterExt<T, E: Into<CursedErrorHandle>>: Iterator<Item = Result<T, E>> + Sized {
+    /// the successes in their order and every error, without stopping at the first one
+    fn collect_cursed(self) -> (Vec<T>, MultiError) {
+        let mut values = Vec::new();
+        let mut errors = MultiError::new();
+
+        for item in self {
+            match item {
+                Ok(value) => values.push(value),
+                Err(error) => errors.push(error),
+            }
+        }
+        (values, errors)
+    }
+    /// the successes if there was no error, otherwise the errors converted with
+    /// [`From<MultiError>`](CursedErrorHandle#impl-From<MultiError>-for-CursedErrorHandle)
+    ///
+    /// The whole iterator is consumed either way.
+    fn collect_or_cursed(self) -> CursedResult<Vec<T>> {
+        match self.collect_cursed() {
+            (values, errors) if errors.is_empty() => Ok(values),
+            (_, errors) => Err(errors.into()),
+        }
+    }
+    /// adds the position of the item as the `index` field of each error, counted from 0
+    /// over all items
+    fn index_errors(self) -> impl Iterator<Item = CursedResult<T>> {
+        self.enumerate().map(|(index, item)| item.map_err(|error| error.into().with_field("index", index)))
+    }
+}
+
+impl<T, E: Into<CursedErrorHandle>, I: Iterator<Item = Result<T, E>>> CursedIterExt<T, E> for I {}
@@ -0,0 +1,177 @@
+//! constructors that share the namespace, tags and category of a subsystem
+
+use crate::*;
+
+use std::sync::Arc;
+
+/// creates errors with the defaults of a subsystem, configured once
+///
+/// Every error gets the [namespace](CursedErrorHandle::with_namespace), the tags as
+/// the `tags` field and the other fields of the factory. [`ErrorFactory::err`] and
+/// the shortcuts like [`ErrorFactory::not_found`] use the default category, which is
+/// [`CursedCategory::Other`] unless set.
+///
+/// The configuration is shared, so clones are cheap and a clone with a changed
+/// default is the way to override it for one call. Factories can be stored in
+/// statics with [`std::sync::LazyLock`].
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::sync::LazyLock;
+///
+/// static CACHE: LazyLock<ErrorFactory> = LazyLock::new(|| {
+///     ErrorFactory::new()
+///         .namespace("cache")
+///         .tag("infra")
+///         .tag("redis")
+///         .default_category(CursedCategory::Connection)
+/// });
+///
+/// let error = CACHE.err(CursedErrorType::Refused, "redis is down");
+///
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Refused));
+/// assert_eq!(error.namespace(), Some("cache"));
+/// assert_eq!(
+///     error.report().to_string(),
+///     "[cache] connection refused error: \"redis is down\"\n  tags = infra, redis"
+/// );
+///
+/// let error = CACHE.not_found("no entry for user:7").with_field("key", "user:7");
+///
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::NotFound));
+/// assert_eq!(error.fields().collect::<Vec<_>>(), [("tags", "infra, redis".into()), ("key", "user:7".into())]);
+///
+/// let error = CACHE.err_kind(CursedError::Data(CursedErrorType::Invalid), "entry is not valid json");
+///
+/// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+/// assert_eq!(error.namespace(), Some("cache"));
+///
+/// let error = CACHE.clone().namespace("cache.local").field("tier", 1).timed_out("lookup timed out");
+///
+/// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Timedout));
+/// assert_eq!(error.namespace(), Some("cache.local"));
+/// assert_eq!(error.fields().collect::<Vec<_>>(), [("tags", "infra, redis".into()), ("tier", "1".into())]);
+/// assert_eq!(CACHE.err(CursedErrorType::Reset, "").namespace(), Some("cache"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ErrorFactory {
+    defaults: Arc<Defaults>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Defaults {
+    namespace: Option<&'static str>,
+    tags: Vec<&'static str>,
+    fields: Vec<(&'static str, String)>,
+    category: Option<CursedCategory>,
+}
+
+impl ErrorFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// namespace of the created errors, replaces the previous one
+    pub fn namespace(mut self, namespace: &'static str) -> Self {
+        Arc::make_mut(&mut self.defaults).namespace = Some(namespace);
+        self
+    }
+    /// adds a tag, all tags are kept comma separated in the `tags` field
+    ///
+    /// The tags are sorted and duplicates are dropped, so the field doesn't depend on
+    /// the order the tags were added in.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let first = ErrorFactory::new().tag("redis").tag("infra").tag("redis");
+    /// let second = ErrorFactory::new().tag("infra").tag("redis");
+    ///
+    /// assert_eq!(first.invalid("").fields().collect::<Vec<_>>(), [("tags", "infra, redis".into())]);
+    /// assert_eq!(first.invalid("").canonical_json(), second.invalid("").canonical_json());
+    /// ```
+    pub fn tag(mut self, tag: &'static str) -> Self {
+        let tags = &mut Arc::make_mut(&mut self.defaults).tags;
+        if let Err(index) = tags.binary_search(&tag) {
+            tags.insert(index, tag);
+        }
+        self
+    }
+    /// adds a field to every created error, after the `tags` field
+    pub fn field(mut self, key: &'static str, value: impl std::fmt::Display) -> Self {
+        Arc::make_mut(&mut self.defaults).fields.push((key, value.to_string()));
+        self
+    }
+    /// category of the errors created by [`ErrorFactory::err`] and the shortcuts
+    pub fn default_category(mut self, category: CursedCategory) -> Self {
+        Arc::make_mut(&mut self.defaults).category = Some(category);
+        self
+    }
+    /// creates error of the default category
+    #[track_caller]
+    pub fn err(&self, error_type: CursedErrorType, reason: impl Into<String>) -> CursedErrorHandle {
+        let category = self.defaults.category.unwrap_or(CursedCategory::Other);
+        self.err_kind(category.with_type(error_type), reason)
+    }
+    /// creates error of another kind than the default category has
+    #[track_caller]
+    pub fn err_kind(&self, error: CursedError, reason: impl Into<String>) -> CursedErrorHandle {
+        self.apply(CursedErrorHandle::new(error, reason.into()))
+    }
+    /// converts `source` like [`wrap_any`] and adds the defaults, the category of the
+    /// conversion is kept
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let factory = ErrorFactory::new().namespace("loader").tag("startup");
+    /// let error = factory.wrap("x".parse::<u8>().unwrap_err());
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::Parse));
+    /// assert_eq!(error.namespace(), Some("loader"));
+    /// assert!(std::error::Error::source(&error).unwrap().is::<std::num::ParseIntError>());
+    ///
+    /// // handles of other subsystems keep the namespace they already have
+    /// let error = factory.wrap(CursedErrorHandle::new(CursedError::Io(CursedErrorType::NotFound), String::new())
+    ///     .with_namespace("fs"));
+    /// assert_eq!(error.namespace(), Some("fs"));
+    /// ```
+    #[track_caller]
+    pub fn wrap<E: std::error::Error + Send + Sync + 'static>(&self, source: E) -> CursedErrorHandle {
+        self.apply(wrap_any(source))
+    }
+    #[track_caller]
+    pub fn not_found(&self, reason: impl Into<String>) -> CursedErrorHandle {
+        self.err(CursedErrorType::NotFound, reason)
+    }
+    #[track_caller]
+    pub fn invalid(&self, reason: impl Into<String>) -> CursedErrorHandle {
+        self.err(CursedErrorType::Invalid, reason)
+    }
+    #[track_caller]
+    pub fn timed_out(&self, reason: impl Into<String>) -> CursedErrorHandle {
+        self.err(CursedErrorType::Timedout, reason)
+    }
+    #[track_caller]
+    pub fn access_denied(&self, reason: impl Into<String>) -> CursedErrorHandle {
+        self.err(CursedErrorType::AccessDenied, reason)
+    }
+    #[track_caller]
+    pub fn not_supported(&self, reason: impl Into<String>) -> CursedErrorHandle {
+        self.err(CursedErrorType::NotSupported, reason)
+    }
+    fn apply(&self, mut error: CursedErrorHandle) -> CursedErrorHandle {
+        if let Some(namespace) = self.defaults.namespace {
+            error = error.with_namespace(namespace);
+        }
+        if !self.defaults.tags.is_empty() {
+            error = error.with_field("tags", self.defaults.tags.join(", "));
+        }
+        for (key, value) in &self.defaults.fields {
+            error = error.with_field(*key, value);
+        }
+        error
+    }
+}
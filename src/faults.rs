@@ -0,0 +1,282 @@
+//! failures injected on purpose, to test how an application handles each classification
+//!
+//! Fallible paths call [`FaultInjector::check`] with the name of the operation. Without
+//! the `testing` feature the injector is empty and the check always succeeds, so it can
+//! stay in release builds. With the feature, tests add rules that make the check fail
+//! with a chosen kind and afterwards assert which faults fired.
+//!
+//! The injector and its check are stable, the rules and the fired faults are unstable
+//! and need the `testing` feature, which enables `unstable`.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "testing")] {
+//! use curerr::*;
+//! use curerr::faults::FaultInjector;
+//!
+//! struct Store {
+//!     faults: FaultInjector,
+//! }
+//!
+//! impl Store {
+//!     fn get(&self, key: &str) -> CursedResult<String> {
+//!         self.faults.check("db")?;
+//!         Ok(format!("value of {}", key))
+//!     }
+//! }
+//!
+//! let store = Store {
+//!     faults: FaultInjector::new().fail_nth("db", 3, CursedError::Connection(CursedErrorType::Timedout)),
+//! };
+//!
+//! assert!(store.get("a").is_ok());
+//! assert!(store.get("b").is_ok());
+//!
+//! let error = store.get("c").unwrap_err();
+//! assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Timedout));
+//! assert_eq!(error.get_reason(), "injected fault in call 3 of db");
+//!
+//! assert!(store.get("d").is_ok());
+//! store.faults.assert_fired("db", &[3]);
+//! # }
+//! ```
+
+use crate::*;
+
+#[cfg(feature = "testing")]
+use std::sync::Mutex;
+
+/// fails [`FaultInjector::check`] calls by the rules added to it
+///
+/// Calls are counted per operation starting at 1, every call of an operation is checked
+/// against its rules in the order they were added and the first one that triggers
+/// decides the kind of the injected error.
+///
+/// The rules are only available with the `testing` feature, without it the injector
+/// holds nothing and [`FaultInjector::check`] compiles to `Ok(())`.
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    #[cfg(feature = "testing")]
+    state: Mutex<State>,
+}
+
+unstable! {
+    /// a fault injected by [`FaultInjector::check`]
+    #[cfg(feature = "testing")]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct FiredFault {
+        pub op: String,
+        /// number of the call of the operation, starting at 1
+        pub call: u64,
+        pub error: CursedError,
+    }
+}
+
+#[cfg(feature = "testing")]
+#[derive(Debug)]
+struct State {
+    rules: Vec<Rule>,
+    calls: Vec<(String, u64)>,
+    fired: Vec<FiredFault>,
+    /// state of the xorshift64* generator of the probability rules
+    random: u64,
+}
+
+unstable! {
+    #[cfg(feature = "testing")]
+    impl Default for State {
+        fn default() -> Self {
+            Self { rules: Vec::new(), calls: Vec::new(), fired: Vec::new(), random: seed_state(0) }
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+#[derive(Debug)]
+struct Rule {
+    op: String,
+    trigger: Trigger,
+    error: CursedError,
+}
+
+#[cfg(feature = "testing")]
+#[derive(Debug)]
+enum Trigger {
+    Nth(u64),
+    EveryNth(u64),
+    Probability(f64),
+}
+
+/// splitmix64 of the seed, so close seeds give unrelated nonzero states
+#[cfg(feature = "testing")]
+fn seed_state(seed: u64) -> u64 {
+    let seed = seed.wrapping_add(0x9e3779b97f4a7c15);
+    let seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    let seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d049bb133111eb);
+    (seed ^ (seed >> 31)) | 1
+}
+
+/// uniform number in `[0, 1)` from a xorshift64* generator
+#[cfg(feature = "testing")]
+fn next_random(state: &mut u64) -> f64 {
+    let mut x = *state;
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    *state = x;
+    (x.wrapping_mul(0x2545f4914f6cdd1d) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// succeeds unless a rule for `op` triggers on this call, then fails with the kind of
+    /// the rule and a reason like `"injected fault in call 3 of db"`
+    #[cfg(feature = "testing")]
+    #[track_caller]
+    pub fn check(&self, op: &str) -> CursedResult<()> {
+        let mut state = self.lock();
+        let state = &mut *state;
+
+        let call = match state.calls.iter_mut().find(|(name, _)| name == op) {
+            Some((_, calls)) => {
+                *calls += 1;
+                *calls
+            },
+            None => {
+                state.calls.push((op.to_string(), 1));
+                1
+            },
+        };
+
+        let mut fired = None;
+        for rule in state.rules.iter().filter(|rule| rule.op == op) {
+            let triggered = match rule.trigger {
+                Trigger::Nth(n) => call == n,
+                Trigger::EveryNth(n) => n != 0 && call % n == 0,
+                // drawn for every probability rule of the call, so the sequence only
+                // depends on the seed and the calls
+                Trigger::Probability(probability) => next_random(&mut state.random) < probability,
+            };
+            if triggered && fired.is_none() {
+                fired = Some(rule.error);
+            }
+        }
+
+        match fired {
+            Some(error) => {
+                state.fired.push(FiredFault { op: op.to_string(), call, error });
+                Err(CursedErrorHandle::new(error, format!("injected fault in call {} of {}", call, op)))
+            },
+            None => Ok(()),
+        }
+    }
+    /// always succeeds, the rules need the `testing` feature
+    #[cfg(not(feature = "testing"))]
+    #[inline(always)]
+    pub fn check(&self, _op: &str) -> CursedResult<()> {
+        Ok(())
+    }
+}
+
+unstable! {
+    #[cfg(feature = "testing")]
+    impl FaultInjector {
+        /// fails the `n`th call of `op` with `error`
+        pub fn fail_nth(self, op: impl Into<String>, n: u64, error: CursedError) -> Self {
+            self.with_rule(op, Trigger::Nth(n), error)
+        }
+        /// fails every `n`th call of `op` with `error`, a `n` of 0 never fails
+        ///
+        /// # Examples
+        /// ```
+        /// use curerr::*;
+        /// use curerr::faults::FaultInjector;
+        ///
+        /// let faults = FaultInjector::new()
+        ///     .fail_nth("upload", 1, CursedError::Io(CursedErrorType::AccessDenied))
+        ///     .fail_every("upload", 2, CursedError::Connection(CursedErrorType::Reset));
+        ///
+        /// let results: Vec<_> = (0..5).map(|_| faults.check("upload").map_err(|err| *err.get_error())).collect();
+        ///
+        /// assert_eq!(results, [
+        ///     Err(CursedError::Io(CursedErrorType::AccessDenied)),
+        ///     Err(CursedError::Connection(CursedErrorType::Reset)),
+        ///     Ok(()),
+        ///     Err(CursedError::Connection(CursedErrorType::Reset)),
+        ///     Ok(()),
+        /// ]);
+        /// assert!(faults.check("download").is_ok());
+        /// assert_eq!(faults.calls("upload"), 5);
+        /// faults.assert_fired("upload", &[1, 2, 4]);
+        /// faults.assert_fired("download", &[]);
+        /// ```
+        pub fn fail_every(self, op: impl Into<String>, n: u64, error: CursedError) -> Self {
+            self.with_rule(op, Trigger::EveryNth(n), error)
+        }
+        /// fails calls of `op` with `error` with the given probability between 0 and 1
+        ///
+        /// The draws come from a generator seeded with [`FaultInjector::seed`], 0 unless set,
+        /// so a test fails the same calls on every run.
+        ///
+        /// # Examples
+        /// ```
+        /// use curerr::*;
+        /// use curerr::faults::FaultInjector;
+        ///
+        /// let run = |seed| {
+        ///     let faults = FaultInjector::new()
+        ///         .seed(seed)
+        ///         .fail_with_probability("send", 0.25, CursedError::Connection(CursedErrorType::Timedout));
+        ///     for _ in 0..1_000 {
+        ///         let _ = faults.check("send");
+        ///     }
+        ///     faults.fired().into_iter().map(|fault| fault.call).collect::<Vec<_>>()
+        /// };
+        ///
+        /// let fired = run(7);
+        /// assert_eq!(fired, run(7));
+        /// assert_ne!(fired, run(8));
+        /// assert!((200..300).contains(&fired.len()), "{}", fired.len());
+        /// ```
+        pub fn fail_with_probability(self, op: impl Into<String>, probability: f64, error: CursedError) -> Self {
+            self.with_rule(op, Trigger::Probability(probability), error)
+        }
+        /// seeds the generator of the probability rules
+        pub fn seed(mut self, seed: u64) -> Self {
+            self.state_mut().random = seed_state(seed);
+            self
+        }
+        fn with_rule(mut self, op: impl Into<String>, trigger: Trigger, error: CursedError) -> Self {
+            self.state_mut().rules.push(Rule { op: op.into(), trigger, error });
+            self
+        }
+        /// number of checks of `op` so far
+        pub fn calls(&self, op: &str) -> u64 {
+            self.lock().calls.iter().find(|(name, _)| name == op).map_or(0, |(_, calls)| *calls)
+        }
+        /// the injected faults in the order they fired
+        pub fn fired(&self) -> Vec<FiredFault> {
+            self.lock().fired.clone()
+        }
+        /// panics unless the faults of `op` fired exactly at the `calls`, in that order
+        #[track_caller]
+        pub fn assert_fired(&self, op: &str, calls: &[u64]) {
+            let fired: Vec<u64> = self.lock().fired.iter().filter(|fault| fault.op == op).map(|fault| fault.call).collect();
+            assert_eq!(fired, calls, "faults of {} fired at calls {:?}, expected {:?}", op, fired, calls);
+        }
+        /// forgets the counted calls and fired faults, the rules and the generator are kept
+        pub fn reset(&self) {
+            let mut state = self.lock();
+            state.calls.clear();
+            state.fired.clear();
+        }
+        fn state_mut(&mut self) -> &mut State {
+            self.state.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
+        fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+            self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
+    }
+}
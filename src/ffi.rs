@@ -0,0 +1,128 @@
+//! last error slot for callers across the C ABI
+
+use crate::*;
+
+std::thread_local! {
+    static LAST_ERROR: std::cell::RefCell<Option<CursedErrorHandle>> = const { std::cell::RefCell::new(None) };
+}
+
+/// stores `error` as the last error of the current thread, dropping the previous one
+///
+/// Meant for C APIs that return a status code and let the caller fetch the details
+/// afterwards, like `errno`.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// set_last_error(CursedErrorHandle::new(
+///     CursedError::File(CursedErrorType::NotFound),
+///     "config.toml".to_string()
+/// ));
+/// assert_eq!(last_error_code(), CursedError::File(CursedErrorType::NotFound).numeric_code());
+///
+/// std::thread::spawn(|| {
+///     assert!(take_last_error().is_none());
+///     set_last_error(CursedErrorHandle::new(CursedError::Unknown(None), "other thread".to_string()));
+///     assert_eq!(last_error_code(), 9999);
+/// }).join().unwrap();
+///
+/// let error = take_last_error().unwrap();
+/// assert_eq!(error.get_reason(), "config.toml");
+/// assert!(take_last_error().is_none());
+/// assert_eq!(last_error_code(), 0);
+/// ```
+pub fn set_last_error(error: CursedErrorHandle) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+}
+
+/// removes and returns the last error of the current thread
+pub fn take_last_error() -> Option<CursedErrorHandle> {
+    LAST_ERROR.with(|last| last.borrow_mut().take())
+}
+
+/// calls `f` with a view of the last error of the current thread, the error stays set
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// assert_eq!(with_last_error(|error| error.code().to_string()), None);
+///
+/// set_last_error(CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "slow peer".to_string()));
+///
+/// assert_eq!(with_last_error(|error| error.code().to_string()).as_deref(), Some("CALL_TIMED_OUT"));
+/// assert_eq!(with_last_error(|error| error.reason.len()), Some(9));
+/// ```
+pub fn with_last_error<R>(f: impl FnOnce(CursedErrorRef<'_>) -> R) -> Option<R> {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map(|error| f(error.as_ref_view())))
+}
+
+/// [numeric code](CursedError::numeric_code) of the last error of the current thread,
+/// 0 if there is none
+pub fn last_error_code() -> u32 {
+    with_last_error(|error| error.kind().numeric_code()).unwrap_or(0)
+}
+
+/// C version of [`last_error_code`]
+#[no_mangle]
+pub extern "C" fn curerr_last_error_code() -> u32 {
+    last_error_code()
+}
+
+/// drops the last error of the current thread
+#[no_mangle]
+pub extern "C" fn curerr_clear_last_error() {
+    drop(take_last_error());
+}
+
+/// copies the reason of the last error of the current thread into `buf` as a
+/// NUL-terminated string, the error stays set
+///
+/// Returns the length of the whole reason in bytes without the NUL, so a result of
+/// `len` or more means the copy was truncated. The reason is cut on a character
+/// boundary. Returns 0 and writes nothing when there is no last error.
+///
+/// # Safety
+/// `buf` must be valid for writes of `len` bytes, it may be null only if `len` is 0.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// set_last_error(CursedErrorHandle::new(
+///     CursedError::Connection(CursedErrorType::Refused),
+///     "peer refused £5 payment".to_string()
+/// ));
+///
+/// let mut buf = [0u8; 64];
+/// let len = unsafe { curerr_last_message(buf.as_mut_ptr().cast(), buf.len()) };
+/// assert_eq!(len, 24);
+/// assert_eq!(std::ffi::CStr::from_bytes_until_nul(&buf).unwrap().to_str(), Ok("peer refused £5 payment"));
+///
+/// let mut buf = [0xffu8; 15];
+/// let len = unsafe { curerr_last_message(buf.as_mut_ptr().cast(), buf.len()) };
+/// assert_eq!(len, 24);
+/// assert_eq!(std::ffi::CStr::from_bytes_until_nul(&buf).unwrap().to_str(), Ok("peer refused "));
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn curerr_last_message(buf: *mut std::ffi::c_char, len: usize) -> usize {
+    with_last_error(|error| {
+        let reason = error.reason;
+
+        if len > 0 {
+            let mut copied = reason.len().min(len - 1);
+            while !reason.is_char_boundary(copied) {
+                copied -= 1;
+            }
+
+            // SAFETY: the caller guarantees `buf` is valid for `len` bytes and `copied < len`
+            unsafe {
+                std::ptr::copy_nonoverlapping(reason.as_ptr(), buf.cast::<u8>(), copied);
+                *buf.add(copied) = 0;
+            }
+        }
+
+        reason.len()
+    }).unwrap_or(0)
+}
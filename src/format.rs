@@ -0,0 +1,139 @@
+//! versions of the Display and Debug output of handles, so log parsers can keep reading
+//! the old one while the new one is adopted
+
+use crate::*;
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+static DEFAULT_VERSION: AtomicU8 = AtomicU8::new(FormatVersion::V1 as u8);
+
+/// the Display and Debug output of a [`CursedErrorHandle`], selected per handle by
+/// [`CursedErrorHandle::with_format_version`] or for all others by
+/// [`set_default_format_version`]
+///
+/// Both versions stay byte for byte the same across releases, `tests/format_v1.txt` and
+/// `tests/format_v2.txt` pin the output of every kind. `V1` remains the default for at
+/// least one more release. Only the two impls follow the version, reports, compact and
+/// serialized forms don't change.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = |version| {
+///     let mut error = CursedErrorHandle::new(CursedError::Other(CursedErrorType::NotFound), "no \"users\" table".to_string())
+///         .with_op("query")
+///         .with_format_version(version);
+///     error.push_context("while migrating");
+///     error
+/// };
+///
+/// assert_eq!(error(FormatVersion::V1).to_string(), r#"query: not found error: "while migrating: no \"users\" table""#);
+/// assert_eq!(format!("{:?}", error(FormatVersion::V1)), r#"not found("no \"users\" table")"#);
+///
+/// assert_eq!(error(FormatVersion::V2).to_string(), r#"query: other not found: while migrating: no "users" table"#);
+/// assert_eq!(
+///     format!("{:?}", error(FormatVersion::V2)),
+///     r#"CursedErrorHandle { kind: Other(NotFound), reason: "no \"users\" table", op: "query", contexts: ["while migrating"] }"#
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FormatVersion {
+    /// `op: kind error: "contexts: reason"` with the reason quoted and escaped like a
+    /// Rust string, `Other` kinds without their category, and `kind("reason")` for Debug
+    #[default]
+    V1 = 1,
+    /// `op: kind: contexts: reason` with only backslashes and line breaks escaped, every
+    /// kind with its category, and Debug listing the kind, reason, op, namespace and
+    /// contexts that are set
+    V2 = 2,
+}
+
+/// the version of handles without their own [`CursedErrorHandle::with_format_version`],
+/// [`FormatVersion::V1`] unless changed
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "replica took too long".to_string());
+///
+/// set_default_format_version(FormatVersion::V2);
+/// assert_eq!(error.to_string(), "call timed out: replica took too long");
+///
+/// set_default_format_version(FormatVersion::V1);
+/// assert_eq!(error.to_string(), "call timed out error: \"replica took too long\"");
+/// ```
+pub fn set_default_format_version(version: FormatVersion) {
+    DEFAULT_VERSION.store(version as u8, Ordering::Relaxed);
+}
+
+pub(crate) fn default_format_version() -> FormatVersion {
+    match DEFAULT_VERSION.load(Ordering::Relaxed) {
+        2 => FormatVersion::V2,
+        _ => FormatVersion::V1,
+    }
+}
+
+impl CursedErrorHandle {
+    /// formats this handle in `version` regardless of [`set_default_format_version`]
+    pub fn with_format_version(mut self, version: FormatVersion) -> Self {
+        self.inner_mut().format_version = Some(version);
+        self
+    }
+    /// the version of the Display and Debug output of the handle
+    pub fn format_version(&self) -> FormatVersion {
+        self.inner().and_then(|inner| inner.format_version).unwrap_or_else(default_format_version)
+    }
+}
+
+/// the [`FormatVersion::V2`] Display output
+pub(crate) fn display_v2(error: &CursedErrorHandle, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if let Some(op) = error.op() {
+        write!(f, "{}: ", op)?;
+    }
+    match error.get_error() {
+        CursedError::Other(error_type) => write!(f, "other {}", error_type.to_str())?,
+        kind => write!(f, "{}", kind)?,
+    }
+
+    let reason = Some(error.get_reason()).filter(|reason| !reason.is_empty());
+    for part in error.contexts().iter().map(String::as_str).chain(reason) {
+        f.write_str(": ")?;
+        write_line(f, part)?;
+    }
+    Ok(())
+}
+
+/// the [`FormatVersion::V2`] Debug output
+pub(crate) fn debug_v2(error: &CursedErrorHandle, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let mut debug = f.debug_struct("CursedErrorHandle");
+    debug.field("kind", error.get_error()).field("reason", &error.get_reason());
+    if let Some(op) = error.op() {
+        debug.field("op", &op);
+    }
+    if let Some(namespace) = error.namespace() {
+        debug.field("namespace", &namespace);
+    }
+    if !error.contexts().is_empty() {
+        debug.field("contexts", &error.contexts());
+    }
+    debug.finish()
+}
+
+/// writes `text` with backslashes and line breaks escaped, so it stays on one line
+fn write_line(f: &mut std::fmt::Formatter<'_>, text: &str) -> std::fmt::Result {
+    let mut rest = text;
+    while let Some(index) = rest.find(['\\', '\n', '\r', '\t']) {
+        f.write_str(&rest[..index])?;
+        f.write_str(match rest.as_bytes()[index] {
+            b'\\' => "\\\\",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            _ => "\\t",
+        })?;
+        rest = &rest[index + 1..];
+    }
+    f.write_str(rest)
+}
@@ -0,0 +1,2872 @@
+//! the error handle and the data attached to it
+
+use crate::*;
+use crate::convert::file_error;
+use crate::dedup;
+use crate::intern::intern;
+use crate::format::{debug_v2, display_v2};
+use crate::scope;
+use crate::truncate::{max_reason_len, truncate};
+use crate::repr::HandleRepr;
+use crate::kind::{intern_name, ERROR_TYPES, TYPED_CATEGORIES};
+use std::io::ErrorKind;
+
+/// struct created for error handling
+/// 
+/// # Examples
+/// ```
+/// use curerr::*;
+/// 
+/// fn devide(a: i32, b: i32) -> Result<i32, CursedErrorHandle> {
+///     if b == 0 {
+///         return Err(CursedErrorHandle::new(
+///             CursedError::Input(CursedErrorType::Invalid),
+///             "0 division!!!".to_string()
+///         ))
+///     }
+/// 
+///     Ok(a/b)
+/// }
+/// 
+/// let result = devide(6, 3).expect("division error");
+/// 
+/// assert_eq!(result, 2)
+/// ```
+///
+/// The handle is a single pointer to its data, so `Result<T, CursedErrorHandle>` stays
+/// small. Clones are cheap, they share the same data until one of them is modified.
+///
+/// ```
+/// use curerr::*;
+/// use std::error::Error;
+///
+/// let error = CursedErrorHandle::new(
+///     CursedError::Call(CursedErrorType::Aborted),
+///     "worker crashed".to_string()
+/// ).with_source(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+///
+/// let mut clone = error.clone();
+/// assert!(std::ptr::eq(
+///     error.source().unwrap() as *const _ as *const u8,
+///     clone.source().unwrap() as *const _ as *const u8
+/// ));
+///
+/// clone.set_reason("worker restarted".to_string());
+/// assert_eq!(error.get_reason(), "worker crashed");
+/// assert_eq!(clone.get_reason(), "worker restarted");
+/// ```
+#[derive(Clone)]
+pub struct CursedErrorHandle {
+    repr: Repr,
+}
+
+#[derive(Clone)]
+enum Repr {
+    Shared(std::sync::Arc<Inner>),
+    Static(&'static StaticError),
+}
+
+// the handle is two words at most, so results stay small on the happy path
+const _: () = assert!(std::mem::size_of::<CursedErrorHandle>() <= std::mem::size_of::<usize>() * 2);
+const _: () = assert!(std::mem::size_of::<Option<CursedErrorHandle>>() == std::mem::size_of::<CursedErrorHandle>());
+#[cfg(target_pointer_width = "64")]
+const _: () = assert!(std::mem::size_of::<CursedResult<u64>>() <= std::mem::size_of::<usize>() * 2);
+
+/// the kind and reason of a [`CursedErrorHandle::new_static`] handle, built in const
+/// context so the handle only points to it
+pub struct StaticError {
+    error: CursedError,
+    reason: &'static str,
+}
+
+impl StaticError {
+    pub const fn new(error: CursedError, reason: &'static str) -> Self {
+        Self { error: error.normalized(), reason }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Inner {
+    pub(crate) error: CursedError,
+    pub(crate) reason: Reason,
+    /// converted from a bare [`CursedError`] and given no reason since, displayed without one
+    pub(crate) reasonless: bool,
+    pub(crate) source: Option<std::sync::Arc<dyn std::error::Error + Send + Sync>>,
+    pub(crate) os_code: Option<i32>,
+    pub(crate) contexts: Vec<String>,
+    pub(crate) op: Option<&'static str>,
+    pub(crate) namespace: Option<&'static str>,
+    pub(crate) fields: Vec<(std::borrow::Cow<'static, str>, Field)>,
+    pub(crate) help: Option<std::borrow::Cow<'static, str>>,
+    pub(crate) docs_url: Option<std::borrow::Cow<'static, str>>,
+    pub(crate) public_message: Option<std::borrow::Cow<'static, str>>,
+    pub(crate) exit_code: Option<u8>,
+    pub(crate) path: Option<std::path::PathBuf>,
+    pub(crate) peer: Option<PeerAddr>,
+    pub(crate) var_name: Option<String>,
+    pub(crate) command: Option<(std::ffi::OsString, Vec<std::ffi::OsString>)>,
+    pub(crate) timeout: Option<(std::time::Duration, Option<std::time::Duration>)>,
+    pub(crate) retry_after: Option<std::time::Duration>,
+    pub(crate) location: Option<&'static std::panic::Location<'static>>,
+    pub(crate) backtrace: Option<std::sync::Arc<std::backtrace::Backtrace>>,
+    pub(crate) repeats: u32,
+    pub(crate) format_version: Option<FormatVersion>,
+    pub(crate) trace: crate::trace::Trace,
+}
+
+/// reason of a shared handle, interned reasons point into the pool of [`crate::intern`]
+#[derive(Clone)]
+pub(crate) enum Reason {
+    Owned(std::borrow::Cow<'static, str>),
+    Interned(std::sync::Arc<str>),
+}
+
+impl std::ops::Deref for Reason {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Reason::Owned(reason) => reason,
+            Reason::Interned(reason) => reason,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum Field {
+    Plain(String),
+    Sensitive(String),
+}
+
+impl Field {
+    pub(crate) fn display(&self) -> std::borrow::Cow<'_, str> {
+        match self {
+            Field::Plain(value) => std::borrow::Cow::Borrowed(value),
+            Field::Sensitive(value) => std::borrow::Cow::Owned(Sensitive(value).to_string()),
+        }
+    }
+}
+
+impl Inner {
+    /// captures the location of the caller and a backtrace if enabled, see [`capture_backtraces`]
+    #[track_caller]
+    pub(crate) fn new(error: CursedError, reason: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        let mut inner = Self::empty(error);
+        inner.location = Some(std::panic::Location::caller());
+        if backtraces_enabled() {
+            inner.backtrace = Some(std::sync::Arc::new(std::backtrace::Backtrace::force_capture()));
+        }
+        inner.set_reason(reason.into());
+        inner
+    }
+    /// sets an op and namespace read from input, the ones that can't be kept as
+    /// `'static` names become `op` and `namespace` fields
+    pub(crate) fn set_read_names(&mut self, op: Option<&str>, namespace: Option<&str>) {
+        for (key, name, slot) in [("op", op, &mut self.op), ("namespace", namespace, &mut self.namespace)] {
+            let Some(name) = name else { continue };
+            match intern_name(name) {
+                Some(name) => *slot = Some(name),
+                None => self.fields.push((key.into(), Field::Plain(name.to_string()))),
+            }
+        }
+    }
+    /// data with only the kind, without a reason, location or backtrace
+    pub(crate) fn empty(error: CursedError) -> Self {
+        Self {
+            error: error.normalized(),
+            reason: Reason::Owned(std::borrow::Cow::Borrowed("")),
+            reasonless: false,
+            source: None,
+            os_code: None,
+            contexts: Vec::new(),
+            op: None,
+            namespace: None,
+            fields: Vec::new(),
+            help: None,
+            docs_url: None,
+            public_message: None,
+            exit_code: None,
+            path: None,
+            peer: None,
+            var_name: None,
+            command: None,
+            timeout: None,
+            retry_after: None,
+            location: None,
+            backtrace: None,
+            repeats: 0,
+            format_version: None,
+            trace: crate::trace::Trace::new(),
+        }
+    }
+    /// sets the reason cut to the [`set_max_reason_len`] cap, the `reason_len` field
+    /// has the length of a cut reason
+    pub(crate) fn set_reason(&mut self, reason: std::borrow::Cow<'static, str>) {
+        let (reason, len) = truncate(reason);
+        if !self.fields.is_empty() {
+            self.fields.retain(|(key, _)| key != "reason_len");
+        }
+        if let Some(len) = len {
+            self.fields.push(("reason_len".into(), Field::Plain(len.to_string())));
+        }
+        self.reason = Reason::Owned(reason);
+        self.reasonless = false;
+    }
+}
+
+/// result with [`CursedErrorHandle`] as the error
+pub type CursedResult<T> = Result<T, CursedErrorHandle>;
+
+impl CursedErrorHandle {
+    #[track_caller]
+    pub fn new(error: CursedError, reason: String) -> Self {
+        Self::from_inner(Inner::new(error, reason))
+    }
+    /// creates error with a reason from a global pool, so repeated errors with the same
+    /// reason share one allocation
+    ///
+    /// Meant for hot paths that create many errors with a few distinct reasons, like
+    /// "connection reset by peer" in a proxy. The pool is bounded, new reasons are not
+    /// added once it holds [`set_intern_capacity`] of them, see [`intern_stats`] for
+    /// tuning. It is split in shards and repeated reasons only take a read lock.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let reset = || CursedErrorHandle::new_interned(
+    ///     CursedError::Connection(CursedErrorType::Reset),
+    ///     "connection reset by peer"
+    /// );
+    /// let (first, second) = (reset(), reset());
+    ///
+    /// assert_eq!(first.get_reason(), "connection reset by peer");
+    /// assert!(std::ptr::eq(first.get_reason(), second.get_reason()));
+    ///
+    /// let stats = intern_stats();
+    /// assert_eq!((stats.size, stats.hits, stats.misses), (1, 1, 1));
+    /// assert_eq!(stats.hit_rate(), 0.5);
+    /// ```
+    #[track_caller]
+    pub fn new_interned(error: CursedError, reason: &str) -> Self {
+        let mut inner = Inner::new(error, "");
+        // a cut reason isn't worth sharing
+        match reason.len() > max_reason_len() {
+            true => inner.set_reason(reason.to_string().into()),
+            false => inner.reason = Reason::Interned(intern(reason)),
+        }
+        Self::from_inner(inner)
+    }
+    /// creates error in const context, e.g. for statics
+    ///
+    /// Display output is the same as for [`CursedErrorHandle::new`]. Static errors
+    /// have no source or OS code and, being created at compile time, are never
+    /// reported to the [error hook](set_error_hook). Outside of statics and consts the
+    /// [`StaticError`] is written in a `const` block.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// static SHUTDOWN: CursedErrorHandle = CursedErrorHandle::new_static(&StaticError::new(
+    ///     CursedError::Call(CursedErrorType::Aborted),
+    ///     "server is shutting down"
+    /// ));
+    ///
+    /// let threads: Vec<_> = (0..4)
+    ///     .map(|_| std::thread::spawn(|| SHUTDOWN.to_string()))
+    ///     .collect();
+    ///
+    /// for thread in threads {
+    ///     assert_eq!(thread.join().unwrap(), "call aborted error: \"server is shutting down\"");
+    /// }
+    ///
+    /// let dynamic = CursedErrorHandle::new(
+    ///     CursedError::Call(CursedErrorType::Aborted),
+    ///     "server is shutting down".to_string()
+    /// );
+    /// assert_eq!(SHUTDOWN.to_string(), dynamic.to_string());
+    ///
+    /// let queue = CursedErrorHandle::new_static(&const {
+    ///     StaticError::new(CursedError::Custom { category: "queue", kind: CursedErrorType::Custom("stalled") }, "no consumer")
+    /// });
+    /// assert_eq!(queue.code(), "QUEUE_STALLED");
+    /// ```
+    pub const fn new_static(error: &'static StaticError) -> Self {
+        Self { repr: Repr::Static(error) }
+    }
+    /// wraps fully built data and reports the new handle to the error hook
+    pub(crate) fn from_inner(mut inner: Inner) -> Self {
+        let scopes = scope::contexts();
+        if !scopes.is_empty() {
+            inner.contexts.splice(0..0, scopes);
+        }
+        let mut handle = Self { repr: Repr::Shared(std::sync::Arc::new(inner)) };
+        if dedup::enabled() {
+            handle.inner_mut().repeats = dedup::repeat_count(handle.fingerprint());
+        }
+        call_error_hook(&handle);
+        handle
+    }
+    /// wraps data copied from another handle, without scopes, dedup or the error hook
+    pub(crate) fn from_inner_copy(inner: Inner) -> Self {
+        Self { repr: Repr::Shared(std::sync::Arc::new(inner)) }
+    }
+    /// data of the handle, copied first if it is shared with clones or static
+    pub(crate) fn inner_mut(&mut self) -> &mut Inner {
+        if let Repr::Static(_) = self.repr {
+            let mut inner = Inner::new(*self.get_error(), self.static_reason());
+            inner.location = None;
+            inner.backtrace = None;
+            self.repr = Repr::Shared(std::sync::Arc::new(inner));
+        }
+
+        match &mut self.repr {
+            Repr::Shared(inner) => std::sync::Arc::make_mut(inner),
+            Repr::Static(_) => unreachable!("static handle was just converted"),
+        }
+    }
+    /// data of the handle, `None` for static handles
+    pub(crate) fn inner(&self) -> Option<&Inner> {
+        match &self.repr {
+            Repr::Shared(inner) => Some(inner),
+            Repr::Static(_) => None,
+        }
+    }
+    fn static_reason(&self) -> &'static str {
+        match self.repr {
+            Repr::Static(error) => error.reason,
+            Repr::Shared(_) => "",
+        }
+    }
+    /// attaches the error that caused this one, it is returned by [`std::error::Error::source`]
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::error::Error;
+    ///
+    /// let cause = CursedErrorHandle::new(
+    ///     CursedError::File(CursedErrorType::NotFound),
+    ///     "config.toml".to_string()
+    /// );
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Data(CursedErrorType::Invalid),
+    ///     "can't load config".to_string()
+    /// ).with_source(cause);
+    ///
+    /// assert_eq!(error.source().unwrap().to_string(), "file not found error: \"config.toml\"");
+    /// ```
+    pub fn with_source(mut self, source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        self.inner_mut().source = Some(std::sync::Arc::from(source.into()));
+        self
+    }
+    /// attaches a key-value field, fields are shown by [`CursedErrorHandle::report`]
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Refused),
+    ///     "login failed".to_string()
+    /// ).with_field("user", "admin").with_field_sensitive("password", "hunter2");
+    ///
+    /// let fields: Vec<_> = error.fields().collect();
+    /// assert_eq!(fields[0], ("user", "admin".into()));
+    /// assert_eq!(fields[1], ("password", "[REDACTED]".into()));
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "connection refused error: \"login failed\"\n  user = admin\n  password = [REDACTED]"
+    /// );
+    /// ```
+    pub fn with_field(mut self, key: impl Into<std::borrow::Cow<'static, str>>, value: impl std::fmt::Display) -> Self {
+        self.inner_mut().fields.push((key.into(), Field::Plain(value.to_string())));
+        self
+    }
+    /// attaches a key-value field whose value is displayed like [`Sensitive`]
+    pub fn with_field_sensitive(mut self, key: impl Into<std::borrow::Cow<'static, str>>, value: impl std::fmt::Display) -> Self {
+        self.inner_mut().fields.push((key.into(), Field::Sensitive(value.to_string())));
+        self
+    }
+    /// fields attached with [`CursedErrorHandle::with_field`] in the order they were attached,
+    /// sensitive values are redacted unless [`reveal_sensitive`] is enabled
+    ///
+    /// Reports and the serialized forms keep this order too, only
+    /// [`CursedErrorHandle::canonical_json`] sorts the fields by key and keeps the last of
+    /// fields with the same key.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, std::borrow::Cow<'_, str>)> {
+        self.inner()
+            .map(|inner| inner.fields.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.display()))
+    }
+    /// attaches a suggestion of what to do next, it is shown by [`CursedErrorHandle::report`]
+    /// but not by [`Display`](std::fmt::Display)
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::File(CursedErrorType::AlreadyExists),
+    ///     "out.txt".to_string()
+    /// ).help("try running with --force");
+    ///
+    /// assert_eq!(error.get_help(), Some("try running with --force"));
+    /// assert_eq!(error.to_string(), "file already exists error: \"out.txt\"");
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "file already exists error: \"out.txt\"\nhelp: try running with --force"
+    /// );
+    ///
+    /// let error = error.help("remove the file\nor choose another output");
+    ///
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "file already exists error: \"out.txt\"\n\
+    ///      help: remove the file\n      \
+    ///      or choose another output"
+    /// );
+    /// ```
+    pub fn help(mut self, help: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.inner_mut().help = Some(help.into());
+        self
+    }
+    pub fn get_help(&self) -> Option<&str> {
+        self.inner()?.help.as_deref()
+    }
+    /// link to the explanation of the error, the one set with [`CursedErrorHandle::with_docs_url`]
+    /// or the [code](CursedErrorHandle::code) appended to the base set with [`set_docs_base_url`]
+    ///
+    /// The link is the last line of [`CursedErrorHandle::report`].
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Refused),
+    ///     "db:5432".to_string()
+    /// );
+    /// assert_eq!(error.docs_url(), None);
+    ///
+    /// set_docs_base_url("https://docs.example.com/errors/");
+    /// assert_eq!(error.docs_url().unwrap(), "https://docs.example.com/errors/CONNECTION_REFUSED");
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "connection refused error: \"db:5432\"\n\
+    ///      docs: https://docs.example.com/errors/CONNECTION_REFUSED"
+    /// );
+    ///
+    /// let error = error.with_docs_url("https://db.example.com/errors/E42");
+    /// assert_eq!(error.docs_url().unwrap(), "https://db.example.com/errors/E42");
+    ///
+    /// clear_docs_base_url();
+    /// assert_eq!(error.docs_url().unwrap(), "https://db.example.com/errors/E42");
+    /// ```
+    pub fn docs_url(&self) -> Option<String> {
+        if let Some(url) = self.inner().and_then(|inner| inner.docs_url.as_deref()) {
+            return Some(url.to_string());
+        }
+
+        let base = (*DOCS_BASE_URL.read().unwrap_or_else(|poisoned| poisoned.into_inner()))?;
+        Some(format!("{}/{}", base.trim_end_matches('/'), self.code()))
+    }
+    /// overrides the link returned by [`CursedErrorHandle::docs_url`]
+    pub fn with_docs_url(mut self, url: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.inner_mut().docs_url = Some(url.into());
+        self
+    }
+    /// message that is safe to show to users of a service, unlike the reason that may
+    /// contain internal details
+    pub fn with_public_message(mut self, message: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.inner_mut().public_message = Some(message.into());
+        self
+    }
+    pub fn get_public_message(&self) -> Option<&str> {
+        self.inner()?.public_message.as_deref()
+    }
+    /// public message, falling back to the reason for client errors and to `generic`
+    /// for server errors, which may leak internal details
+    #[cfg(any(feature = "axum", feature = "tonic"))]
+    pub(crate) fn safe_message<'a>(&'a self, generic: &'a str) -> &'a str {
+        match self.get_public_message() {
+            Some(message) => message,
+            None if self.get_error().http_status() >= 500 => generic,
+            None => self.get_reason(),
+        }
+    }
+    /// process exit code for the error, the one set with [`CursedErrorHandle::with_exit_code`]
+    /// or [`CursedError::exit_code`]
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::process::ExitCode;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Envvar(CursedErrorType::NotFound),
+    ///     "HOME".to_string()
+    /// );
+    /// assert_eq!(error.exit_code(), 78);
+    ///
+    /// let error = error.with_exit_code(3);
+    /// assert_eq!(error.exit_code(), 3);
+    /// # #[cfg(feature = "std-conversions")]
+    /// assert_eq!(ExitCode::from(error), ExitCode::from(3));
+    /// ```
+    pub fn exit_code(&self) -> u8 {
+        match self.inner().and_then(|inner| inner.exit_code) {
+            Some(exit_code) => exit_code,
+            None => self.get_error().exit_code(),
+        }
+    }
+    /// overrides the code returned by [`CursedErrorHandle::exit_code`]
+    pub fn with_exit_code(mut self, exit_code: u8) -> Self {
+        self.inner_mut().exit_code = Some(exit_code);
+        self
+    }
+    /// where the error was created, `None` for static handles
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let (error, line) = (CursedErrorHandle::new(CursedError::Unknown(None), "oops".to_string()), line!());
+    ///
+    /// assert_eq!(error.location().unwrap().line(), line);
+    /// assert_eq!(error.location().unwrap().file(), file!());
+    /// ```
+    pub fn location(&self) -> Option<&'static std::panic::Location<'static>> {
+        self.inner()?.location
+    }
+    /// backtrace captured when the error was created, only if enabled by `RUST_BACKTRACE`,
+    /// `RUST_LIB_BACKTRACE` or [`capture_backtraces`]
+    pub fn backtrace(&self) -> Option<&std::backtrace::Backtrace> {
+        self.inner()?.backtrace.as_deref()
+    }
+    /// whether the error repeats the previous error of the thread, see [`set_dedup_window`]
+    pub fn is_duplicate(&self) -> bool {
+        self.repeat_count() > 0
+    }
+    /// number of identical errors created right before this one on the thread, `0`
+    /// unless [`set_dedup_window`] is enabled
+    pub fn repeat_count(&self) -> u32 {
+        self.inner().map_or(0, |inner| inner.repeats)
+    }
+    /// kind of the error, a copy of [`CursedErrorHandle::get_error`]
+    pub fn kind(&self) -> CursedError {
+        *self.get_error()
+    }
+    /// whether both errors have the same category and type, the reasons are ignored
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let handle = |error, reason: &str| CursedErrorHandle::new(error, reason.to_string());
+    /// let queue = |kind| CursedError::Custom { category: "queue", kind };
+    ///
+    /// let refused = handle(CursedError::Connection(CursedErrorType::Refused), "db is down");
+    ///
+    /// assert!(refused.same_kind(&handle(CursedError::Connection(CursedErrorType::Refused), "cache is down")));
+    /// assert!(!refused.same_kind(&handle(CursedError::Connection(CursedErrorType::Reset), "db is down")));
+    /// assert!(handle(CursedError::NoError, "a").same_kind(&handle(CursedError::NoError, "b")));
+    /// assert!(handle(CursedError::Unknown(None), "a").same_kind(&handle(CursedError::Unknown(None), "b")));
+    /// assert!(!handle(CursedError::Unknown(None), "a").same_kind(&handle(CursedError::NoError, "a")));
+    ///
+    /// let name = String::from("queue");
+    /// let parsed: CursedError = format!("{} timed out", name).parse().unwrap();
+    ///
+    /// assert!(handle(queue(CursedErrorType::Timedout), "a").same_kind(&handle(parsed, "b")));
+    /// assert!(!handle(queue(CursedErrorType::Timedout), "a").same_kind(&handle(queue(CursedErrorType::Reset), "a")));
+    /// ```
+    pub fn same_kind(&self, other: &Self) -> bool {
+        self.get_error() == other.get_error()
+    }
+    pub fn get_error(&self) -> &CursedError {
+        match self.repr {
+            Repr::Shared(ref inner) => &inner.error,
+            Repr::Static(error) => &error.error,
+        }
+    }
+    pub fn get_reason(&self) -> &str {
+        match self.inner() {
+            Some(inner) => &inner.reason,
+            None => self.static_reason(),
+        }
+    }
+    pub fn set_reason(&mut self, reason: String) {
+        self.inner_mut().set_reason(reason.into());
+    }
+    /// adds a layer of context, e.g. `"while loading config"`, without wrapping the error
+    ///
+    /// Contexts are displayed before the reason, the last pushed one first.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let mut error = CursedErrorHandle::new(
+    ///     CursedError::File(CursedErrorType::NotFound),
+    ///     "config.toml is missing".to_string()
+    /// );
+    /// error.push_context("while reading config");
+    /// error.push_context("while loading settings");
+    /// error.push_context("while starting server");
+    ///
+    /// assert_eq!(error.contexts(), ["while starting server", "while loading settings", "while reading config"]);
+    /// assert_eq!(error.get_reason(), "config.toml is missing");
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "file not found error: \"while starting server: while loading settings: \
+    ///      while reading config: config.toml is missing\""
+    /// );
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "file not found error: \"config.toml is missing\"\n  \
+    ///      context: while starting server\n  \
+    ///      context: while loading settings\n  \
+    ///      context: while reading config"
+    /// );
+    /// ```
+    #[track_caller]
+    pub fn push_context(&mut self, context: impl Into<String>) {
+        let inner = self.inner_mut();
+        inner.contexts.insert(0, context.into());
+        inner.push_trace();
+    }
+    /// contexts added by [`CursedErrorHandle::push_context`], the outermost first
+    pub fn contexts(&self) -> &[String] {
+        match self.inner() {
+            Some(inner) => &inner.contexts,
+            None => &[],
+        }
+    }
+    /// tags the error with the name of the failed operation, e.g. `"load_config"`
+    ///
+    /// The name is shown before the error in Display and as its own line in the report.
+    /// Each call replaces the previous name, so when several layers tag the same error
+    /// the outermost one is kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Refused),
+    ///     "upstream is down".to_string()
+    /// ).with_op("dial_upstream");
+    ///
+    /// assert_eq!(error.op(), Some("dial_upstream"));
+    /// assert_eq!(error.to_string(), "dial_upstream: connection refused error: \"upstream is down\"");
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "connection refused error: \"upstream is down\"\n  op: dial_upstream"
+    /// );
+    ///
+    /// let error = error.with_op("load_config");
+    /// assert_eq!(error.op(), Some("load_config"));
+    /// ```
+    #[track_caller]
+    pub fn with_op(mut self, op: &'static str) -> Self {
+        let inner = self.inner_mut();
+        inner.op = Some(op);
+        inner.push_trace();
+        self
+    }
+    /// name set by [`CursedErrorHandle::with_op`]
+    pub fn op(&self) -> Option<&'static str> {
+        self.inner()?.op
+    }
+    /// tags the error with the crate or subsystem that produced it, e.g. `"cache"`
+    ///
+    /// The namespace is shown in brackets before the error in the report, also for
+    /// sources. An error keeps the first namespace it was tagged with, so when the layers
+    /// above tag it with theirs the innermost one is kept. [`cursed_ns!`] defines a
+    /// constructor that tags every error of a module.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Refused),
+    ///     "redis is down".to_string()
+    /// ).with_namespace("cache");
+    ///
+    /// let error = Err::<(), _>(error).context("while fetching the page").unwrap_err().with_namespace("fetcher");
+    ///
+    /// assert_eq!(error.namespace(), Some("cache"));
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "[cache] connection refused error: \"redis is down\"\n  \
+    ///      context: while fetching the page"
+    /// );
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::Call(CursedErrorType::Aborted), "no page".to_string())
+    ///     .with_namespace("fetcher")
+    ///     .with_source(error);
+    ///
+    /// assert_eq!(error.namespace(), Some("fetcher"));
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "[fetcher] call aborted error: \"no page\"\n\
+    ///      caused by: [cache] connection refused error: \"while fetching the page: redis is down\""
+    /// );
+    /// ```
+    pub fn with_namespace(mut self, namespace: &'static str) -> Self {
+        self.inner_mut().namespace.get_or_insert(namespace);
+        self
+    }
+    /// namespace set by [`CursedErrorHandle::with_namespace`]
+    pub fn namespace(&self) -> Option<&'static str> {
+        self.inner()?.namespace
+    }
+    /// replaces the kind of the error with the one returned by `f`, the reason, source,
+    /// fields and location stay the same
+    ///
+    /// With [`keep_original_kind`] enabled a changed kind is recorded in the
+    /// `original_kind` field, changing it again keeps the first recorded kind.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::error::Error;
+    ///
+    /// let storage_error = CursedErrorHandle::new(
+    ///     CursedError::File(CursedErrorType::NotFound),
+    ///     "no row for user 7".to_string()
+    /// ).with_source(std::fmt::Error).with_field("table", "users");
+    /// let location = storage_error.location();
+    ///
+    /// let error = storage_error.map_kind(|kind| match kind {
+    ///     CursedError::File(error_type) | CursedError::Path(error_type) => CursedError::Data(error_type),
+    ///     kind => kind,
+    /// });
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::NotFound));
+    /// assert_eq!(error.get_reason(), "no row for user 7");
+    /// assert!(error.source().unwrap().is::<std::fmt::Error>());
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("table", "users".into())]);
+    /// assert_eq!(error.location(), location);
+    ///
+    /// keep_original_kind(true);
+    /// let error = error
+    ///     .recategorize(CursedError::Input(CursedErrorType::Invalid))
+    ///     .recategorize(CursedError::Call(CursedErrorType::Aborted));
+    /// keep_original_kind(false);
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Aborted));
+    /// assert_eq!(
+    ///     error.fields().collect::<Vec<_>>(),
+    ///     [("table", "users".into()), ("original_kind", "data not found".into())]
+    /// );
+    /// ```
+    pub fn map_kind(mut self, f: impl FnOnce(CursedError) -> CursedError) -> Self {
+        let original = *self.get_error();
+        let error = f(original).normalized();
+        if error == original {
+            return self
+        }
+
+        let inner = self.inner_mut();
+        inner.error = error;
+        if KEEP_ORIGINAL_KIND.load(std::sync::atomic::Ordering::Relaxed)
+            && inner.fields.iter().all(|(key, _)| key != "original_kind") {
+            inner.fields.push(("original_kind".into(), Field::Plain(original.to_string())));
+        }
+        self
+    }
+    /// replaces the kind of the error, see [`CursedErrorHandle::map_kind`]
+    pub fn recategorize(self, error: CursedError) -> Self {
+        self.map_kind(|_| error)
+    }
+    /// machine readable code of the error, see [`CursedError::code`]
+    pub fn code(&self) -> std::borrow::Cow<'static, str> {
+        self.get_error().code()
+    }
+    /// only the classification, the [code](CursedErrorHandle::code) in lowercase like
+    /// `connection_refused`, e.g. for metric labels
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "redis".to_string());
+    /// assert_eq!(error.display_kind().to_string(), "connection_refused");
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::Call(CursedErrorType::Custom("rate limited")), String::new());
+    /// assert_eq!(error.display_kind().to_string(), "call_rate_limited");
+    /// assert_eq!(CursedErrorHandle::new(CursedError::NoError, String::new()).display_kind().to_string(), "no_error");
+    /// ```
+    pub fn display_kind(&self) -> impl std::fmt::Display + '_ {
+        DisplayKind(self)
+    }
+    /// only the contexts and the reason, like between the quotes of the
+    /// [`Display`](std::fmt::Display) output but not escaped
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let mut error = CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), "no \"app.toml\"".to_string());
+    /// assert_eq!(error.display_reason().to_string(), "no \"app.toml\"");
+    ///
+    /// error.push_context("while starting");
+    /// assert_eq!(error.display_reason().to_string(), "while starting: no \"app.toml\"");
+    /// ```
+    pub fn display_reason(&self) -> impl std::fmt::Display + '_ {
+        DisplayReason(self)
+    }
+    /// the code and the reason on one line, the reason cut to at most `max_len` bytes
+    /// with `…` appended when it is longer
+    ///
+    /// The reason is cut before the character that doesn't fit, line breaks are written
+    /// as spaces.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Refused),
+    ///     "can't reach redis\nat 10.0.0.7".to_string()
+    /// );
+    ///
+    /// assert_eq!(error.display_compact(64).to_string(), "CONNECTION_REFUSED: can't reach redis at 10.0.0.7");
+    /// assert_eq!(error.display_compact(11).to_string(), "CONNECTION_REFUSED: can't reach…");
+    /// assert_eq!(error.display_compact(0).to_string(), "CONNECTION_REFUSED: …");
+    ///
+    /// // "ü" is two bytes, the third byte would split it
+    /// let error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), "grüße".to_string());
+    ///
+    /// assert_eq!(error.display_compact(3).to_string(), "DATA_INVALID: gr…");
+    /// assert_eq!(error.display_compact(4).to_string(), "DATA_INVALID: grü…");
+    /// assert_eq!(error.display_compact(7).to_string(), "DATA_INVALID: grüße");
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), "🦀🦀".to_string());
+    /// assert_eq!(error.display_compact(7).to_string(), "DATA_INVALID: 🦀…");
+    /// ```
+    pub fn display_compact(&self, max_len: usize) -> impl std::fmt::Display + '_ {
+        DisplayCompact { handle: self, max_len }
+    }
+    /// raw OS error code (errno, Win32 error) of the io error this handle was converted from
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// # #[cfg(feature = "std-conversions")] {
+    /// let error: CursedErrorHandle = std::io::Error::from_raw_os_error(13).into();
+    /// assert_eq!(error.os_code(), Some(13));
+    /// # }
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::Unknown(None), "oops".to_string());
+    /// assert_eq!(error.os_code(), None);
+    /// ```
+    pub fn os_code(&self) -> Option<i32> {
+        self.inner()?.os_code
+    }
+    /// best effort [`std::io::ErrorKind`] of the error
+    ///
+    /// The kind is exact when an [`std::io::Error`] is found in the source chain or an
+    /// [OS code](CursedErrorHandle::os_code) is present. Otherwise it is approximated by
+    /// the reverse mapping of the error kind, and `None` is returned for
+    /// [`CursedError::NoError`] and [`CursedError::Unknown`].
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Data(CursedErrorType::Invalid),
+    ///     "can't read config".to_string()
+    /// ).with_source(Error::from(ErrorKind::WouldBlock));
+    /// assert_eq!(error.io_kind(), Some(ErrorKind::WouldBlock));
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::File(CursedErrorType::NotFound),
+    ///     "config.toml".to_string()
+    /// );
+    /// assert_eq!(error.io_kind(), Some(ErrorKind::NotFound));
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::Unknown(None), "oops".to_string());
+    /// assert_eq!(error.io_kind(), None);
+    /// ```
+    pub fn io_kind(&self) -> Option<ErrorKind> {
+        let mut source = std::error::Error::source(self);
+        while let Some(error) = source {
+            if let Some(error) = error.downcast_ref::<std::io::Error>() {
+                return Some(error.kind())
+            }
+            source = error.source();
+        }
+
+        if let Some(os_code) = self.os_code() {
+            return Some(std::io::Error::from_raw_os_error(os_code).kind())
+        }
+
+        match *self.get_error() {
+            CursedError::NoError | CursedError::Unknown(None) => None,
+            error => Some(ErrorKind::from(error)),
+        }
+    }
+    /// first error of type `E` in the source chain
+    ///
+    /// Handles converted from an [`std::io::Error`] keep it as their source, so the
+    /// original with its kind, OS code and inner error can be inspected after the
+    /// conversion.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// # #[cfg(feature = "std-conversions")] {
+    /// let error: CursedErrorHandle = std::io::Error::from_raw_os_error(13).into();
+    /// let error = CursedErrorHandle::new(CursedError::File(CursedErrorType::AccessDenied), "can't save".to_string())
+    ///     .with_source(error);
+    ///
+    /// let io = error.find_source::<std::io::Error>().unwrap();
+    /// assert_eq!(io.kind(), std::io::ErrorKind::PermissionDenied);
+    /// assert_eq!(io.raw_os_error(), Some(13));
+    ///
+    /// let error: CursedErrorHandle = std::io::Error::other(std::fmt::Error).into();
+    /// let inner = error.find_source::<std::io::Error>().unwrap().get_ref().unwrap();
+    /// assert!(inner.is::<std::fmt::Error>());
+    ///
+    /// assert!(error.find_source::<std::num::ParseIntError>().is_none());
+    /// # }
+    /// ```
+    pub fn find_source<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        let mut source = std::error::Error::source(self);
+        while let Some(error) = source {
+            if let Some(error) = error.downcast_ref::<E>() {
+                return Some(error)
+            }
+            source = error.source();
+        }
+        None
+    }
+    /// gives back the [`std::io::Error`] the handle was converted from, or one built from
+    /// the handle
+    ///
+    /// The original is returned as is when it is the direct source of the handle, neither
+    /// the handle nor the source are shared with a clone and the handle carries nothing
+    /// beyond it: no contexts, fields or other details and the reason of the conversion.
+    /// Such handles with only an [OS code](CursedErrorHandle::os_code) become
+    /// [`std::io::Error::from_raw_os_error`]. The others are kept as the inner error of an
+    /// io error of their [io kind](CursedErrorHandle::io_kind), [`CursedError::NoError`]
+    /// and `Unknown(None)` have no io kind and are returned as the error.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// # #[cfg(feature = "std-conversions")] {
+    /// let error: CursedErrorHandle = Error::new(ErrorKind::WouldBlock, "socket is busy").into();
+    ///
+    /// let original = error.into_io().unwrap();
+    /// assert_eq!(original.kind(), ErrorKind::WouldBlock);
+    /// assert_eq!(original.to_string(), "socket is busy");
+    ///
+    /// // the field would be lost with the original, the handle is kept inside
+    /// let error: CursedErrorHandle = Error::new(ErrorKind::WouldBlock, "socket is busy").into();
+    /// let wrapped = error.with_field("socket", 3).into_io().unwrap();
+    /// assert_eq!(wrapped.kind(), ErrorKind::WouldBlock);
+    /// let handle = wrapped.get_ref().unwrap().downcast_ref::<CursedErrorHandle>().unwrap();
+    /// assert_eq!(handle.fields().collect::<Vec<_>>(), [("socket", "3".into())]);
+    ///
+    /// // a clone still holds the source, the error is built from the handle
+    /// let error: CursedErrorHandle = Error::from_raw_os_error(2).into();
+    /// let clone = error.clone();
+    /// let rebuilt = error.into_io().unwrap();
+    /// assert_eq!(rebuilt.raw_os_error(), Some(2));
+    /// assert!(clone.into_io().is_ok());
+    /// # }
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), "config.toml".to_string());
+    /// let rebuilt = error.into_io().unwrap();
+    /// assert_eq!(rebuilt.kind(), ErrorKind::NotFound);
+    /// assert_eq!(rebuilt.to_string(), "file not found error: \"config.toml\"");
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::Unknown(None), "oops".to_string());
+    /// assert_eq!(error.into_io().unwrap_err().get_reason(), "oops");
+    /// ```
+    pub fn into_io(self) -> Result<std::io::Error, Self> {
+        if !self.carries_only_io() {
+            return match self.io_kind() {
+                Some(kind) => Ok(std::io::Error::new(kind, self)),
+                None => Err(self),
+            }
+        }
+        let handle = match self.take_io_source() {
+            Ok(error) => return Ok(error),
+            Err(handle) => handle,
+        };
+
+        if let Some(os_code) = handle.os_code() {
+            return Ok(std::io::Error::from_raw_os_error(os_code))
+        }
+        match *handle.get_error() {
+            CursedError::NoError | CursedError::Unknown(None) => Err(handle),
+            error => Ok(std::io::Error::new(ErrorKind::from(error), handle)),
+        }
+    }
+    /// whether a handle converted from an io error holds nothing an io error rebuilt from
+    /// it would lose, handles of other errors are kept whole by [`CursedErrorHandle::into_io`]
+    fn carries_only_io(&self) -> bool {
+        let Some(inner) = self.inner() else {
+            return true
+        };
+        let message = match (&inner.source, inner.os_code) {
+            (Some(source), _) if source.is::<std::io::Error>() => source.to_string(),
+            (_, Some(os_code)) => std::io::Error::from_raw_os_error(os_code).to_string(),
+            _ => return true,
+        };
+
+        *inner.reason == message
+            && inner.contexts.is_empty()
+            && inner.fields.is_empty()
+            && inner.op.is_none()
+            && inner.namespace.is_none()
+            && inner.help.is_none()
+            && inner.docs_url.is_none()
+            && inner.public_message.is_none()
+            && inner.exit_code.is_none()
+            && inner.path.is_none()
+            && inner.peer.is_none()
+            && inner.var_name.is_none()
+            && inner.command.is_none()
+            && inner.timeout.is_none()
+            && inner.retry_after.is_none()
+    }
+    fn take_io_source(self) -> Result<std::io::Error, Self> {
+        let Repr::Shared(inner) = self.repr else {
+            return Err(self)
+        };
+        let mut inner = match std::sync::Arc::try_unwrap(inner) {
+            Ok(inner) => inner,
+            Err(inner) => return Err(Self { repr: Repr::Shared(inner) }),
+        };
+
+        match inner.source.take() {
+            Some(source) if source.is::<std::io::Error>() => {
+                // SAFETY: the source was just checked to be an `io::Error`, so the data
+                // pointer of the `Arc` points to one
+                let source = unsafe { std::sync::Arc::from_raw(std::sync::Arc::into_raw(source).cast::<std::io::Error>()) };
+                match std::sync::Arc::try_unwrap(source) {
+                    Ok(error) => return Ok(error),
+                    Err(source) => inner.source = Some(source),
+                }
+            },
+            source => inner.source = source,
+        }
+        Err(Self { repr: Repr::Shared(std::sync::Arc::new(inner)) })
+    }
+    /// the source chain without io errors whose message is the reason of the handle
+    /// before them, like the one a handle was converted from
+    pub(crate) fn reported_sources(&self) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+        let mut previous: &(dyn std::error::Error + 'static) = self;
+        std::iter::successors(std::error::Error::source(self), |error| error.source()).filter(move |error| {
+            let repeated = error.is::<std::io::Error>()
+                && previous.downcast_ref::<CursedErrorHandle>().is_some_and(|handle| handle.get_reason() == error.to_string());
+            previous = *error;
+            !repeated
+        })
+    }
+    /// multi-line human readable description with details and the chain of sources
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// # #[cfg(feature = "std-conversions")] {
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Data(CursedErrorType::Invalid),
+    ///     "can't load config".to_string()
+    /// ).with_source(CursedErrorHandle::from(std::io::Error::from_raw_os_error(2)));
+    ///
+    /// # #[cfg(unix)]
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "data invalid error: \"can't load config\"\n\
+    ///      caused by: io not found error: \"No such file or directory (os error 2)\""
+    /// );
+    ///
+    /// let error = CursedErrorHandle::from(std::io::Error::from_raw_os_error(2));
+    ///
+    /// # #[cfg(unix)]
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "io not found error: \"No such file or directory (os error 2)\"\n  os error: 2"
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// Reasons are not escaped, their continuation lines are indented by two spaces.
+    ///
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::File(CursedErrorType::Invalid),
+    ///     "expected \"name\" field\ngot: 3".to_string()
+    /// ).with_field("line", 4);
+    ///
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "file invalid error: \"expected \"name\" field\n  \
+    ///        got: 3\"\n  \
+    ///      line = 4"
+    /// );
+    /// ```
+    pub fn report(&self) -> CursedReport<'_> {
+        CursedReport { handle: self, redact_patterns: &[] }
+    }
+
+    /// reason with volatile parts replaced by placeholders, used by [`CursedErrorHandle::fingerprint`]
+    ///
+    /// Runs of digits become `<n>`. Words made only of hex digits that mix digits and
+    /// letters and are at least 4 characters long, or are prefixed by `0x`, become `<hex>`.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Reset),
+    ///     "peer 10.0.0.5:443 reset request 9f3a2c1b at 0x7ffd".to_string()
+    /// );
+    ///
+    /// assert_eq!(error.normalized_reason(), "peer <n>.<n>.<n>.<n>:<n> reset request <hex> at <hex>");
+    /// ```
+    pub fn normalized_reason(&self) -> String {
+        normalize_volatile(self.get_reason())
+    }
+
+    /// stable hash of the [canonical JSON](CursedErrorHandle::canonical_json) of the error
+    /// with its volatile parts left out
+    ///
+    /// This is 64 bit FNV-1a over the canonical JSON with the reason and the field values
+    /// replaced by their [normalized](CursedErrorHandle::normalized_reason) forms and
+    /// without the retry hint. Errors that only differ in the order of their fields or in
+    /// the numbers and ids of their text get the same fingerprint. It doesn't depend on the
+    /// process, platform or crate build, so it can be stored and compared across runs.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let first = CursedErrorHandle::new(
+    ///     CursedError::Call(CursedErrorType::Timedout),
+    ///     "request 1234 timed out".to_string()
+    /// ).with_field("shard", 3).with_field("host", "db-1");
+    /// let second = CursedErrorHandle::new(
+    ///     CursedError::Call(CursedErrorType::Timedout),
+    ///     "request 98 timed out".to_string()
+    /// ).with_field("host", "db-2").with_field("shard", 11);
+    /// let other = CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Timedout),
+    ///     "request 1234 timed out".to_string()
+    /// ).with_field("shard", 3).with_field("host", "db-1");
+    ///
+    /// assert_eq!(first.fingerprint(), second.fingerprint());
+    /// assert_ne!(first.fingerprint(), other.fingerprint());
+    /// assert_ne!(first.fingerprint(), first.clone().with_field("replica", true).fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        fingerprint(HandleRepr::new(self))
+    }
+
+    /// single line JSON of the error that only depends on its content, e.g. to diff logs
+    /// or to compare errors in snapshot tests
+    ///
+    /// This is the `serde` form with every key sorted, including the fields, and of fields
+    /// with the same key only the last one attached. The keys are `category`, `code`,
+    /// `reason`, `type` and, when set, `custom`, `fields`, `io_kind`, `namespace`, `op`,
+    /// `os_code` and `retry_after_ms`. Sensitive values are always redacted and sources are
+    /// left out, like the contexts, which are not part of the reason.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let first = CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "lookup timed out".to_string())
+    ///     .with_field("shard", 7)
+    ///     .with_field("host", "db-1")
+    ///     .with_field_sensitive("token", "hunter2");
+    /// let second = CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "lookup timed out".to_string())
+    ///     .with_field_sensitive("token", "hunter2")
+    ///     .with_field("host", "db-0")
+    ///     .with_field("host", "db-1")
+    ///     .with_field("shard", 7);
+    ///
+    /// assert_eq!(first.canonical_json(), second.canonical_json());
+    /// assert_eq!(
+    ///     first.canonical_json(),
+    ///     r#"{"category":"call","code":"CALL_TIMED_OUT","fields":{"host":"db-1","shard":"7","token":"[REDACTED]"},"reason":"lookup timed out","type":"timed_out"}"#
+    /// );
+    ///
+    /// let error = CursedErrorHandle::new(CursedError::File(CursedErrorType::Invalid), "bad \"name\"\n\tat 3".to_string());
+    /// assert_eq!(
+    ///     error.canonical_json(),
+    ///     r#"{"category":"file","code":"FILE_INVALID","reason":"bad \"name\"\n\tat 3","type":"invalid"}"#
+    /// );
+    /// ```
+    pub fn canonical_json(&self) -> String {
+        HandleRepr::new(self).to_canonical_json()
+    }
+
+    /// version byte written by [`CursedErrorHandle::encode`]
+    pub const ENCODING_VERSION: u8 = 1;
+
+    /// appends a compact binary form of the error to `buf`, read back by [`CursedErrorHandle::decode`]
+    ///
+    /// The record is the [`CursedErrorHandle::ENCODING_VERSION`] byte, the
+    /// [`CursedError::numeric_code`] as a LEB128 varint, the names of a custom category
+    /// and a custom type when the code says there are any, and the reason. Names and
+    /// the reason are UTF-8 prefixed with their length as a varint.
+    ///
+    /// The source chain is flattened into the reason together with the contexts, like
+    /// `"while starting: config.toml is missing: No such file or directory"`. Fields,
+    /// help, os codes and other attached data are not encoded, see [`NormalizedError`].
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let mut error = CursedErrorHandle::new(
+    ///     CursedError::File(CursedErrorType::NotFound),
+    ///     "config.toml is missing".to_string()
+    /// ).with_source(std::io::Error::other("no such file"));
+    /// error.push_context("while starting");
+    ///
+    /// let mut buf = Vec::new();
+    /// error.encode(&mut buf);
+    ///
+    /// // version 1, code 809 as a varint
+    /// assert_eq!(buf[..3], [CursedErrorHandle::ENCODING_VERSION, 0xa9, 0x06]);
+    ///
+    /// let decoded = CursedErrorHandle::decode(&buf).unwrap();
+    ///
+    /// assert_eq!(decoded.get_error(), error.get_error());
+    /// assert_eq!(decoded.get_reason(), "while starting: config.toml is missing: no such file");
+    ///
+    /// let custom = CursedErrorHandle::new(
+    ///     CursedError::Custom { category: "queue", kind: CursedErrorType::Custom("deadlocked") },
+    ///     "two writers".to_string()
+    /// );
+    /// let mut buf = Vec::new();
+    /// custom.encode(&mut buf);
+    ///
+    /// assert_eq!(CursedErrorHandle::decode(&buf).unwrap().get_error(), custom.get_error());
+    /// ```
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        let error = self.get_error();
+
+        buf.push(Self::ENCODING_VERSION);
+        write_varint(buf, error.numeric_code() as u64);
+        if let CursedError::Custom { category, .. } = error {
+            write_encoded_str(buf, category);
+        }
+        if let Some(CursedErrorType::Custom(name)) = error.get_type() {
+            write_encoded_str(buf, name);
+        }
+
+        let mut reason = String::new();
+        for context in self.contexts() {
+            reason += context;
+            reason += ": ";
+        }
+        reason += self.get_reason();
+
+        for error in self.reported_sources() {
+            reason += &format!(": {}", error);
+        }
+        write_encoded_str(buf, &reason);
+    }
+    /// reads an error written by [`CursedErrorHandle::encode`], bytes after the record are ignored
+    ///
+    /// Codes this version of the crate doesn't know decode to [`CursedError::Unknown`] with
+    /// the code kept in the `unknown_code` field, truncated buffers fail with `Data(NotEnough)` and records of a newer version with
+    /// `Data(NotSupported)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let mut buf = Vec::new();
+    /// CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Refused),
+    ///     "upstream is down".to_string()
+    /// ).encode(&mut buf);
+    ///
+    /// for len in 0..buf.len() {
+    ///     let error = CursedErrorHandle::decode(&buf[..len]).unwrap_err();
+    ///     assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::NotEnough));
+    /// }
+    ///
+    /// let mut future = buf.clone();
+    /// future[0] = CursedErrorHandle::ENCODING_VERSION + 1;
+    /// let error = CursedErrorHandle::decode(&future).unwrap_err();
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Data(CursedErrorType::NotSupported));
+    /// assert_eq!(error.get_reason(), "encoding version 2 is not supported");
+    ///
+    /// // a category added by a later version
+    /// let unknown = [CursedErrorHandle::ENCODING_VERSION, 0xdd, 0x0b, 2, b'h', b'i'];
+    /// let error = CursedErrorHandle::decode(&unknown).unwrap();
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Unknown(None));
+    /// assert_eq!(error.get_reason(), "hi");
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("unknown_code", "1501".into())]);
+    /// ```
+    pub fn decode(mut bytes: &[u8]) -> CursedResult<Self> {
+        let truncated = || CursedErrorHandle::new(
+            CursedError::Data(CursedErrorType::NotEnough),
+            "encoded error is truncated".to_string()
+        );
+
+        let (&version, rest) = bytes.split_first().ok_or_else(truncated)?;
+        if version != Self::ENCODING_VERSION {
+            return Err(CursedErrorHandle::new(
+                CursedError::Data(CursedErrorType::NotSupported),
+                format!("encoding version {} is not supported", version)
+            ))
+        }
+        bytes = rest;
+
+        let code = read_varint(&mut bytes)?;
+        // custom names that can't be kept, see `CursedErrorType::Custom`
+        let mut unkept = Vec::new();
+        let error = match u32::try_from(code).ok().and_then(CursedError::from_numeric_code) {
+            Some(error @ (CursedError::NoError | CursedError::Unknown(_))) => error,
+            _ => {
+                let category = match code / 100 == CursedCategory::CUSTOM_CODE as u64 {
+                    true => Some(read_encoded_str(&mut bytes)?),
+                    false => None,
+                };
+                let error_type = match code % 100 == CursedErrorType::CUSTOM_CODE as u64 {
+                    true => Some(read_encoded_str(&mut bytes)?),
+                    false => None,
+                };
+
+                let kept_category = category.map(intern_name);
+                let kept_type = error_type.map(intern_name);
+                for (key, name, kept) in [("unknown_category", category, kept_category), ("unknown_type", error_type, kept_type)] {
+                    if let (Some(name), Some(None)) = (name, kept) {
+                        unkept.push((key, name));
+                    }
+                }
+
+                let error_type = match kept_type {
+                    Some(kept) => kept.map(CursedErrorType::Custom),
+                    None => ERROR_TYPES.get((code % 100).wrapping_sub(1) as usize).copied(),
+                };
+                match (kept_category, error_type) {
+                    (Some(Some(category)), Some(kind)) => CursedError::Custom { category, kind },
+                    (None, Some(error_type)) => TYPED_CATEGORIES.get((code / 100).wrapping_sub(1) as usize)
+                        .map_or(CursedError::Unknown(None), |category| category.with_type(error_type)),
+                    _ => CursedError::Unknown(None),
+                }
+            }
+        };
+        let reason = read_encoded_str(&mut bytes)?;
+
+        let mut inner = Inner::new(error, reason.to_string());
+        if error == CursedError::Unknown(None) && code != error.numeric_code() as u64 {
+            inner.fields.push(("unknown_code".into(), Field::Plain(code.to_string())));
+        }
+        for (key, name) in unkept {
+            inner.fields.push((key.into(), Field::Plain(name.to_string())));
+        }
+        Ok(CursedErrorHandle::from_inner(inner))
+    }
+
+    /// increments the `curerr_errors_total` counter of the installed `metrics` recorder
+    ///
+    /// The counter is labeled with `category` and `type` ids, so the number of series is
+    /// bounded by the number of [`CursedCategory`] and [`CursedErrorType`] variants. Errors
+    /// without a type use `"none"`.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    ///
+    /// let recorder = DebuggingRecorder::new();
+    /// let snapshotter = recorder.snapshotter();
+    ///
+    /// metrics::with_local_recorder(&recorder, || {
+    ///     for _ in 0..2 {
+    ///         CursedErrorHandle::new(
+    ///             CursedError::Connection(CursedErrorType::Refused),
+    ///             "upstream is down".to_string()
+    ///         ).record_metric();
+    ///     }
+    ///     CursedErrorHandle::new(
+    ///         CursedError::File(CursedErrorType::NotFound),
+    ///         "config.toml".to_string()
+    ///     ).record_metric();
+    /// });
+    ///
+    /// let mut counters: Vec<_> = snapshotter.snapshot().into_vec().into_iter()
+    ///     .map(|(key, _, _, value)| {
+    ///         let labels: Vec<_> = key.key().labels()
+    ///             .map(|label| format!("{}={}", label.key(), label.value()))
+    ///             .collect();
+    ///         (key.key().name().to_string(), labels.join(","), value)
+    ///     })
+    ///     .collect();
+    /// counters.sort_by(|a, b| a.1.cmp(&b.1));
+    ///
+    /// assert_eq!(counters, vec![
+    ///     ("curerr_errors_total".to_string(), "category=connection,type=refused".to_string(), DebugValue::Counter(2)),
+    ///     ("curerr_errors_total".to_string(), "category=file,type=not_found".to_string(), DebugValue::Counter(1)),
+    /// ]);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn record_metric(&self) {
+        self.as_ref_view().record_metric();
+    }
+
+    /// builds a sentry event grouped by the error classification instead of the message
+    ///
+    /// The exception list holds the source chain with the root cause first, as sentry
+    /// expects, without the io error a handle was converted from, which would repeat its
+    /// reason. The event is tagged with `category`, `type` and `severity`, and the
+    /// [fields](CursedErrorHandle::fields) are its extra data. Sensitive values are
+    /// `[REDACTED]` even with [`reveal_sensitive`] enabled, the event leaves the process.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::new(
+    ///     CursedError::Connection(CursedErrorType::Refused),
+    ///     "upstream 10.0.0.5 is down".to_string()
+    /// ).with_source(std::io::Error::from(std::io::ErrorKind::ConnectionRefused))
+    /// .with_field("attempt", 3)
+    /// .with_field_sensitive("token", "hunter2");
+    ///
+    /// reveal_sensitive(true);
+    /// let event = error.to_sentry_event();
+    /// reveal_sensitive(false);
+    ///
+    /// assert_eq!(event.fingerprint.as_ref(), ["curerr", "connection", "refused"]);
+    /// assert_eq!(event.tags["category"], "connection");
+    /// assert_eq!(event.tags["type"], "refused");
+    /// assert_eq!(event.tags["severity"], "warning");
+    /// assert_eq!(event.exception.len(), 2);
+    /// assert_eq!(event.exception[1].value.as_deref(), Some("upstream 10.0.0.5 is down"));
+    /// assert_eq!(event.extra["attempt"], "3");
+    /// assert_eq!(event.extra["token"], "[REDACTED]");
+    ///
+    /// # #[cfg(feature = "std-conversions")]
+    /// assert_eq!(CursedErrorHandle::from(std::io::Error::from_raw_os_error(2)).to_sentry_event().exception.len(), 1);
+    /// ```
+    #[cfg(feature = "sentry")]
+    pub fn to_sentry_event(&self) -> sentry::protocol::Event<'static> {
+        use sentry::protocol::{Event, Exception, Level};
+
+        let error_type = self.get_error().get_type().map_or("none", |err| err.to_id());
+        let severity = self.get_error().severity();
+
+        let mut exceptions = vec![Exception {
+            ty: self.get_error().to_string(),
+            value: Some(self.get_reason().to_string()),
+            ..Default::default()
+        }];
+        for error in self.reported_sources() {
+            exceptions.push(Exception {
+                ty: sentry::parse_type_from_debug(&format!("{:?}", error)).to_string(),
+                value: Some(error.to_string()),
+                ..Default::default()
+            });
+        }
+        exceptions.reverse();
+
+        let mut event = Event {
+            message: Some(self.to_string()),
+            level: match severity {
+                CursedSeverity::Info => Level::Info,
+                CursedSeverity::Warning => Level::Warning,
+                CursedSeverity::Error => Level::Error,
+                CursedSeverity::Critical => Level::Fatal,
+            },
+            fingerprint: vec![
+                "curerr".into(),
+                self.get_error().category().to_id().into(),
+                error_type.into(),
+            ].into(),
+            exception: exceptions.into(),
+            ..Default::default()
+        };
+        event.tags.insert("category".to_string(), self.get_error().category().to_id().to_string());
+        event.tags.insert("type".to_string(), error_type.to_string());
+        event.tags.insert("severity".to_string(), severity.to_id().to_string());
+        for (key, value) in self.inner().map(|inner| inner.fields.as_slice()).unwrap_or_default() {
+            let value = match value {
+                Field::Plain(value) => value.as_str(),
+                Field::Sensitive(_) => "[REDACTED]",
+            };
+            event.extra.insert(key.to_string(), value.into());
+        }
+
+        event
+    }
+
+    /// sends [`CursedErrorHandle::to_sentry_event`] to the current sentry hub
+    #[cfg(feature = "sentry")]
+    pub fn capture(&self) -> sentry::types::Uuid {
+        sentry::capture_event(self.to_sentry_event())
+    }
+
+    /// creates a `Call(Timedout)` error for an operation that didn't finish within `limit`,
+    /// [`CursedErrorHandle::timed_out_after`] with `limit` as the elapsed time
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::time::Duration;
+    ///
+    /// let error = CursedErrorHandle::timed_out("handshake", Duration::from_secs(5));
+    ///
+    /// assert!(matches!(error.get_error(), CursedError::Call(CursedErrorType::Timedout)));
+    /// assert_eq!(error.get_reason(), "handshake timed out after 5s (limit 5s)");
+    /// assert_eq!(error.timeout_limit(), Some(Duration::from_secs(5)));
+    /// ```
+    #[track_caller]
+    pub fn timed_out(what: &str, limit: std::time::Duration) -> Self {
+        Self::timed_out_after(what, limit, Some(limit))
+    }
+
+    /// creates a `Call(Timedout)` error that keeps the limit and the elapsed time as the
+    /// `timeout_limit` and `timeout_elapsed` fields
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::time::Duration;
+    ///
+    /// let error = CursedErrorHandle::timed_out_after(
+    ///     "query",
+    ///     Duration::from_secs(30),
+    ///     Some(Duration::from_millis(30_020))
+    /// );
+    ///
+    /// assert_eq!(error.get_reason(), "query timed out after 30.02s (limit 30s)");
+    /// assert_eq!(error.timeout_limit(), Some(Duration::from_secs(30)));
+    /// assert_eq!(error.timeout_elapsed(), Some(Duration::from_millis(30_020)));
+    ///
+    /// let error = CursedErrorHandle::timed_out_after("ping", Duration::from_micros(250), None);
+    /// assert_eq!(error.get_reason(), "ping timed out (limit 250µs)");
+    /// assert_eq!(error.timeout_elapsed(), None);
+    ///
+    /// let reason = |limit| CursedErrorHandle::timed_out_after("job", limit, None).get_reason().to_string();
+    ///
+    /// assert_eq!(reason(Duration::from_nanos(800)), "job timed out (limit 800ns)");
+    /// assert_eq!(reason(Duration::from_nanos(1_500)), "job timed out (limit 1.5µs)");
+    /// assert_eq!(reason(Duration::from_micros(999_999)), "job timed out (limit 1s)");
+    /// assert_eq!(reason(Duration::from_millis(1_500)), "job timed out (limit 1.5s)");
+    /// assert_eq!(reason(Duration::from_secs(90)), "job timed out (limit 1m30s)");
+    /// assert_eq!(reason(Duration::from_secs(7_200)), "job timed out (limit 2h)");
+    /// assert_eq!(reason(Duration::from_secs(10_805)), "job timed out (limit 3h0m5s)");
+    /// assert_eq!(reason(Duration::from_millis(119_999)), "job timed out (limit 2m)");
+    /// ```
+    #[track_caller]
+    pub fn timed_out_after(what: &str, limit: std::time::Duration, elapsed: Option<std::time::Duration>) -> Self {
+        let reason = match elapsed {
+            Some(elapsed) => format!(
+                "{} timed out after {} (limit {})",
+                what,
+                format_duration(elapsed),
+                format_duration(limit)
+            ),
+            None => format!("{} timed out (limit {})", what, format_duration(limit)),
+        };
+
+        let mut inner = Inner::new(CursedError::Call(CursedErrorType::Timedout), reason);
+        inner.fields.push(("timeout_limit".into(), Field::Plain(format_duration(limit))));
+        if let Some(elapsed) = elapsed {
+            inner.fields.push(("timeout_elapsed".into(), Field::Plain(format_duration(elapsed))));
+        }
+        inner.timeout = Some((limit, elapsed));
+        Self::from_inner(inner)
+    }
+    /// limit set by [`CursedErrorHandle::timed_out_after`]
+    pub fn timeout_limit(&self) -> Option<std::time::Duration> {
+        Some(self.inner()?.timeout?.0)
+    }
+    /// elapsed time set by [`CursedErrorHandle::timed_out_after`]
+    pub fn timeout_elapsed(&self) -> Option<std::time::Duration> {
+        self.inner()?.timeout?.1
+    }
+    /// attaches how long the other side asked to wait before trying again, which
+    /// [`retry`] sleeps instead of the delay of its policy
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::time::Duration;
+    ///
+    /// let result: CursedResult<()> = Err(CursedErrorHandle::new(
+    ///     CursedError::Call(CursedErrorType::NotEnough),
+    ///     "quota of 100 requests exceeded".to_string()
+    /// ).with_retry_after(Duration::from_secs(30)));
+    ///
+    /// let error = result.context("while syncing contacts").unwrap_err();
+    /// assert_eq!(error.retry_after(), Some(Duration::from_secs(30)));
+    ///
+    /// // handles wrapping the throttled error report its hint too
+    /// let error = CursedErrorHandle::new(CursedError::Call(CursedErrorType::Aborted), "sync failed".to_string())
+    ///     .with_source(error);
+    /// assert_eq!(error.retry_after(), Some(Duration::from_secs(30)));
+    /// ```
+    pub fn with_retry_after(mut self, delay: std::time::Duration) -> Self {
+        self.inner_mut().retry_after = Some(delay);
+        self
+    }
+    /// hint set by [`CursedErrorHandle::with_retry_after`] on this error or, if none,
+    /// the first one found in the source chain
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        let mut error: Option<&(dyn std::error::Error + 'static)> = Some(self);
+        while let Some(current) = error {
+            let hint = current.downcast_ref::<CursedErrorHandle>().and_then(|handle| handle.inner()?.retry_after);
+            if hint.is_some() {
+                return hint
+            }
+            error = current.source();
+        }
+        None
+    }
+    /// creates error for an error response of an HTTP service, the kind is picked by
+    /// [`CursedError::from_http_status`] and the status is kept as the `http_status` field
+    ///
+    /// For 429 and 503 `retry_after` is read as the value of the `Retry-After` header,
+    /// either a number of seconds or an HTTP date, and becomes the
+    /// [retry hint](CursedErrorHandle::retry_after). Dates in the past give a zero delay,
+    /// values that can't be read are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let error = CursedErrorHandle::from_http_status(429, "slow down".to_string(), Some("120"));
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::NotEnough));
+    /// assert_eq!(error.retry_after(), Some(Duration::from_secs(120)));
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("http_status", "429".into())]);
+    ///
+    /// let error = CursedErrorHandle::from_http_status(503, "maintenance".to_string(), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+    /// assert_eq!(error.retry_after(), Some(Duration::ZERO));
+    ///
+    /// let error = CursedErrorHandle::from_http_status(503, "maintenance".to_string(), Some("Fri, 01 Jan 2100 00:00:00 GMT"));
+    /// let date = SystemTime::now() + error.retry_after().unwrap();
+    /// let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(4_102_444_800);
+    ///
+    /// assert!(date.duration_since(expected).unwrap_or_else(|error| error.duration()) < Duration::from_secs(1));
+    ///
+    /// assert_eq!(CursedErrorHandle::from_http_status(503, String::new(), Some("soon")).retry_after(), None);
+    /// assert_eq!(CursedErrorHandle::from_http_status(404, String::new(), Some("120")).retry_after(), None);
+    /// ```
+    #[track_caller]
+    pub fn from_http_status(status: u16, reason: String, retry_after: Option<&str>) -> Self {
+        let mut inner = Inner::new(CursedError::from_http_status(status), reason);
+        inner.fields.push(("http_status".into(), Field::Plain(status.to_string())));
+        if matches!(status, 429 | 503) {
+            inner.retry_after = retry_after.and_then(|value| parse_retry_after(value, std::time::SystemTime::now()));
+        }
+        Self::from_inner(inner)
+    }
+    /// creates a `Memory(NotEnough)` error for a failed allocation of `bytes`, the size
+    /// is kept as the `bytes` field
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::alloc_failed(1_610_612_736);
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Memory(CursedErrorType::NotEnough));
+    /// assert_eq!(error.get_reason(), "failed to allocate 1.5GiB (1610612736 bytes)");
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("bytes", "1610612736".into())]);
+    ///
+    /// let reason = |bytes| CursedErrorHandle::alloc_failed(bytes).get_reason().to_string();
+    ///
+    /// assert_eq!(reason(1), "failed to allocate 1 byte");
+    /// assert_eq!(reason(1_000), "failed to allocate 1000 bytes");
+    /// assert_eq!(reason(4_096), "failed to allocate 4KiB (4096 bytes)");
+    /// assert_eq!(reason(1_048_575), "failed to allocate 1MiB (1048575 bytes)");
+    /// # #[cfg(target_pointer_width = "64")] {
+    /// assert_eq!(reason(5 << 40), "failed to allocate 5TiB (5497558138880 bytes)");
+    /// assert_eq!(reason(usize::MAX), "failed to allocate 16EiB (18446744073709551615 bytes)");
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn alloc_failed(bytes: usize) -> Self {
+        let reason = format!("failed to allocate {}", format_size(bytes as u64));
+
+        let mut inner = Inner::new(CursedError::Memory(CursedErrorType::NotEnough), reason);
+        inner.fields.push(("bytes".into(), Field::Plain(bytes.to_string())));
+        Self::from_inner(inner)
+    }
+    /// creates error about a file system path, the path is appended to the reason and
+    /// kept as the `path` field
+    ///
+    /// Paths that are not valid UTF-8 are displayed lossily.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::for_path(
+    ///     "/etc/app.toml",
+    ///     CursedError::Data(CursedErrorType::Invalid),
+    ///     "config has no [server] table"
+    /// );
+    ///
+    /// assert_eq!(error.path(), Some(std::path::Path::new("/etc/app.toml")));
+    /// assert_eq!(error.get_reason(), "config has no [server] table (path /etc/app.toml)");
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "data invalid error: \"config has no [server] table (path /etc/app.toml)\"\n  path = /etc/app.toml"
+    /// );
+    ///
+    /// # #[cfg(unix)] {
+    /// use std::os::unix::ffi::OsStrExt;
+    ///
+    /// let path = std::ffi::OsStr::from_bytes(b"/tmp/bad\xffname");
+    /// let error = CursedErrorHandle::for_path(path, CursedError::Path(CursedErrorType::Invalid), "can't use");
+    ///
+    /// assert_eq!(error.path().unwrap().as_os_str(), path);
+    /// assert_eq!(error.get_reason(), "can't use (path /tmp/bad\u{FFFD}name)");
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn for_path(path: impl Into<std::path::PathBuf>, error: CursedError, reason: impl Into<String>) -> Self {
+        let path = path.into();
+        let reason = format!("{} (path {})", reason.into(), path.display());
+
+        let mut inner = Inner::new(error, reason);
+        inner.fields.push(("path".into(), Field::Plain(path.to_string_lossy().into_owned())));
+        inner.path = Some(path);
+        Self::from_inner(inner)
+    }
+    /// creates error for a failed operation on the file at `path`, like [`std::fs::File::open`]
+    ///
+    /// The kind comes from the io error with the `Io` category replaced by `File`,
+    /// the OS code is kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let path = "/definitely/not/here.toml";
+    /// let error = CursedErrorHandle::open_err(path, std::fs::File::open(path).unwrap_err());
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::File(CursedErrorType::NotFound));
+    /// assert_eq!(error.path(), Some(std::path::Path::new(path)));
+    /// assert!(error.get_reason().ends_with("(path /definitely/not/here.toml)"));
+    /// # #[cfg(unix)]
+    /// assert_eq!(error.os_code(), Some(2));
+    /// ```
+    #[track_caller]
+    pub fn open_err(path: impl Into<std::path::PathBuf>, error: std::io::Error) -> Self {
+        let mut handle = Self::for_path(path, file_error(error.kind().into()), error.to_string());
+        handle.inner_mut().os_code = error.raw_os_error();
+        handle
+    }
+    /// path the error is about, set by [`CursedErrorHandle::for_path`]
+    pub fn path(&self) -> Option<&std::path::Path> {
+        self.inner()?.path.as_deref()
+    }
+    /// creates error about a network peer, the address is appended to the reason and
+    /// kept as the `peer` field
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::net::{Ipv6Addr, SocketAddr};
+    ///
+    /// let addr: SocketAddr = "10.0.0.5:443".parse().unwrap();
+    /// let error = CursedErrorHandle::for_addr(addr, CursedError::Connection(CursedErrorType::Refused), "handshake failed");
+    ///
+    /// assert_eq!(error.addr(), Some(addr));
+    /// assert_eq!(error.get_reason(), "handshake failed (peer 10.0.0.5:443)");
+    /// assert_eq!(
+    ///     error.report().to_string(),
+    ///     "connection refused error: \"handshake failed (peer 10.0.0.5:443)\"\n  peer = 10.0.0.5:443"
+    /// );
+    ///
+    /// let error = CursedErrorHandle::for_addr((Ipv6Addr::LOCALHOST, 8080), CursedError::Connection(CursedErrorType::Reset), "closed");
+    /// assert_eq!(error.get_reason(), "closed (peer [::1]:8080)");
+    ///
+    /// let error = CursedErrorHandle::for_addr("db.internal:5432", CursedError::Address(CursedErrorType::NotFound), "can't resolve");
+    /// assert_eq!(error.addr(), None);
+    /// assert_eq!(error.peer(), Some(&PeerAddr::Unresolved("db.internal:5432".to_string())));
+    /// assert_eq!(error.get_reason(), "can't resolve (peer db.internal:5432)");
+    /// ```
+    #[track_caller]
+    pub fn for_addr(addr: impl Into<PeerAddr>, error: CursedError, reason: impl Into<String>) -> Self {
+        let peer = addr.into();
+        let reason = format!("{} (peer {})", reason.into(), peer);
+
+        let mut inner = Inner::new(error, reason);
+        inner.fields.push(("peer".into(), Field::Plain(peer.to_string())));
+        inner.peer = Some(peer);
+        Self::from_inner(inner)
+    }
+    /// address of the peer set by [`CursedErrorHandle::for_addr`], `None` if it was not resolved
+    pub fn addr(&self) -> Option<std::net::SocketAddr> {
+        match self.peer()? {
+            PeerAddr::Resolved(addr) => Some(*addr),
+            PeerAddr::Unresolved(_) => None,
+        }
+    }
+    /// peer set by [`CursedErrorHandle::for_addr`]
+    pub fn peer(&self) -> Option<&PeerAddr> {
+        self.inner()?.peer.as_ref()
+    }
+    /// creates error for a failed name lookup of `host`, the host is included in the
+    /// reason and kept as the `host` field
+    ///
+    /// Lookup failures become `Address(NotFound)`: the errors the standard library makes
+    /// of resolver errors, which have no specific [`ErrorKind`], and errors of the kinds
+    /// `NotFound` and `Other`. Everything else, like an unreachable host or network, is
+    /// classified as usual. The OS code is kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// let lookup = Error::other("failed to lookup address information: Name or service not known");
+    /// let error = CursedErrorHandle::resolve_err("upstream.invalid", lookup);
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Address(CursedErrorType::NotFound));
+    /// assert_eq!(
+    ///     error.get_reason(),
+    ///     "failed to resolve upstream.invalid: failed to lookup address information: Name or service not known"
+    /// );
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("host", "upstream.invalid".into())]);
+    ///
+    /// let error = CursedErrorHandle::resolve_err("upstream", Error::from(ErrorKind::TimedOut));
+    /// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Timedout));
+    ///
+    /// let error = CursedErrorHandle::resolve_err("upstream", Error::from(ErrorKind::HostUnreachable));
+    /// assert_eq!(error.get_error(), &CursedError::Unknown(Some(ErrorKind::HostUnreachable)));
+    /// ```
+    #[track_caller]
+    pub fn resolve_err(host: &str, error: std::io::Error) -> Self {
+        let kind = match error.kind() {
+            ErrorKind::NotFound | ErrorKind::Other => CursedError::Address(CursedErrorType::NotFound),
+            // std gives resolver errors a kind that can't be named outside of it, unlike
+            // every kind in `IO_ERROR_KINDS`
+            kind if !crate::convert::IO_ERROR_KINDS.contains(&kind) => CursedError::Address(CursedErrorType::NotFound),
+            kind => CursedError::from(kind),
+        };
+
+        let mut inner = Inner::new(kind, format!("failed to resolve {}: {}", host, error));
+        inner.fields.push(("host".into(), Field::Plain(host.to_string())));
+        inner.os_code = error.raw_os_error();
+        Self::from_inner(inner)
+    }
+    /// creates `Envvar(NotFound)` or `Envvar(Invalid)` error for a failed [`std::env::var`],
+    /// the name is kept as the `env_var` field
+    ///
+    /// The value of a variable that is not valid unicode is not included, it may be secret.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::for_env_var("DATABASE_URL", std::env::VarError::NotPresent);
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Envvar(CursedErrorType::NotFound));
+    /// assert_eq!(error.get_reason(), "environment variable DATABASE_URL is not set");
+    /// assert_eq!(error.var_name(), Some("DATABASE_URL"));
+    ///
+    /// let error = CursedErrorHandle::for_env_var("TOKEN", std::env::VarError::NotUnicode("\u{FFFD}".into()));
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Envvar(CursedErrorType::Invalid));
+    /// assert_eq!(error.get_reason(), "environment variable TOKEN is not valid unicode");
+    /// ```
+    #[track_caller]
+    pub fn for_env_var(name: &str, error: std::env::VarError) -> Self {
+        let (error_type, reason) = match error {
+            std::env::VarError::NotPresent => (CursedErrorType::NotFound, "is not set"),
+            std::env::VarError::NotUnicode(_) => (CursedErrorType::Invalid, "is not valid unicode"),
+        };
+
+        Self::with_var_name(name, error_type, format!("environment variable {} {}", name, reason))
+    }
+    /// creates `Envvar` error for a variable with an unusable value, the name is appended
+    /// to the reason and kept as the `env_var` field
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedErrorHandle::env_var("PORT", CursedErrorType::Overflow, "port 70000 is out of range");
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Envvar(CursedErrorType::Overflow));
+    /// assert_eq!(error.get_reason(), "port 70000 is out of range (environment variable PORT)");
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("env_var", "PORT".into())]);
+    /// ```
+    #[track_caller]
+    pub fn env_var(name: &str, error_type: CursedErrorType, reason: impl Into<String>) -> Self {
+        Self::with_var_name(name, error_type, format!("{} (environment variable {})", reason.into(), name))
+    }
+    #[track_caller]
+    fn with_var_name(name: &str, error_type: CursedErrorType, reason: String) -> Self {
+        let mut inner = Inner::new(CursedError::Envvar(error_type), reason);
+        inner.fields.push(("env_var".into(), Field::Plain(name.to_string())));
+        inner.var_name = Some(name.to_string());
+        Self::from_inner(inner)
+    }
+    /// name of the environment variable set by [`CursedErrorHandle::for_env_var`] or
+    /// [`CursedErrorHandle::env_var`]
+    pub fn var_name(&self) -> Option<&str> {
+        self.inner()?.var_name.as_deref()
+    }
+    /// wraps the error of running `command`, the reason is prefixed with the shell quoted
+    /// command line and the program and arguments are kept as the `program` and `args` fields
+    ///
+    /// The command line in the reason is cut after 256 characters, arguments that are not
+    /// valid UTF-8 are displayed lossily.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::error::Error;
+    /// use std::process::Command;
+    ///
+    /// # #[cfg(feature = "std-conversions")] {
+    /// let mut command = Command::new("/definitely/not/here");
+    /// command.args(["--config", "my config.toml", "it's"]);
+    ///
+    /// let spawn_error = command.output().unwrap_err();
+    /// let error = CursedErrorHandle::for_command(&command, spawn_error);
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Io(CursedErrorType::NotFound));
+    /// assert!(error.get_reason().starts_with(
+    ///     "`/definitely/not/here --config 'my config.toml' 'it'\\''s'`: "
+    /// ));
+    /// assert_eq!(error.command_program().unwrap(), "/definitely/not/here");
+    /// assert_eq!(error.command_args().len(), 3);
+    /// assert_eq!(
+    ///     error.fields().collect::<Vec<_>>(),
+    ///     [("program", "/definitely/not/here".into()), ("args", "--config 'my config.toml' 'it'\\''s'".into())]
+    /// );
+    /// assert!(error.source().is_some());
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn for_command(command: &std::process::Command, error: impl Into<CursedErrorHandle>) -> Self {
+        const MAX_COMMAND_LINE: usize = 256;
+
+        let source = error.into();
+        let program = command.get_program().to_os_string();
+        let args: Vec<_> = command.get_args().map(std::ffi::OsStr::to_os_string).collect();
+
+        let quoted_args = args.iter()
+            .map(|arg| shell_quote(&arg.to_string_lossy()).into_owned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut command_line = shell_quote(&program.to_string_lossy()).into_owned();
+        if !quoted_args.is_empty() {
+            command_line = format!("{} {}", command_line, quoted_args);
+        }
+        if let Some((end, _)) = command_line.char_indices().nth(MAX_COMMAND_LINE) {
+            command_line = format!("{}...", &command_line[..end]);
+        }
+
+        let mut inner = Inner::new(*source.get_error(), format!("`{}`: {}", command_line, source.get_reason()));
+        inner.fields.push(("program".into(), Field::Plain(program.to_string_lossy().into_owned())));
+        inner.fields.push(("args".into(), Field::Plain(quoted_args)));
+        inner.command = Some((program, args));
+        Self::from_inner(inner).with_source(source)
+    }
+    /// program of the command set by [`CursedErrorHandle::for_command`]
+    pub fn command_program(&self) -> Option<&std::ffi::OsStr> {
+        self.inner()?.command.as_ref().map(|(program, _)| program.as_os_str())
+    }
+    /// arguments of the command set by [`CursedErrorHandle::for_command`]
+    pub fn command_args(&self) -> &[std::ffi::OsString] {
+        match self.inner().and_then(|inner| inner.command.as_ref()) {
+            Some((_, args)) => args,
+            None => &[],
+        }
+    }
+
+    /// creates error for a finished child process, returns `None` if it exited successfully
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::process::Command;
+    ///
+    /// # #[cfg(unix)] {
+    /// let status = Command::new("true").status().unwrap();
+    /// assert!(CursedErrorHandle::from_exit_status("true", status).is_none());
+    ///
+    /// let status = Command::new("sh").args(["-c", "exit 3"]).status().unwrap();
+    /// let error = CursedErrorHandle::from_exit_status("sh", status).unwrap();
+    ///
+    /// assert!(matches!(error.get_error(), CursedError::Call(CursedErrorType::Aborted)));
+    /// assert_eq!(error.get_reason(), "`sh` exited with code 3");
+    ///
+    /// let status = Command::new("sh").args(["-c", "kill -9 $$"]).status().unwrap();
+    /// let error = CursedErrorHandle::from_exit_status("sh", status).unwrap();
+    ///
+    /// assert!(matches!(error.get_error(), CursedError::Call(CursedErrorType::Interrupted)));
+    /// assert_eq!(error.get_reason(), "`sh` was terminated by signal 9");
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn from_exit_status(program: &str, status: std::process::ExitStatus) -> Option<Self> {
+        let (error, reason) = Self::describe_exit_status(program, status)?;
+        Some(Self::new(error, reason))
+    }
+
+    /// same as [`CursedErrorHandle::from_exit_status`], but also appends the stderr tail of the process
+    ///
+    /// Only the last 4 KiB of stderr are kept, invalid UTF-8 is replaced.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::process::Command;
+    ///
+    /// # #[cfg(unix)] {
+    /// let output = Command::new("sh").args(["-c", "echo broken >&2; exit 3"]).output().unwrap();
+    /// let error = CursedErrorHandle::from_output("sh", &output).unwrap();
+    ///
+    /// assert_eq!(error.get_reason(), "`sh` exited with code 3, stderr: broken");
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn from_output(program: &str, output: &std::process::Output) -> Option<Self> {
+        const STDERR_TAIL: usize = 4096;
+
+        let (error, mut reason) = Self::describe_exit_status(program, output.status)?;
+
+        let stderr = &output.stderr[output.stderr.len().saturating_sub(STDERR_TAIL)..];
+        let stderr = String::from_utf8_lossy(stderr);
+        let stderr = stderr.trim();
+
+        if !stderr.is_empty() {
+            let ellipsis = if output.stderr.len() > STDERR_TAIL { "..." } else { "" };
+            reason = format!("{}, stderr: {}{}", reason, ellipsis, stderr);
+        }
+
+        Some(Self::new(error, reason))
+    }
+
+    fn describe_exit_status(program: &str, status: std::process::ExitStatus) -> Option<(CursedError, String)> {
+        if status.success() {
+            return None
+        }
+
+        if let Some(code) = status.code() {
+            return Some((
+                CursedError::Call(CursedErrorType::Aborted),
+                format!("`{}` exited with code {}", program, code)
+            ))
+        }
+
+        #[cfg(unix)]
+        if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+            return Some((
+                CursedError::Call(CursedErrorType::Interrupted),
+                format!("`{}` was terminated by signal {}", program, signal)
+            ))
+        }
+
+        Some((
+            CursedError::Call(CursedErrorType::Aborted),
+            format!("`{}` failed with {}", program, status)
+        ))
+    }
+}
+
+/// a handle without a reason, displayed as just the kind
+///
+/// The reason isn't allocated, the handle is created like with [`CursedErrorHandle::new`]
+/// so it still gets the location, the [scope](scope) contexts and goes to the
+/// [error hook](set_error_hook). To attach a reason use [`CursedError::with_reason`].
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// fn open(exists: bool) -> CursedResult<()> {
+///     match exists {
+///         true => Ok(()),
+///         false => Err(CursedError::from(std::io::ErrorKind::NotFound).into()),
+///     }
+/// }
+///
+/// let error = open(false).unwrap_err();
+///
+/// assert_eq!(error.get_error(), &CursedError::Io(CursedErrorType::NotFound));
+/// assert_eq!(error.get_reason(), "");
+/// assert_eq!(error.to_string(), "io not found error");
+///
+/// fn classify(code: &str) -> CursedResult<()> {
+///     Err(CursedError::from_code(code))?
+/// }
+/// assert_eq!(classify("CALL_TIMED_OUT").unwrap_err().to_string(), "call timed out error");
+/// ```
+impl From<CursedError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(error: CursedError) -> Self {
+        let mut inner = Inner::new(error, "");
+        inner.reasonless = true;
+        Self::from_inner(inner)
+    }
+}
+
+/// one line `kind error: "contexts: reason"`, quotes, backslashes and line breaks
+/// inside the quotes are escaped like in Rust strings
+///
+/// This is the [`FormatVersion::V1`] output, see [`FormatVersion::V2`] for the other one.
+///
+/// Fields are left out, and the kind of a custom type in `Other` or in a custom
+/// category can't be parsed back from the phrase, see [`NormalizedError`]. Errors
+/// converted from a bare [`CursedError`] are only `kind error` until they get a reason
+/// or contexts, an empty reason given explicitly is still shown as `""`.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = |reason: &str| CursedErrorHandle::new(CursedError::File(CursedErrorType::Invalid), reason.to_string());
+///
+/// assert_eq!(error("").to_string(), "file invalid error: \"\"");
+/// assert_eq!(CursedErrorHandle::from(CursedError::File(CursedErrorType::Invalid)).to_string(), "file invalid error");
+///
+/// assert_eq!(
+///     error("expected \"name\" field").to_string(),
+///     r#"file invalid error: "expected \"name\" field""#
+/// );
+/// assert_eq!(
+///     error("expected a field\ngot: 3").to_string(),
+///     r#"file invalid error: "expected a field\ngot: 3""#
+/// );
+/// assert_eq!(
+///     error("expected \"name\"\r\n\tin C:\\config").to_string(),
+///     r#"file invalid error: "expected \"name\"\r\n\tin C:\\config""#
+/// );
+/// ```
+impl std::fmt::Display for CursedErrorHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.format_version() == FormatVersion::V2 {
+            return display_v2(self, f)
+        }
+        if let Some(op) = self.op() {
+            write!(f, "{}: ", op)?;
+        }
+        write!(f, "{} error", self.get_error())?;
+        if self.inner().is_some_and(|inner| inner.reasonless) && self.contexts().is_empty() {
+            return Ok(())
+        }
+        f.write_str(": \"")?;
+        for context in self.contexts() {
+            write_escaped(f, context)?;
+            f.write_str(": ")?;
+        }
+        write_escaped(f, self.get_reason())?;
+        f.write_str("\"")
+    }
+}
+
+struct DisplayKind<'a>(&'a CursedErrorHandle);
+
+impl std::fmt::Display for DisplayKind<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_part(f: &mut std::fmt::Formatter<'_>, name: &str) -> std::fmt::Result {
+            for c in name.chars() {
+                std::fmt::Write::write_char(f, if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '_' })?;
+            }
+            Ok(())
+        }
+
+        let error = self.0.get_error();
+        write_part(f, error.category().to_id())?;
+        if let Some(error_type) = error.get_type() {
+            f.write_str("_")?;
+            write_part(f, error_type.to_id())?;
+        }
+        Ok(())
+    }
+}
+
+struct DisplayReason<'a>(&'a CursedErrorHandle);
+
+impl std::fmt::Display for DisplayReason<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for context in self.0.contexts() {
+            write!(f, "{}: ", context)?;
+        }
+        f.write_str(self.0.get_reason())
+    }
+}
+
+struct DisplayCompact<'a> {
+    handle: &'a CursedErrorHandle,
+    max_len: usize,
+}
+
+impl std::fmt::Display for DisplayCompact<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = self.handle.get_reason();
+        let mut end = reason.len().min(self.max_len);
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        write!(f, "{}: ", self.handle.code())?;
+        for (index, line) in reason[..end].split('\n').enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(line.strip_suffix('\r').unwrap_or(line))?;
+        }
+        if end < reason.len() {
+            f.write_str("…")?;
+        }
+        Ok(())
+    }
+}
+
+/// writes `text` with quotes, backslashes and line breaks escaped, so it can be put between quotes
+pub(crate) fn write_escaped(f: &mut std::fmt::Formatter<'_>, text: &str) -> std::fmt::Result {
+    let mut rest = text;
+    while let Some(index) = rest.find(['"', '\\', '\n', '\r', '\t']) {
+        f.write_str(&rest[..index])?;
+        f.write_str(match rest.as_bytes()[index] {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            _ => "\\t",
+        })?;
+        rest = &rest[index + 1..];
+    }
+    f.write_str(rest)
+}
+
+/// 64 bit FNV-1a of the canonical JSON of `repr` with its volatile parts left out, see
+/// [`CursedErrorHandle::fingerprint`]
+pub(crate) fn fingerprint(mut repr: HandleRepr<'_>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    repr.reason = normalize_volatile(&repr.reason).into();
+    repr.retry_after_ms = None;
+    for (_, value) in &mut repr.fields {
+        *value = normalize_volatile(value).into();
+    }
+
+    let mut hash = OFFSET_BASIS;
+    for byte in repr.to_canonical_json().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+
+    hash
+}
+
+/// `text` with runs of digits and hex words replaced, see [`CursedErrorHandle::normalized_reason`]
+fn normalize_volatile(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric()) {
+        normalized.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len());
+        let word = &rest[..end];
+        rest = &rest[end..];
+
+        let hex = word.strip_prefix("0x").or_else(|| word.strip_prefix("0X"));
+        let is_hex = match hex {
+            Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()),
+            None => word.len() >= 4
+                && word.chars().all(|c| c.is_ascii_hexdigit())
+                && word.chars().any(|c| c.is_ascii_digit())
+                && word.chars().any(|c| c.is_ascii_alphabetic()),
+        };
+        if is_hex {
+            normalized.push_str("<hex>");
+            continue
+        }
+
+        let mut in_digits = false;
+        for c in word.chars() {
+            if c.is_ascii_digit() {
+                if !in_digits {
+                    normalized.push_str("<n>");
+                }
+                in_digits = true;
+            } else {
+                normalized.push(c);
+                in_digits = false;
+            }
+        }
+    }
+    normalized.push_str(rest);
+
+    normalized
+}
+
+/// appends `text` as a JSON string, escaped like `serde_json` does
+pub(crate) fn write_json_str(json: &mut String, text: &str) {
+    use std::fmt::Write;
+
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\u{8}' => json.push_str("\\b"),
+            '\u{c}' => json.push_str("\\f"),
+            c if c < ' ' => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            },
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+impl std::fmt::Debug for CursedErrorHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.format_version() == FormatVersion::V2 {
+            return debug_v2(self, f)
+        }
+        f.debug_tuple(&self.get_error().to_string())
+            .field(&self.get_reason())
+            .finish()
+        }
+}
+pub(crate) static REVEAL_SENSITIVE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+static KEEP_ORIGINAL_KIND: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// whether errors capture backtraces, 0 until set or read from the environment, then 1
+/// when they do and 2 when they don't
+static CAPTURE_BACKTRACES: std::sync::atomic::AtomicU8 = std::sync::atomic::AtomicU8::new(0);
+
+/// captures a backtrace for every error created afterwards, or for none, whatever
+/// `RUST_LIB_BACKTRACE` and `RUST_BACKTRACE` are
+///
+/// Until it is called the environment decides like for
+/// [`Backtrace::capture`](std::backtrace::Backtrace::capture): `RUST_LIB_BACKTRACE`, or
+/// `RUST_BACKTRACE` when it isn't set, enables them unless it is `0`. The variables are
+/// read on the first error. Capturing walks the stack of every new error, so a service that
+/// sets `RUST_BACKTRACE=1` for its panics can turn it off for errors here.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// capture_backtraces(false);
+/// assert!(CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "slow".to_string()).backtrace().is_none());
+///
+/// capture_backtraces(true);
+/// let error = CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "slow".to_string());
+/// assert_eq!(error.backtrace().unwrap().status(), std::backtrace::BacktraceStatus::Captured);
+/// capture_backtraces(false);
+/// ```
+pub fn capture_backtraces(capture: bool) {
+    CAPTURE_BACKTRACES.store(if capture { 1 } else { 2 }, std::sync::atomic::Ordering::Relaxed);
+}
+
+fn backtraces_enabled() -> bool {
+    use std::sync::atomic::Ordering;
+
+    match CAPTURE_BACKTRACES.load(Ordering::Relaxed) {
+        1 => true,
+        2 => false,
+        _ => {
+            let variable = std::env::var_os("RUST_LIB_BACKTRACE").or_else(|| std::env::var_os("RUST_BACKTRACE"));
+            let enabled = variable.is_some_and(|value| value != "0");
+            // a concurrent `capture_backtraces` wins
+            let _ = CAPTURE_BACKTRACES.compare_exchange(0, if enabled { 1 } else { 2 }, Ordering::Relaxed, Ordering::Relaxed);
+            CAPTURE_BACKTRACES.load(Ordering::Relaxed) == 1
+        },
+    }
+}
+
+/// records the kind replaced by [`CursedErrorHandle::map_kind`] in the `original_kind` field
+pub fn keep_original_kind(keep: bool) {
+    KEEP_ORIGINAL_KIND.store(keep, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// shows the values wrapped in [`Sensitive`] instead of `[REDACTED]`, meant for local debugging
+pub fn reveal_sensitive(reveal: bool) {
+    REVEAL_SENSITIVE.store(reveal, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// value that is displayed as `[REDACTED]` unless [`reveal_sensitive`] is enabled,
+/// serialization always uses the redacted form
+///
+/// The value is hidden when it is formatted, so it has to be wrapped before being
+/// interpolated into a reason.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedErrorHandle::new(
+///     CursedError::Connection(CursedErrorType::Refused),
+///     format!("can't connect with token {}", Sensitive("s3cr3t"))
+/// );
+///
+/// assert_eq!(error.get_reason(), "can't connect with token [REDACTED]");
+/// assert_eq!(format!("{:?}", Sensitive("s3cr3t")), "[REDACTED]");
+///
+/// reveal_sensitive(true);
+/// assert_eq!(Sensitive("s3cr3t").to_string(), "s3cr3t");
+/// reveal_sensitive(false);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Sensitive<T>(pub T);
+
+impl<T: std::fmt::Display> std::fmt::Display for Sensitive<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match REVEAL_SENSITIVE.load(std::sync::atomic::Ordering::Relaxed) {
+            true => self.0.fmt(f),
+            false => f.write_str("[REDACTED]"),
+        }
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match REVEAL_SENSITIVE.load(std::sync::atomic::Ordering::Relaxed) {
+            true => self.0.fmt(f),
+            false => f.write_str("[REDACTED]"),
+        }
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> CursedResult<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or_else(|| CursedErrorHandle::new(
+            CursedError::Data(CursedErrorType::NotEnough),
+            "encoded error is truncated".to_string()
+        ))?;
+        *bytes = rest;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value)
+        }
+    }
+
+    Err(CursedErrorHandle::new(
+        CursedError::Data(CursedErrorType::Overflow),
+        "encoded number is too large".to_string()
+    ))
+}
+
+fn write_encoded_str(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn read_encoded_str<'a>(bytes: &mut &'a [u8]) -> CursedResult<&'a str> {
+    let len = read_varint(bytes)?;
+    if len > bytes.len() as u64 {
+        return Err(CursedErrorHandle::new(
+            CursedError::Data(CursedErrorType::NotEnough),
+            "encoded error is truncated".to_string()
+        ))
+    }
+
+    let (value, rest) = bytes.split_at(len as usize);
+    *bytes = rest;
+    std::str::from_utf8(value).map_err(|_| CursedErrorHandle::new(
+        CursedError::Data(CursedErrorType::Invalid),
+        "encoded string is not valid utf-8".to_string()
+    ))
+}
+
+/// delay of a `Retry-After` header value, delay seconds or an IMF-fixdate like
+/// `Wed, 21 Oct 2015 07:28:00 GMT`, relative to `now`, dates too far to be a time are
+/// unreadable
+fn parse_retry_after(value: &str, now: std::time::SystemTime) -> Option<std::time::Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(seconds))
+    }
+
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    let [_, day, month, year, time, "GMT"] = value.split_whitespace().collect::<Vec<_>>()[..] else {
+        return None
+    };
+    let (day, year) = (day.parse::<u64>().ok()?, year.parse::<u64>().ok()?);
+    let month = MONTHS.iter().position(|name| *name == month)? as u64 + 1;
+    let [hours, minutes, seconds] = time.split(':').map(str::parse::<u64>).collect::<Result<Vec<_>, _>>().ok()?[..] else {
+        return None
+    };
+    if !(1..=crate::civil::days_in_month(year, month)).contains(&day) || year < 1970 || hours > 23 || minutes > 59 || seconds > 60 {
+        return None
+    }
+
+    let days = crate::civil::days_from_civil(year, month, day)?;
+    let seconds = days.checked_mul(86_400)?.checked_add(hours * 3_600 + minutes * 60 + seconds)?;
+    let date = std::time::UNIX_EPOCH.checked_add(std::time::Duration::from_secs(seconds))?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// formats `duration` like `250µs`, `30.02s` or `3h0m5s`, with at most two decimals
+pub(crate) fn format_duration(duration: std::time::Duration) -> String {
+    fn decimal(value: f64, unit: &str) -> String {
+        let value = format!("{:.2}", value);
+        format!("{}{}", value.trim_end_matches('0').trim_end_matches('.'), unit)
+    }
+
+    let nanos = duration.as_nanos();
+
+    if nanos < 1_000 {
+        return format!("{}ns", nanos)
+    }
+    // thresholds sit just below the next unit so rounding never prints `1000ms`
+    if nanos < 999_995 {
+        return decimal(nanos as f64 / 1e3, "µs")
+    }
+    if nanos < 999_995_000 {
+        return decimal(nanos as f64 / 1e6, "ms")
+    }
+    if duration.as_secs_f64() < 59.995 {
+        return decimal(duration.as_secs_f64(), "s")
+    }
+
+    let centis = (nanos + 5_000_000) / 10_000_000;
+    let seconds = decimal((centis % 6_000) as f64 / 100.0, "s");
+    let (hours, minutes) = (centis / 360_000, centis / 6_000 % 60);
+
+    let mut formatted = String::new();
+    if hours > 0 {
+        formatted += &format!("{}h", hours);
+    }
+    if seconds != "0s" {
+        formatted += &format!("{}m{}", minutes, seconds);
+    } else if minutes > 0 {
+        formatted += &format!("{}m", minutes);
+    }
+    formatted
+}
+
+/// formats `bytes` in binary units like `4KiB` or `1.5GiB`, with at most two decimals
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    // rounding to two decimals must not print `1024KiB`
+    while value >= 1_023.995 && unit + 1 < UNITS.len() {
+        value /= 1_024.0;
+        unit += 1;
+    }
+
+    let value = format!("{:.2}", value);
+    format!("{}{}", value.trim_end_matches('0').trim_end_matches('.'), UNITS[unit])
+}
+
+/// `bytes` as a count like `1 byte` or `12 bytes`, from 1KiB on with the binary size in
+/// front like `1KiB (1024 bytes)`
+pub(crate) fn format_size(bytes: u64) -> String {
+    match bytes {
+        1 => "1 byte".to_string(),
+        0..=1_023 => format!("{} bytes", bytes),
+        _ => format!("{} ({} bytes)", format_bytes(bytes), bytes),
+    }
+}
+
+/// quotes `word` for a POSIX shell unless it only has characters that need no quoting
+pub(crate) fn shell_quote(word: &str) -> std::borrow::Cow<'_, str> {
+    let is_plain = |c: char| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c);
+
+    if !word.is_empty() && word.chars().all(is_plain) {
+        return std::borrow::Cow::Borrowed(word)
+    }
+    std::borrow::Cow::Owned(format!("'{}'", word.replace('\'', "'\\''")))
+}
+
+/// network peer of an error, see [`CursedErrorHandle::for_addr`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PeerAddr {
+    Resolved(std::net::SocketAddr),
+    /// `host:port` for errors that happened before the address was resolved
+    Unresolved(String),
+}
+
+impl std::fmt::Display for PeerAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerAddr::Resolved(addr) => addr.fmt(f),
+            PeerAddr::Unresolved(addr) => f.write_str(addr),
+        }
+    }
+}
+
+impl From<std::net::SocketAddr> for PeerAddr {
+    fn from(addr: std::net::SocketAddr) -> Self {
+        PeerAddr::Resolved(addr)
+    }
+}
+
+impl From<std::net::SocketAddrV4> for PeerAddr {
+    fn from(addr: std::net::SocketAddrV4) -> Self {
+        PeerAddr::Resolved(addr.into())
+    }
+}
+
+impl From<std::net::SocketAddrV6> for PeerAddr {
+    fn from(addr: std::net::SocketAddrV6) -> Self {
+        PeerAddr::Resolved(addr.into())
+    }
+}
+
+impl<I: Into<std::net::IpAddr>> From<(I, u16)> for PeerAddr {
+    fn from((ip, port): (I, u16)) -> Self {
+        PeerAddr::Resolved(std::net::SocketAddr::new(ip.into(), port))
+    }
+}
+
+impl From<&str> for PeerAddr {
+    fn from(addr: &str) -> Self {
+        PeerAddr::Unresolved(addr.to_string())
+    }
+}
+
+impl From<String> for PeerAddr {
+    fn from(addr: String) -> Self {
+        PeerAddr::Unresolved(addr)
+    }
+}
+
+/// with the `nightly` feature the handle provides its [`CursedError`], [`Location`](std::panic::Location)
+/// and [`Backtrace`](std::backtrace::Backtrace) through `std::error::Request`
+///
+/// # Examples
+#[cfg_attr(feature = "nightly", doc = "```")]
+#[cfg_attr(not(feature = "nightly"), doc = "```ignore")]
+/// #![feature(error_generic_member_access, error_reporter)]
+/// use curerr::*;
+/// use std::error::{request_ref, Report};
+///
+/// let error = CursedErrorHandle::new(
+///     CursedError::Data(CursedErrorType::Invalid),
+///     "can't load config".to_string()
+/// ).with_source(CursedErrorHandle::new(
+///     CursedError::File(CursedErrorType::NotFound),
+///     "config.toml".to_string()
+/// ));
+///
+/// assert_eq!(request_ref::<CursedError>(&error), Some(&CursedError::Data(CursedErrorType::Invalid)));
+/// assert_eq!(request_ref::<std::panic::Location>(&error).unwrap().file(), file!());
+/// assert_eq!(request_ref::<std::backtrace::Backtrace>(&error).is_some(), error.backtrace().is_some());
+///
+/// assert_eq!(
+///     Report::new(error).pretty(true).to_string(),
+///     "data invalid error: \"can't load config\"\n\n\
+///      Caused by:\n      \
+///      file not found error: \"config.toml\""
+/// );
+/// ```
+impl std::error::Error for CursedErrorHandle {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner()?.source.as_deref().map(|source| source as _)
+    }
+    #[cfg(feature = "nightly")]
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        request.provide_ref::<CursedError>(self.get_error());
+
+        if let Some(location) = self.location() {
+            request.provide_ref::<std::panic::Location<'static>>(location);
+        }
+        if let Some(backtrace) = self.backtrace() {
+            request.provide_ref::<std::backtrace::Backtrace>(backtrace);
+        }
+    }
+}
+
+type ErrorHook = std::sync::Arc<dyn Fn(&CursedErrorHandle) + Send + Sync>;
+
+static ERROR_HOOK_SET: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+/// the hooks in the order they were added, replaced as a whole so calling them only
+/// clones the `Arc`
+static ERROR_HOOKS: std::sync::RwLock<Option<std::sync::Arc<[ErrorHook]>>> = std::sync::RwLock::new(None);
+
+thread_local! {
+    static IN_ERROR_HOOK: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// installs a hook called with every [`CursedErrorHandle`] created by [`CursedErrorHandle::new`]
+///
+/// The hook replaces the previous ones, [`add_error_hook`] keeps them. Panics inside the
+/// hook are caught and ignored, errors created by the hook itself don't call it again.
+/// Without a hook, creating an error doesn't take the lock of the hooks.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static CREATED: AtomicUsize = AtomicUsize::new(0);
+///
+/// set_error_hook(|_| { CREATED.fetch_add(1, Ordering::Relaxed); });
+///
+/// for _ in 0..3 {
+///     CursedErrorHandle::new(CursedError::Unknown(None), "oops".to_string());
+/// }
+/// assert_eq!(CREATED.load(Ordering::Relaxed), 3);
+///
+/// set_error_hook(|_| panic!("broken hook"));
+/// let error = CursedErrorHandle::new(CursedError::Unknown(None), "still works".to_string());
+/// assert_eq!(error.get_reason(), "still works");
+///
+/// clear_error_hook();
+/// CursedErrorHandle::new(CursedError::Unknown(None), "not counted".to_string());
+/// assert_eq!(CREATED.load(Ordering::Relaxed), 3);
+/// ```
+pub fn set_error_hook(hook: impl Fn(&CursedErrorHandle) + Send + Sync + 'static) {
+    let mut slot = ERROR_HOOKS.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *slot = Some(std::sync::Arc::new([std::sync::Arc::new(hook) as ErrorHook]));
+    #[cfg(feature = "metrics")]
+    METRICS_ENABLED.store(false, std::sync::atomic::Ordering::Relaxed);
+    ERROR_HOOK_SET.store(true, std::sync::atomic::Ordering::Release);
+}
+
+/// installs a hook next to the ones already installed, each error calls them in the
+/// order they were added
+///
+/// This is how [`ErrorHistory::install`], [`ErrorStats::install`] and
+/// [`SamplingReporter::install`] combine. A panicking hook doesn't skip the ones after it.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static CREATED: AtomicUsize = AtomicUsize::new(0);
+/// static REFUSED: AtomicUsize = AtomicUsize::new(0);
+///
+/// add_error_hook(|_| { CREATED.fetch_add(1, Ordering::Relaxed); });
+/// add_error_hook(|error| if error.get_error().get_type() == Some(CursedErrorType::Refused) {
+///     REFUSED.fetch_add(1, Ordering::Relaxed);
+/// });
+///
+/// CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "down".to_string());
+/// CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), "gone".to_string());
+/// assert_eq!((CREATED.load(Ordering::Relaxed), REFUSED.load(Ordering::Relaxed)), (2, 1));
+/// ```
+pub fn add_error_hook(hook: impl Fn(&CursedErrorHandle) + Send + Sync + 'static) {
+    let mut slot = ERROR_HOOKS.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    push_error_hook(&mut slot, std::sync::Arc::new(hook));
+}
+
+fn push_error_hook(slot: &mut Option<std::sync::Arc<[ErrorHook]>>, hook: ErrorHook) {
+    let hooks = slot.iter().flat_map(|hooks| hooks.iter().cloned());
+    *slot = Some(hooks.chain([hook]).collect());
+    ERROR_HOOK_SET.store(true, std::sync::atomic::Ordering::Release);
+}
+
+/// removes the hooks installed by [`set_error_hook`] and [`add_error_hook`]
+pub fn clear_error_hook() {
+    let mut slot = ERROR_HOOKS.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *slot = None;
+    #[cfg(feature = "metrics")]
+    METRICS_ENABLED.store(false, std::sync::atomic::Ordering::Relaxed);
+    ERROR_HOOK_SET.store(false, std::sync::atomic::Ordering::Release);
+}
+
+pub(crate) static DOCS_BASE_URL: std::sync::RwLock<Option<&'static str>> = std::sync::RwLock::new(None);
+
+/// sets the base of the links returned by [`CursedErrorHandle::docs_url`]
+pub fn set_docs_base_url(url: &'static str) {
+    *DOCS_BASE_URL.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(url);
+}
+
+/// removes the base set by [`set_docs_base_url`]
+pub fn clear_docs_base_url() {
+    *DOCS_BASE_URL.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+pub(crate) fn call_error_hook(handle: &CursedErrorHandle) {
+    if !ERROR_HOOK_SET.load(std::sync::atomic::Ordering::Relaxed) {
+        return
+    }
+    if IN_ERROR_HOOK.with(|in_hook| in_hook.replace(true)) {
+        return
+    }
+
+    let hooks = ERROR_HOOKS.read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+
+    for hook in hooks.iter().flat_map(|hooks| hooks.iter()) {
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| hook(handle)));
+    }
+
+    IN_ERROR_HOOK.with(|in_hook| in_hook.set(false));
+}
+
+/// whether the hook of [`enable_metrics`] is installed, changed under the lock of the hooks
+#[cfg(feature = "metrics")]
+static METRICS_ENABLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// adds [`CursedErrorHandle::record_metric`] as an error hook, so every created error is counted
+///
+/// Other hooks are kept, see [`add_error_hook`]. Calls while it is installed do nothing,
+/// so a library and the application can both enable metrics. [`set_error_hook`] and
+/// [`clear_error_hook`] remove it.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+///
+/// let recorder = DebuggingRecorder::new();
+/// let snapshotter = recorder.snapshotter();
+///
+/// enable_metrics();
+/// enable_metrics();
+/// metrics::with_local_recorder(&recorder, || {
+///     CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "upstream is down".to_string());
+/// });
+///
+/// let counters: Vec<_> = snapshotter.snapshot().into_vec().into_iter().map(|(_, _, _, value)| value).collect();
+/// assert_eq!(counters, [DebugValue::Counter(1)]);
+/// ```
+#[cfg(feature = "metrics")]
+pub fn enable_metrics() {
+    let mut slot = ERROR_HOOKS.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !METRICS_ENABLED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+        push_error_hook(&mut slot, std::sync::Arc::new(CursedErrorHandle::record_metric));
+    }
+}
@@ -0,0 +1,133 @@
+//! the last errors of the process kept for post-mortem dumps, see [`ErrorHistory`]
+
+use crate::*;
+use crate::civil::civil_from_days;
+use crate::handle::{Inner, Reason};
+use crate::truncate::cut;
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// the most bytes of a reason kept per entry, including the truncation marker
+const MAX_ENTRY_REASON_LEN: usize = 256;
+
+/// the last `capacity` errors with the time they were recorded, including the ones that
+/// were handled and dropped since, for dumps after a crash or a failed health check
+///
+/// Installed as the error hook with [`ErrorHistory::install`] it records every error when
+/// it is created. Entries keep a copy of the kind, the reason cut to 256 bytes, the op,
+/// namespace, os code and location of the error, without its contexts, fields and
+/// sources, so the memory used is bounded by the capacity whatever the size of the
+/// errors. When full, each new entry replaces the oldest one.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let history = ErrorHistory::new(2);
+/// for shard in 1..=3 {
+///     history.record(&CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), format!("shard {}", shard)));
+/// }
+///
+/// let reasons: Vec<_> = history.recent(10).iter().map(|error| error.get_reason().to_string()).collect();
+/// assert_eq!(reasons, ["shard 2", "shard 3"]);
+///
+/// let mut dump = Vec::new();
+/// history.dump_to(&mut dump).unwrap();
+/// let dump = String::from_utf8(dump).unwrap();
+/// // e.g. "2026-10-14T09:30:00.125Z FILE_NOT_FOUND: shard 2"
+/// assert!(dump.lines().next().unwrap().ends_with("Z FILE_NOT_FOUND: shard 2"));
+/// ```
+///
+/// With [`ErrorHistory::install_panic_hook`] a panic dumps the history to stderr.
+///
+/// ```no_run
+/// use curerr::*;
+/// use std::sync::Arc;
+///
+/// let history = Arc::new(ErrorHistory::new(64));
+/// ErrorHistory::install(history.clone());
+/// ErrorHistory::install_panic_hook(history);
+/// ```
+pub struct ErrorHistory {
+    capacity: usize,
+    entries: Mutex<VecDeque<(SystemTime, CursedErrorHandle)>>,
+}
+
+impl ErrorHistory {
+    /// a history keeping the last `capacity` errors, `0` keeps none
+    pub fn new(capacity: usize) -> Self {
+        ErrorHistory { capacity, entries: Mutex::new(VecDeque::with_capacity(capacity)) }
+    }
+    /// adds `history` as an error hook, next to the ones of [`add_error_hook`]
+    ///
+    /// Static handles don't call the hook, so they are only recorded when they get modified.
+    pub fn install(history: std::sync::Arc<ErrorHistory>) {
+        add_error_hook(move |error| history.record(error));
+    }
+    /// makes panics dump `history` to stderr under a `last errors:` line, after the panic
+    /// hook that was installed before, which still runs first
+    pub fn install_panic_hook(history: std::sync::Arc<ErrorHistory>) {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            let mut stderr = std::io::stderr().lock();
+            let _ = writeln!(stderr, "last errors:").and_then(|()| history.dump_to(&mut stderr));
+        }));
+    }
+    /// adds a copy of `error` as the newest entry, dropping the oldest one when full
+    pub fn record(&self, error: &CursedErrorHandle) {
+        if self.capacity == 0 {
+            return
+        }
+
+        let mut inner = Inner::empty(*error.get_error());
+        let reason = error.get_reason();
+        inner.reason = Reason::Owned(cut(reason, MAX_ENTRY_REASON_LEN).unwrap_or_else(|| reason.to_string()).into());
+        inner.op = error.op();
+        inner.namespace = error.namespace();
+        inner.os_code = error.os_code();
+        inner.location = error.location();
+        let entry = (SystemTime::now(), CursedErrorHandle::from_inner_copy(inner));
+
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+    /// the newest `n` entries, the oldest of them first
+    pub fn recent(&self, n: usize) -> Vec<CursedErrorHandle> {
+        let entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.iter().skip(entries.len().saturating_sub(n)).map(|(_, error)| error.clone()).collect()
+    }
+    /// writes every entry, the oldest first, as a line with the UTC time it was recorded
+    /// and the [compact form](CursedErrorHandle::display_compact) of the error
+    pub fn dump_to(&self, out: &mut impl std::io::Write) -> std::io::Result<()> {
+        let entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+        for (at, error) in &entries {
+            writeln!(out, "{} {}", format_timestamp(*at), error.display_compact(MAX_ENTRY_REASON_LEN))?;
+        }
+        Ok(())
+    }
+}
+
+/// `at` as an RFC 3339 timestamp in UTC with milliseconds, e.g. `2026-10-14T09:30:00.125Z`
+fn format_timestamp(at: SystemTime) -> String {
+    let since_epoch = at.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+    let (days, seconds) = (since_epoch.as_secs() / 86_400, since_epoch.as_secs() % 86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
@@ -0,0 +1,137 @@
+//! bounded pool of shared reasons for [`crate::CursedErrorHandle::new_interned`]
+
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+const DEFAULT_CAPACITY: usize = 1_024;
+
+/// the pool is split by the hash of the reasons, so threads creating errors with
+/// different reasons don't wait for each other
+const SHARDS: usize = 16;
+
+static SHARDS_OF_POOL: [Shard; SHARDS] = [const { Shard::new() }; SHARDS];
+static CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_CAPACITY);
+/// reasons in all shards, never more than the capacity
+static SIZE: AtomicUsize = AtomicUsize::new(0);
+
+struct Shard {
+    reasons: RwLock<BTreeSet<Arc<str>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Shard {
+    const fn new() -> Self {
+        Shard { reasons: RwLock::new(BTreeSet::new()), hits: AtomicU64::new(0), misses: AtomicU64::new(0) }
+    }
+}
+
+/// the shard of `reason` by the FNV-1a hash of its bytes, which is quick for short strings
+fn shard_of(reason: &str) -> &'static Shard {
+    let hash = reason.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    &SHARDS_OF_POOL[hash as usize % SHARDS]
+}
+
+/// shared copy of `reason` from the pool, added to it on a miss while it isn't full
+///
+/// Hits only take the read lock of one shard.
+pub(crate) fn intern(reason: &str) -> Arc<str> {
+    let shard = shard_of(reason);
+
+    if let Some(interned) = shard.reasons.read().unwrap_or_else(|poisoned| poisoned.into_inner()).get(reason) {
+        shard.hits.fetch_add(1, Ordering::Relaxed);
+        return interned.clone()
+    }
+
+    let mut reasons = shard.reasons.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    // added by another thread since the read lock was released
+    if let Some(interned) = reasons.get(reason) {
+        shard.hits.fetch_add(1, Ordering::Relaxed);
+        return interned.clone()
+    }
+
+    shard.misses.fetch_add(1, Ordering::Relaxed);
+    let interned: Arc<str> = reason.into();
+    let reserved = SIZE.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |size| {
+        (size < CAPACITY.load(Ordering::Relaxed)).then_some(size + 1)
+    });
+    if reserved.is_ok() {
+        reasons.insert(interned.clone());
+    }
+    interned
+}
+
+/// sets how many distinct reasons the pool keeps, 1024 by default
+///
+/// Once the pool is full new reasons are not added, the errors get their own copy. The
+/// pool keeps the reasons it has, so it should be big enough for the reasons that
+/// repeat, see [`intern_stats`]. Lowering the capacity drops reasons right away until
+/// it fits, which ones is unspecified, `0` disables interning. Handles keep their reasons
+/// after they are dropped from the pool.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// set_intern_capacity(2);
+///
+/// let error = |reason| CursedErrorHandle::new_interned(CursedError::Connection(CursedErrorType::Reset), reason);
+/// let broken = error("broken pipe");
+/// error("connection reset by peer");
+/// let refused = error("connection refused");
+///
+/// // the pool was full for "connection refused"
+/// assert_eq!(intern_stats().size, 2);
+/// assert!(std::ptr::eq(broken.get_reason(), error("broken pipe").get_reason()));
+/// assert!(!std::ptr::eq(refused.get_reason(), error("connection refused").get_reason()));
+/// assert_eq!(intern_stats().misses, 4);
+///
+/// set_intern_capacity(0);
+/// assert_eq!(intern_stats().size, 0);
+/// assert_eq!(broken.get_reason(), "broken pipe");
+/// ```
+pub fn set_intern_capacity(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+
+    for shard in &SHARDS_OF_POOL {
+        let mut reasons = shard.reasons.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        while SIZE.load(Ordering::Relaxed) > capacity && reasons.pop_first().is_some() {
+            SIZE.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// size and lookups of the pool used by [`crate::CursedErrorHandle::new_interned`]
+///
+/// The counts of the shards are read one after another, so lookups running at the same
+/// time may be counted or not.
+pub fn intern_stats() -> InternStats {
+    InternStats {
+        size: SIZE.load(Ordering::Relaxed),
+        capacity: CAPACITY.load(Ordering::Relaxed),
+        hits: SHARDS_OF_POOL.iter().map(|shard| shard.hits.load(Ordering::Relaxed)).sum(),
+        misses: SHARDS_OF_POOL.iter().map(|shard| shard.misses.load(Ordering::Relaxed)).sum(),
+    }
+}
+
+/// snapshot returned by [`intern_stats`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InternStats {
+    /// distinct reasons in the pool
+    pub size: usize,
+    pub capacity: usize,
+    /// lookups that found the reason in the pool
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl InternStats {
+    /// share of the lookups that were hits, `0` before the first lookup
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
@@ -0,0 +1,1084 @@
+//! kinds of errors: categories, types and severities
+
+use crate::*;
+use crate::convert::{classify_text, classify_type, io_kind_from_name, CLASSIFY_BY_TEXT};
+use crate::policy;
+use crate::registry::spec_of;
+
+/// enum with kinds of errors
+/// # Examples
+/// ```
+/// use curerr::*;
+/// 
+/// let error = CursedErrorHandle::new(
+///     CursedError::Path(CursedErrorType::Invalid),
+///     "path is invalid".to_string()
+/// );
+/// 
+/// assert_eq!(format!("{}", error), "path invalid error: \"path is invalid\"".to_string());
+/// ```
+///
+/// Errors sent by newer versions of the crate can have kinds this one doesn't know.
+/// [`CursedError::from_code`], the serde impls and [`CursedErrorHandle::decode`] read
+/// unknown categories and numeric codes as [`CursedError::Unknown`] and unknown type
+/// names as [`CursedErrorType::Custom`] instead of failing, deserialized handles keep
+/// the original in the `unknown_category` or `unknown_code` field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CursedError {
+    Connection(CursedErrorType),
+    Address(CursedErrorType),
+    Memory(CursedErrorType),
+    Buffer(CursedErrorType),
+    Envvar(CursedErrorType),
+    Other(CursedErrorType),
+    Input(CursedErrorType),
+    File(CursedErrorType),
+    Path(CursedErrorType),
+    Data(CursedErrorType),
+    Call(CursedErrorType),
+    /// failed operating system IO that doesn't belong to a more specific category,
+    /// what most [`std::io::ErrorKind`]s convert to
+    ///
+    /// Before this category existed `NotFound`, `PermissionDenied`, `AlreadyExists`,
+    /// `Interrupted` and `Unsupported` became [`CursedError::Other`], which is now only
+    /// used for errors that have no category at all.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::io::ErrorKind;
+    ///
+    /// let error = CursedError::from(ErrorKind::PermissionDenied);
+    ///
+    /// assert_eq!(error, CursedError::Io(CursedErrorType::AccessDenied));
+    /// assert_eq!(error.to_string(), "io access denied");
+    /// assert_eq!(error.code(), "IO_ACCESS_DENIED");
+    /// assert_eq!(error.numeric_code(), 1203);
+    /// assert_eq!("io access denied".parse::<CursedError>().unwrap(), error);
+    /// assert_eq!(ErrorKind::from(error), ErrorKind::PermissionDenied);
+    ///
+    /// assert_eq!(CursedError::from(ErrorKind::NotFound).to_string(), "io not found");
+    /// assert_eq!(CursedError::from(ErrorKind::AlreadyExists).to_string(), "io already exists");
+    /// assert_eq!(CursedError::from(ErrorKind::Interrupted).to_string(), "io interrupted");
+    /// assert_eq!(CursedError::from(ErrorKind::Unsupported).to_string(), "io not supported");
+    /// ```
+    Io(CursedErrorType),
+    /// failed TLS and certificate checks, e.g. an expired or untrusted certificate is
+    /// `Security(Invalid)` and a rejected peer is `Security(AccessDenied)`
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedError::Security(CursedErrorType::Invalid);
+    ///
+    /// assert_eq!(error.to_string(), "security invalid");
+    /// assert_eq!(error.code(), "SECURITY_INVALID");
+    /// assert_eq!(error.numeric_code(), 1311);
+    /// assert_eq!("security invalid".parse::<CursedError>().unwrap(), error);
+    /// assert_eq!(error.exit_code(), 77);
+    /// assert!(!error.is_retryable());
+    /// ```
+    Security(CursedErrorType),
+    /// failed queries and transactions of a database, filled by the `db_*` constructors of
+    /// [`CursedErrorHandle`] so services map the errors of their drivers the same way
+    ///
+    /// An unavailable database is `Database(Refused)`, a violated constraint
+    /// `Database(AlreadyExists)`, a query running too long `Database(Timedout)` and a
+    /// transaction that failed to serialize `Database(Aborted)`.
+    ///
+    /// Before this category existed `database` was parsed as a custom category, the
+    /// string codes are unchanged but the numeric codes moved from 98xx to 14xx. Handles
+    /// and [`CursedCategory::with_type`] still given `Custom { category: "database" }`
+    /// hold `Database` instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedError::Database(CursedErrorType::Aborted);
+    ///
+    /// assert_eq!(error.to_string(), "database aborted");
+    /// assert_eq!(error.code(), "DATABASE_ABORTED");
+    /// assert_eq!(error.numeric_code(), 1412);
+    /// assert_eq!("database aborted".parse::<CursedError>().unwrap(), error);
+    /// assert_eq!(error.http_status(), 409);
+    /// assert!(error.is_retryable());
+    /// ```
+    Database(CursedErrorType),
+    /// category outside of the predefined ones, e.g. `"queue"`
+    ///
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedError::Custom { category: "queue", kind: CursedErrorType::Timedout };
+    ///
+    /// assert_eq!(error.to_string(), "queue timed out");
+    /// assert_eq!(error.code(), "QUEUE_TIMED_OUT");
+    /// assert_eq!(error.category(), CursedCategory::Custom("queue"));
+    /// assert_eq!(error.severity(), CursedSeverity::Warning);
+    /// assert_eq!(std::io::ErrorKind::from(error), std::io::ErrorKind::TimedOut);
+    /// assert_eq!("queue timed out".parse::<CursedError>().unwrap(), error);
+    /// ```
+    Custom {
+        category: &'static str,
+        kind: CursedErrorType,
+    },
+    NoError,
+    /// an error that doesn't fit any category, converted [`std::io::ErrorKind`]s without
+    /// a mapping keep the original kind
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::io::ErrorKind;
+    ///
+    /// let error = CursedError::from(ErrorKind::FileTooLarge);
+    ///
+    /// assert_eq!(error, CursedError::Unknown(Some(ErrorKind::FileTooLarge)));
+    /// assert_eq!(error.to_string(), "unknown (FileTooLarge)");
+    /// assert_eq!("unknown (FileTooLarge)".parse::<CursedError>().unwrap(), error);
+    /// assert_eq!(ErrorKind::from(error), ErrorKind::FileTooLarge);
+    /// assert_eq!(CursedError::from(ErrorKind::Other), CursedError::Unknown(None));
+    /// assert!(cursed_match!(error, Unknown));
+    /// ```
+    Unknown(Option<std::io::ErrorKind>),
+}
+
+impl std::fmt::Display for CursedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CursedError::Connection(err) => write!(f, "connection {}", err.to_str()),
+            CursedError::Address(err) => write!(f, "address {}", err.to_str()),
+            CursedError::Buffer(err) => write!(f, "buffer {}", err.to_str()),
+            CursedError::Envvar(err) => write!(f, "envvar {}", err.to_str()),
+            CursedError::Memory(err) => write!(f, "memory {}", err.to_str()),
+            CursedError::Input(err) => write!(f, "input {}", err.to_str()),
+            CursedError::File(err) => write!(f, "file {}", err.to_str()),
+            CursedError::Path(err) => write!(f, "path {}", err.to_str()),
+            CursedError::Call(err) => write!(f, "call {}", err.to_str()), 
+            CursedError::Data(err) => write!(f, "data {}", err.to_str()),
+            CursedError::Io(err) => write!(f, "io {}", err.to_str()),
+            CursedError::Security(err) => write!(f, "security {}", err.to_str()),
+            CursedError::Database(err) => write!(f, "database {}", err.to_str()),
+            CursedError::Other(err) => f.write_str(err.to_str()),
+            CursedError::Custom { category, kind } => write!(f, "{} {}", category, kind.to_str()),
+            CursedError::NoError => f.write_str("no error"),
+            CursedError::Unknown(None) => f.write_str("unknown"),
+            CursedError::Unknown(Some(kind)) => write!(f, "unknown ({:?})", kind),
+        }        
+    }
+}
+
+impl CursedError {
+    /// creates a handle of this kind with the `reason`, the same as [`CursedErrorHandle::new`]
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedError::from(std::io::ErrorKind::TimedOut).with_reason("replica took too long");
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Timedout));
+    /// assert_eq!(error.to_string(), "call timed out error: \"replica took too long\"");
+    ///
+    /// let error = CursedError::from_code("DATA_PARSE").with_reason(format!("line {}", 7));
+    /// assert_eq!(error.get_reason(), "line 7");
+    /// ```
+    #[track_caller]
+    pub fn with_reason(self, reason: impl Into<String>) -> CursedErrorHandle {
+        CursedErrorHandle::new(self, reason.into())
+    }
+    /// whether both errors have the same category, custom categories are compared by name
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let queue = |kind| CursedError::Custom { category: "queue", kind };
+    ///
+    /// assert!(CursedError::File(CursedErrorType::NotFound).same_category(&CursedError::File(CursedErrorType::Invalid)));
+    /// assert!(!CursedError::File(CursedErrorType::NotFound).same_category(&CursedError::Path(CursedErrorType::NotFound)));
+    /// assert!(queue(CursedErrorType::Timedout).same_category(&queue(CursedErrorType::Refused)));
+    /// assert!(!queue(CursedErrorType::Timedout).same_category(&CursedError::Custom { category: "cache", kind: CursedErrorType::Timedout }));
+    /// assert!(CursedError::NoError.same_category(&CursedError::NoError));
+    /// assert!(CursedError::Unknown(None).same_category(&CursedError::Unknown(None)));
+    /// ```
+    pub fn same_category(&self, other: &Self) -> bool {
+        self.category() == other.category()
+    }
+    /// returns the category of the error, the variant without its type
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedError::Connection(CursedErrorType::Refused);
+    ///
+    /// assert_eq!(error.category(), CursedCategory::Connection);
+    /// assert_eq!(error.category().to_id(), "connection");
+    /// ```
+    pub const fn category(&self) -> CursedCategory {
+        match self {
+            CursedError::Connection(_) => CursedCategory::Connection,
+            CursedError::Address(_) => CursedCategory::Address,
+            CursedError::Memory(_) => CursedCategory::Memory,
+            CursedError::Buffer(_) => CursedCategory::Buffer,
+            CursedError::Envvar(_) => CursedCategory::Envvar,
+            CursedError::Other(_) => CursedCategory::Other,
+            CursedError::Input(_) => CursedCategory::Input,
+            CursedError::File(_) => CursedCategory::File,
+            CursedError::Path(_) => CursedCategory::Path,
+            CursedError::Data(_) => CursedCategory::Data,
+            CursedError::Call(_) => CursedCategory::Call,
+            CursedError::Io(_) => CursedCategory::Io,
+            CursedError::Security(_) => CursedCategory::Security,
+            CursedError::Database(_) => CursedCategory::Database,
+            CursedError::Custom { category, .. } => CursedCategory::Custom(category),
+            CursedError::NoError => CursedCategory::NoError,
+            CursedError::Unknown(_) => CursedCategory::Unknown,
+        }
+    }
+    /// machine readable code of the error, e.g. `"CONNECTION_REFUSED"`
+    ///
+    /// Codes of custom categories are their name uppercased with everything except
+    /// letters and digits replaced by `_`, [`CursedError::from_code`] reads them back as
+    /// `Unknown(None)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// assert_eq!(CursedError::Connection(CursedErrorType::Refused).code(), "CONNECTION_REFUSED");
+    /// assert_eq!(CursedError::Other(CursedErrorType::NotFound).code(), "OTHER_NOT_FOUND");
+    /// assert_eq!(CursedError::NoError.code(), "NO_ERROR");
+    /// ```
+    pub fn code(&self) -> std::borrow::Cow<'static, str> {
+        fn code_part(name: &str) -> String {
+            name.chars()
+                .map(|c| if c.is_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+                .collect()
+        }
+
+        match (self, self.get_type()) {
+            (CursedError::Custom { .. }, Some(kind)) | (_, Some(kind @ CursedErrorType::Custom(_))) => format!(
+                "{}_{}",
+                code_part(self.category().to_id()),
+                code_part(kind.to_id())
+            ).into(),
+            _ => spec_of(self).code.into(),
+        }
+    }
+    /// stable numeric code of the error, `category * 100 + type`
+    ///
+    /// Categories and types are numbered from 1 in declaration order.
+    /// [`CursedError::NoError`] is 0 and [`CursedError::Unknown`] is 9999, custom
+    /// categories use [`CursedCategory::CUSTOM_CODE`] and custom types
+    /// [`CursedErrorType::CUSTOM_CODE`], so their names are lost.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedError::Connection(CursedErrorType::Refused);
+    ///
+    /// assert_eq!(error.numeric_code(), 110);
+    /// assert_eq!(CursedError::from_numeric_code(110), Some(error));
+    /// assert_eq!(CursedError::NoError.numeric_code(), 0);
+    /// ```
+    pub fn numeric_code(&self) -> u32 {
+        match (self, self.get_type()) {
+            (CursedError::Custom { .. }, Some(CursedErrorType::Custom(_))) => {
+                CursedCategory::CUSTOM_CODE * 100 + CursedErrorType::CUSTOM_CODE
+            },
+            (CursedError::Custom { .. }, _) => CursedCategory::CUSTOM_CODE * 100 + spec_of(self).numeric_code % 100,
+            _ => spec_of(self).numeric_code,
+        }
+    }
+    /// parses [`CursedError::numeric_code`], `None` for unassigned and custom codes
+    pub fn from_numeric_code(code: u32) -> Option<CursedError> {
+        match code {
+            0 => return Some(CursedError::NoError),
+            9999 => return Some(CursedError::Unknown(None)),
+            _ => (),
+        }
+
+        let category = TYPED_CATEGORIES.get((code / 100).checked_sub(1)? as usize)?;
+        let error_type = ERROR_TYPES.get((code % 100).checked_sub(1)? as usize)?;
+
+        Some(category.with_type(*error_type))
+    }
+    /// parses [`CursedError::code`], unknown categories become [`CursedError::Unknown`]
+    ///
+    /// Unknown types of a predefined category become [`CursedErrorType::Custom`] types
+    /// with the lowercased name, or unknown errors once 256 custom names read from input
+    /// are kept. Codes of custom categories can't be told apart from categories added
+    /// later, so they are unknown as well.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// assert_eq!(CursedError::from_code("FILE_NOT_FOUND"), CursedError::File(CursedErrorType::NotFound));
+    /// assert_eq!(CursedError::from_code("NO_ERROR"), CursedError::NoError);
+    /// assert_eq!(
+    ///     CursedError::from_code("CONNECTION_RATE_LIMITED"),
+    ///     CursedError::Connection(CursedErrorType::Custom("rate limited"))
+    /// );
+    /// assert_eq!(CursedError::from_code("QUANTUM_FLUX_ERROR"), CursedError::Unknown(None));
+    /// assert_eq!(CursedError::from_code("QUEUE_TIMED_OUT"), CursedError::Unknown(None));
+    /// ```
+    pub fn from_code(code: &str) -> CursedError {
+        if code == "NO_ERROR" {
+            return CursedError::NoError
+        }
+
+        for category in TYPED_CATEGORIES {
+            let error_type = match code.strip_prefix(category.to_id().to_uppercase().as_str()).and_then(|rest| rest.strip_prefix('_')) {
+                Some(error_type) if !error_type.is_empty() => error_type.to_lowercase(),
+                _ => continue,
+            };
+
+            let error_type = CursedErrorType::from_id(&error_type)
+                .or_else(|| intern_name(&error_type.replace('_', " ")).map(CursedErrorType::Custom));
+            return error_type.map_or(CursedError::Unknown(None), |error_type| category.with_type(error_type))
+        }
+
+        CursedError::Unknown(None)
+    }
+    /// guesses the kind of an arbitrary error
+    ///
+    /// The source chain is walked and the first error of a known std type decides the kind,
+    /// io errors are classified by their [`ErrorKind`](std::io::ErrorKind). When nothing is recognized and
+    /// [`classify_by_text`] is enabled, the messages are searched for phrases like
+    /// `"permission denied"` or `"timed out"`, otherwise the kind is [`CursedError::Unknown`].
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::error::Error;
+    ///
+    /// let cases: Vec<(Box<dyn Error>, CursedError)> = vec![
+    ///     (std::io::Error::from(std::io::ErrorKind::NotFound).into(), CursedError::Io(CursedErrorType::NotFound)),
+    ///     ("x".parse::<std::net::IpAddr>().unwrap_err().into(), CursedError::Address(CursedErrorType::Parse)),
+    ///     (std::str::from_utf8(&[0xff]).unwrap_err().into(), CursedError::Data(CursedErrorType::Invalid)),
+    ///     (String::from_utf8(vec![0xff]).unwrap_err().into(), CursedError::Data(CursedErrorType::Invalid)),
+    ///     ("x".parse::<i32>().unwrap_err().into(), CursedError::Data(CursedErrorType::Parse)),
+    ///     ("x".parse::<f64>().unwrap_err().into(), CursedError::Data(CursedErrorType::Parse)),
+    ///     ("x".parse::<bool>().unwrap_err().into(), CursedError::Data(CursedErrorType::Parse)),
+    ///     (u8::try_from(300_i32).unwrap_err().into(), CursedError::Data(CursedErrorType::Overflow)),
+    ///     (std::env::var("CURERR_NOT_SET").unwrap_err().into(), CursedError::Envvar(CursedErrorType::NotFound)),
+    ///     (std::time::UNIX_EPOCH.duration_since(std::time::SystemTime::now()).unwrap_err().into(), CursedError::Other(CursedErrorType::Invalid)),
+    ///     (Vec::<u8>::new().try_reserve(usize::MAX).unwrap_err().into(), CursedError::Memory(CursedErrorType::NotEnough)),
+    ///     ("permission denied".into(), CursedError::Unknown(None)),
+    /// ];
+    ///
+    /// for (error, expected) in cases {
+    ///     assert_eq!(CursedError::classify(&*error), expected, "{}", error);
+    /// }
+    ///
+    /// let wrapped = CursedErrorHandle::new(CursedError::Unknown(None), "can't load".to_string())
+    ///     .with_source("x".parse::<i32>().unwrap_err());
+    /// assert_eq!(CursedError::classify(&wrapped), CursedError::Data(CursedErrorType::Parse));
+    /// ```
+    pub fn classify(error: &(dyn std::error::Error + 'static)) -> CursedError {
+        let mut source = Some(error);
+        while let Some(error) = source {
+            if let Some(kind) = classify_type(error) {
+                return kind;
+            }
+            source = error.source();
+        }
+
+        if CLASSIFY_BY_TEXT.load(std::sync::atomic::Ordering::Relaxed) {
+            let mut source = Some(error);
+            while let Some(error) = source {
+                if let Some(kind) = classify_text(&error.to_string()) {
+                    return kind;
+                }
+                source = error.source();
+            }
+        }
+
+        CursedError::Unknown(None)
+    }
+    /// how serious the error is
+    ///
+    /// Memory errors are critical, retryable ones are warnings, [`CursedError::NoError`]
+    /// is informational and everything else is an error.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// assert_eq!(CursedError::Memory(CursedErrorType::NotEnough).severity(), CursedSeverity::Critical);
+    /// assert_eq!(CursedError::Call(CursedErrorType::Timedout).severity(), CursedSeverity::Warning);
+    /// assert_eq!(CursedError::File(CursedErrorType::NotFound).severity(), CursedSeverity::Error);
+    /// ```
+    pub fn severity(&self) -> CursedSeverity {
+        policy::severity(self).unwrap_or(spec_of(self).severity)
+    }
+    /// HTTP status code that fits the error best
+    ///
+    /// Invalid input is a client error, invalid data is unprocessable and failures of
+    /// other calls or connections are reported as gateway errors. Errors without a
+    /// fitting status, including custom types, are internal server errors.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// assert_eq!(CursedError::File(CursedErrorType::NotFound).http_status(), 404);
+    /// assert_eq!(CursedError::Input(CursedErrorType::Parse).http_status(), 400);
+    /// assert_eq!(CursedError::Data(CursedErrorType::Invalid).http_status(), 422);
+    /// assert_eq!(CursedError::Call(CursedErrorType::Timedout).http_status(), 504);
+    /// assert_eq!(CursedError::Memory(CursedErrorType::NotEnough).http_status(), 503);
+    /// assert_eq!(CursedError::Unknown(None).http_status(), 500);
+    /// ```
+    pub fn http_status(&self) -> u16 {
+        policy::http_status(self).unwrap_or(spec_of(self).http_status)
+    }
+    /// kind of an error response received from an HTTP service
+    ///
+    /// Statuses below 400 are no errors. Rejected credentials (401, 403) are
+    /// `Security(AccessDenied)`, the other client errors describe the call, e.g. throttling
+    /// (429) is `Call(NotEnough)`, running out of a quota. An unavailable service (503) is
+    /// `Connection(Refused)`. Statuses without a fitting kind are unknown.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let cases = [
+    ///     (204, CursedError::NoError),
+    ///     (400, CursedError::Input(CursedErrorType::Invalid)),
+    ///     (401, CursedError::Security(CursedErrorType::AccessDenied)),
+    ///     (404, CursedError::Call(CursedErrorType::NotFound)),
+    ///     (408, CursedError::Call(CursedErrorType::Timedout)),
+    ///     (413, CursedError::Input(CursedErrorType::Overflow)),
+    ///     (409, CursedError::Call(CursedErrorType::AlreadyExists)),
+    ///     (418, CursedError::Unknown(None)),
+    ///     (422, CursedError::Data(CursedErrorType::Invalid)),
+    ///     (429, CursedError::Call(CursedErrorType::NotEnough)),
+    ///     (500, CursedError::Unknown(None)),
+    ///     (502, CursedError::Connection(CursedErrorType::Refused)),
+    ///     (503, CursedError::Connection(CursedErrorType::Refused)),
+    ///     (504, CursedError::Call(CursedErrorType::Timedout)),
+    /// ];
+    /// for (status, error) in cases {
+    ///     assert_eq!(CursedError::from_http_status(status), error, "{}", status);
+    /// }
+    /// ```
+    pub fn from_http_status(status: u16) -> CursedError {
+        match status {
+            ..=399 => CursedError::NoError,
+            400 => CursedError::Input(CursedErrorType::Invalid),
+            401 | 403 => CursedError::Security(CursedErrorType::AccessDenied),
+            404 | 410 => CursedError::Call(CursedErrorType::NotFound),
+            405 => CursedError::Call(CursedErrorType::NotSupported),
+            408 => CursedError::Call(CursedErrorType::Timedout),
+            409 => CursedError::Call(CursedErrorType::AlreadyExists),
+            413 => CursedError::Input(CursedErrorType::Overflow),
+            422 => CursedError::Data(CursedErrorType::Invalid),
+            429 => CursedError::Call(CursedErrorType::NotEnough),
+            501 => CursedError::Call(CursedErrorType::NotImplemented),
+            502 | 503 => CursedError::Connection(CursedErrorType::Refused),
+            504 => CursedError::Call(CursedErrorType::Timedout),
+            _ => CursedError::Unknown(None),
+        }
+    }
+    /// process exit code that fits the error best, following the BSD `sysexits.h` codes
+    ///
+    /// | error | code |
+    /// |-|-|
+    /// | `NoError` | 0 |
+    /// | `Input` | 64 (usage) |
+    /// | `Data` | 65 (data error) |
+    /// | `File`, `Path` or `Io` with `NotFound` | 66 (no input) |
+    /// | `Connection`, `Address` or `Database` | 69 (unavailable) |
+    /// | `Memory` | 71 (OS error) |
+    /// | `File`, `Buffer` or `Io` | 74 (IO error) |
+    /// | any `Timedout` or `Interrupted` | 75 (temporary failure) |
+    /// | any `AccessDenied` | 77 (no permission) |
+    /// | `Security` | 77 (no permission) |
+    /// | `Envvar` | 78 (configuration error) |
+    /// | everything else | 1 |
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// assert_eq!(CursedError::Input(CursedErrorType::Invalid).exit_code(), 64);
+    /// assert_eq!(CursedError::File(CursedErrorType::NotFound).exit_code(), 66);
+    /// assert_eq!(CursedError::Other(CursedErrorType::AccessDenied).exit_code(), 77);
+    /// assert_eq!(CursedError::Unknown(None).exit_code(), 1);
+    /// ```
+    pub fn exit_code(&self) -> u8 {
+        policy::exit_code(self).unwrap_or(spec_of(self).exit_code)
+    }
+    unstable! {
+        /// every error without custom parts, the predefined categories with every predefined
+        /// type followed by [`CursedError::NoError`] and `Unknown(None)`
+        ///
+        /// [`registry::REGISTRY`](crate::registry::REGISTRY) has a row for each of them.
+        ///
+        /// # Examples
+        /// ```
+        /// use curerr::*;
+        ///
+        /// let errors = CursedError::all_combinations();
+        ///
+        /// assert_eq!(errors[0], CursedError::Connection(CursedErrorType::NotImplemented));
+        /// assert_eq!(errors[errors.len() - 1], CursedError::Unknown(None));
+        /// assert!(errors.contains(&CursedError::Security(CursedErrorType::Invalid)));
+        /// ```
+        pub fn all_combinations() -> &'static [CursedError] {
+            &ALL_ERRORS
+        }
+    }
+    /// returns the error type, `None` for [`CursedError::NoError`] and [`CursedError::Unknown`]
+    pub fn get_type(&self) -> Option<CursedErrorType> {
+        match self {
+            CursedError::Connection(err)
+            | CursedError::Address(err)
+            | CursedError::Memory(err)
+            | CursedError::Buffer(err)
+            | CursedError::Envvar(err)
+            | CursedError::Other(err)
+            | CursedError::Input(err)
+            | CursedError::File(err)
+            | CursedError::Path(err)
+            | CursedError::Data(err)
+            | CursedError::Call(err)
+            | CursedError::Io(err)
+            | CursedError::Security(err)
+            | CursedError::Database(err)
+            | CursedError::Custom { kind: err, .. } => Some(*err),
+            CursedError::NoError | CursedError::Unknown(_) => None,
+        }
+    }
+    /// whether the failure is transient, so repeating the operation may succeed
+    ///
+    /// Interrupted and timed out errors are always transient, refused, reset and
+    /// aborted ones only for connections.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// assert!(CursedError::Connection(CursedErrorType::Refused).is_retryable());
+    /// assert!(CursedError::Call(CursedErrorType::Timedout).is_retryable());
+    /// assert!(!CursedError::File(CursedErrorType::NotFound).is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        policy::retryable(self).unwrap_or(spec_of(self).retryable)
+    }
+}
+
+pub(crate) const ERROR_TYPES: [CursedErrorType; 14] = [
+    CursedErrorType::NotImplemented,
+    CursedErrorType::AlreadyExists,
+    CursedErrorType::AccessDenied,
+    CursedErrorType::NotSupported,
+    CursedErrorType::Interrupted,
+    CursedErrorType::NotEnough,
+    CursedErrorType::Timedout,
+    CursedErrorType::Overflow,
+    CursedErrorType::NotFound,
+    CursedErrorType::Refused,
+    CursedErrorType::Invalid,
+    CursedErrorType::Aborted,
+    CursedErrorType::Reset,
+    CursedErrorType::Parse,
+];
+
+pub(crate) const TYPED_CATEGORIES: [CursedCategory; 14] = [
+    CursedCategory::Connection,
+    CursedCategory::Address,
+    CursedCategory::Memory,
+    CursedCategory::Buffer,
+    CursedCategory::Envvar,
+    CursedCategory::Other,
+    CursedCategory::Input,
+    CursedCategory::File,
+    CursedCategory::Path,
+    CursedCategory::Data,
+    CursedCategory::Call,
+    CursedCategory::Io,
+    CursedCategory::Security,
+    CursedCategory::Database,
+];
+
+// every error, the typed categories combined with every type followed by `NoError` and
+// `Unknown`, indexed by `CursedError::index`
+pub(crate) static ALL_ERRORS: [CursedError; TYPED_CATEGORIES.len() * ERROR_TYPES.len() + 2] = {
+    let mut errors = [CursedError::Unknown(None); TYPED_CATEGORIES.len() * ERROR_TYPES.len() + 2];
+
+    let mut category = 0;
+    while category < TYPED_CATEGORIES.len() {
+        let mut error_type = 0;
+        while error_type < ERROR_TYPES.len() {
+            errors[category * ERROR_TYPES.len() + error_type] =
+                TYPED_CATEGORIES[category].with_type(ERROR_TYPES[error_type]);
+            error_type += 1;
+        }
+        category += 1;
+    }
+    errors[TYPED_CATEGORIES.len() * ERROR_TYPES.len()] = CursedError::NoError;
+
+    errors
+};
+
+const _: () = {
+    let mut index = 0;
+    while index < ALL_ERRORS.len() {
+        assert!(ALL_ERRORS[index].index() as usize == index);
+        index += 1;
+    }
+};
+
+impl CursedError {
+    /// the error with a custom `"database"` category as [`CursedError::Database`], the
+    /// category it was parsed as before `Database` existed
+    pub(crate) const fn normalized(self) -> Self {
+        const fn is_database(category: &str) -> bool {
+            let (category, database) = (category.as_bytes(), b"database");
+            if category.len() != database.len() {
+                return false
+            }
+            let mut index = 0;
+            while index < database.len() {
+                if category[index] != database[index] {
+                    return false
+                }
+                index += 1;
+            }
+            true
+        }
+
+        match self {
+            CursedError::Custom { category, kind } if is_database(category) => CursedError::Database(kind),
+            error => error,
+        }
+    }
+    /// the position in `ALL_ERRORS` and the registry, panics for errors with custom parts
+    pub(crate) const fn index(&self) -> u16 {
+        let category = match self.category() {
+            CursedCategory::Connection => 0,
+            CursedCategory::Address => 1,
+            CursedCategory::Memory => 2,
+            CursedCategory::Buffer => 3,
+            CursedCategory::Envvar => 4,
+            CursedCategory::Other => 5,
+            CursedCategory::Input => 6,
+            CursedCategory::File => 7,
+            CursedCategory::Path => 8,
+            CursedCategory::Data => 9,
+            CursedCategory::Call => 10,
+            CursedCategory::Io => 11,
+            CursedCategory::Security => 12,
+            CursedCategory::Database => 13,
+            CursedCategory::Custom(_) => panic!("custom errors have no index"),
+            CursedCategory::NoError => return (TYPED_CATEGORIES.len() * ERROR_TYPES.len()) as u16,
+            CursedCategory::Unknown => return (TYPED_CATEGORIES.len() * ERROR_TYPES.len()) as u16 + 1,
+        };
+        let error_type = match self {
+            CursedError::Connection(err)
+            | CursedError::Address(err)
+            | CursedError::Memory(err)
+            | CursedError::Buffer(err)
+            | CursedError::Envvar(err)
+            | CursedError::Other(err)
+            | CursedError::Input(err)
+            | CursedError::File(err)
+            | CursedError::Path(err)
+            | CursedError::Data(err)
+            | CursedError::Call(err)
+            | CursedError::Io(err)
+            | CursedError::Security(err)
+            | CursedError::Database(err)
+            | CursedError::Custom { kind: err, .. } => match err {
+                CursedErrorType::NotImplemented => 0,
+                CursedErrorType::AlreadyExists => 1,
+                CursedErrorType::AccessDenied => 2,
+                CursedErrorType::NotSupported => 3,
+                CursedErrorType::Interrupted => 4,
+                CursedErrorType::NotEnough => 5,
+                CursedErrorType::Timedout => 6,
+                CursedErrorType::Overflow => 7,
+                CursedErrorType::NotFound => 8,
+                CursedErrorType::Refused => 9,
+                CursedErrorType::Invalid => 10,
+                CursedErrorType::Aborted => 11,
+                CursedErrorType::Reset => 12,
+                CursedErrorType::Parse => 13,
+                CursedErrorType::Custom(_) => panic!("custom errors have no index"),
+            },
+            CursedError::NoError | CursedError::Unknown(_) => 0,
+        };
+
+        (category * ERROR_TYPES.len() + error_type) as u16
+    }
+}
+
+/// parses the Display form of the error, e.g. `"connection refused"`
+///
+/// Unrecognized category names become [`CursedError::Custom`] categories, unrecognized
+/// types after a predefined category become [`CursedErrorType::Custom`] types. Their
+/// names are leaked to get a `'static` lifetime, only 256 distinct names read from input
+/// are kept, strings with a new one are refused after that.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// assert_eq!("path invalid".parse::<CursedError>().unwrap(), CursedError::Path(CursedErrorType::Invalid));
+/// assert_eq!("not found".parse::<CursedError>().unwrap(), CursedError::Other(CursedErrorType::NotFound));
+/// assert_eq!("no error".parse::<CursedError>().unwrap(), CursedError::NoError);
+/// assert_eq!(
+///     "connection rate limited".parse::<CursedError>().unwrap(),
+///     CursedError::Connection(CursedErrorType::Custom("rate limited"))
+/// );
+/// assert!("exploded".parse::<CursedError>().is_err());
+/// ```
+impl std::str::FromStr for CursedError {
+    type Err = CursedErrorHandle;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let too_many = || CursedErrorHandle::new(
+            CursedError::Data(CursedErrorType::Overflow),
+            format!("`{}` has a new custom name and {} are already kept", s, MAX_NAMES)
+        );
+
+        match s {
+            "no error" => return Ok(CursedError::NoError),
+            "unknown" => return Ok(CursedError::Unknown(None)),
+            _ => (),
+        }
+
+        if let Some(kind) = s.strip_prefix("unknown (").and_then(|s| s.strip_suffix(')')) {
+            if let Some(kind) = io_kind_from_name(kind) {
+                return Ok(CursedError::Unknown(Some(kind)))
+            }
+        }
+
+        for error_type in ERROR_TYPES {
+            if s == error_type.to_str() {
+                return Ok(CursedError::Other(error_type))
+            }
+
+            let category = match s.strip_suffix(error_type.to_str()).and_then(|rest| rest.strip_suffix(' ')) {
+                Some(category) if !category.is_empty() => category,
+                _ => continue,
+            };
+
+            return match TYPED_CATEGORIES.iter().find(|known| known.to_id() == category) {
+                Some(known) => Ok(known.with_type(error_type)),
+                None => intern_name(category).map(|category| CursedError::Custom { category, kind: error_type }).ok_or_else(too_many),
+            }
+        }
+
+        if let Some((category, error_type)) = s.split_once(' ') {
+            if let Some(known) = TYPED_CATEGORIES.iter().find(|known| known.to_id() == category) {
+                return intern_name(error_type).map(|error_type| known.with_type(CursedErrorType::Custom(error_type))).ok_or_else(too_many)
+            }
+        }
+
+        Err(CursedErrorHandle::new(
+            CursedError::Data(CursedErrorType::Parse),
+            format!("`{}` is not a known error", s)
+        ))
+    }
+}
+
+/// the most custom names read from input that are kept for the whole process
+pub(crate) const MAX_NAMES: usize = 256;
+
+/// a `'static` copy of a custom name or op read from input, `None` once [`MAX_NAMES`]
+/// other names are kept, so that untrusted input can't leak more memory
+pub(crate) fn intern_name(name: &str) -> Option<&'static str> {
+    static NAMES: std::sync::Mutex<Vec<&'static str>> = std::sync::Mutex::new(Vec::new());
+
+    let mut names = NAMES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(interned) = names.iter().find(|interned| **interned == name) {
+        return Some(interned)
+    }
+    if names.len() >= MAX_NAMES {
+        return None
+    }
+
+    let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+    names.push(interned);
+    Some(interned)
+}
+
+/// enum with categories of errors, mirrors the variants of [`CursedError`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum CursedCategory {
+    Connection,
+    Address,
+    Memory,
+    Buffer,
+    Envvar,
+    Other,
+    Input,
+    File,
+    Path,
+    Data,
+    Call,
+    Io,
+    Security,
+    Database,
+    Custom(&'static str),
+    NoError,
+    Unknown,
+}
+
+impl CursedCategory {
+    /// category part of [`CursedError::numeric_code`] reserved for custom categories
+    pub const CUSTOM_CODE: u32 = 98;
+
+    /// machine readable name of the category, e.g. `"no_error"`, custom categories
+    /// return their name as is
+    pub fn to_id(&self) -> &'static str {
+        match self {
+            CursedCategory::Connection => "connection",
+            CursedCategory::Address => "address",
+            CursedCategory::Memory => "memory",
+            CursedCategory::Buffer => "buffer",
+            CursedCategory::Envvar => "envvar",
+            CursedCategory::Other => "other",
+            CursedCategory::Input => "input",
+            CursedCategory::File => "file",
+            CursedCategory::Path => "path",
+            CursedCategory::Data => "data",
+            CursedCategory::Call => "call",
+            CursedCategory::Io => "io",
+            CursedCategory::Security => "security",
+            CursedCategory::Database => "database",
+            CursedCategory::Custom(category) => category,
+            CursedCategory::NoError => "no_error",
+            CursedCategory::Unknown => "unknown",
+        }
+    }
+    /// parses the name returned by [`CursedCategory::to_id`], never returns custom categories
+    pub fn from_id(id: &str) -> Option<Self> {
+        Some(match id {
+            "connection" => CursedCategory::Connection,
+            "address" => CursedCategory::Address,
+            "memory" => CursedCategory::Memory,
+            "buffer" => CursedCategory::Buffer,
+            "envvar" => CursedCategory::Envvar,
+            "other" => CursedCategory::Other,
+            "input" => CursedCategory::Input,
+            "file" => CursedCategory::File,
+            "path" => CursedCategory::Path,
+            "data" => CursedCategory::Data,
+            "call" => CursedCategory::Call,
+            "io" => CursedCategory::Io,
+            "security" => CursedCategory::Security,
+            "database" => CursedCategory::Database,
+            "no_error" => CursedCategory::NoError,
+            "unknown" => CursedCategory::Unknown,
+            _ => return None,
+        })
+    }
+    /// combines the category with an error type, the type is ignored by
+    /// [`CursedCategory::NoError`] and [`CursedCategory::Unknown`]
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// assert_eq!(
+    ///     CursedCategory::File.with_type(CursedErrorType::NotFound),
+    ///     CursedError::File(CursedErrorType::NotFound)
+    /// );
+    /// ```
+    pub const fn with_type(self, error_type: CursedErrorType) -> CursedError {
+        match self {
+            CursedCategory::Connection => CursedError::Connection(error_type),
+            CursedCategory::Address => CursedError::Address(error_type),
+            CursedCategory::Memory => CursedError::Memory(error_type),
+            CursedCategory::Buffer => CursedError::Buffer(error_type),
+            CursedCategory::Envvar => CursedError::Envvar(error_type),
+            CursedCategory::Other => CursedError::Other(error_type),
+            CursedCategory::Input => CursedError::Input(error_type),
+            CursedCategory::File => CursedError::File(error_type),
+            CursedCategory::Path => CursedError::Path(error_type),
+            CursedCategory::Data => CursedError::Data(error_type),
+            CursedCategory::Call => CursedError::Call(error_type),
+            CursedCategory::Io => CursedError::Io(error_type),
+            CursedCategory::Security => CursedError::Security(error_type),
+            CursedCategory::Database => CursedError::Database(error_type),
+            CursedCategory::Custom(category) => CursedError::Custom { category, kind: error_type }.normalized(),
+            CursedCategory::NoError => CursedError::NoError,
+            CursedCategory::Unknown => CursedError::Unknown(None),
+        }
+    }
+}
+
+impl std::fmt::Display for CursedCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_id())
+    }
+}
+
+/// enum with severities of errors, ordered from the least to the most serious
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CursedSeverity {
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl CursedSeverity {
+    /// machine readable name of the severity, e.g. `"warning"`
+    pub fn to_id(&self) -> &'static str {
+        match self {
+            CursedSeverity::Info => "info",
+            CursedSeverity::Warning => "warning",
+            CursedSeverity::Error => "error",
+            CursedSeverity::Critical => "critical",
+        }
+    }
+}
+
+impl std::fmt::Display for CursedSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_id())
+    }
+}
+
+/// enum with error types
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CursedErrorType {
+    NotImplemented,
+    AlreadyExists,
+    AccessDenied,
+    NotSupported,
+    Interrupted,
+    NotEnough,
+    Timedout,
+    Overflow,
+    NotFound,
+    Refused,
+    Invalid,
+    Aborted,
+    Reset,
+    Parse,
+    /// type outside of the predefined ones, e.g. `"rate limited"`
+    ///
+    /// Custom types are not retryable, have the default severity, map to
+    /// [`std::io::ErrorKind::Other`] and use the phrase as their id. Their numeric
+    /// code is the reserved [`CursedErrorType::CUSTOM_CODE`].
+    ///
+    /// Custom names read from strings, codes, JSON or [encoded](CursedErrorHandle::decode)
+    /// errors are kept for the whole process, at most 256 of them. Past that a new name
+    /// is refused, or read as an unknown error with the name in an `unknown_type` or
+    /// `unknown_category` field.
+    ///
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = CursedError::Connection(CursedErrorType::Custom("rate limited"));
+    ///
+    /// assert_eq!(error.to_string(), "connection rate limited");
+    /// assert_eq!(error.code(), "CONNECTION_RATE_LIMITED");
+    /// assert_eq!(error.numeric_code(), 199);
+    /// assert!(!error.is_retryable());
+    /// assert_eq!(error.severity(), CursedSeverity::Error);
+    /// assert_eq!(std::io::ErrorKind::from(error), std::io::ErrorKind::Other);
+    /// ```
+    Custom(&'static str),
+}
+
+impl CursedErrorType {
+    /// type part of [`CursedError::numeric_code`] reserved for custom types
+    pub const CUSTOM_CODE: u32 = 99;
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            CursedErrorType::NotImplemented => "not implemented",
+            CursedErrorType::AlreadyExists => "already exists",
+            CursedErrorType::AccessDenied => "access denied",
+            CursedErrorType::NotSupported => "not supported", 
+            CursedErrorType::Interrupted => "interrupted",
+            CursedErrorType::NotEnough => "not enough",
+            CursedErrorType::Timedout => "timed out",
+            CursedErrorType::NotFound => "not found",
+            CursedErrorType::Overflow => "overflow",
+            CursedErrorType::Refused => "refused",
+            CursedErrorType::Invalid => "invalid",
+            CursedErrorType::Aborted => "aborted",
+            CursedErrorType::Reset => "reset",
+            CursedErrorType::Parse => "parse",
+            CursedErrorType::Custom(phrase) => phrase,
+        }
+    }
+    /// machine readable name of the type, e.g. `"not_found"`, custom types return
+    /// their phrase as is
+    pub fn to_id(&self) -> &'static str {
+        match self {
+            CursedErrorType::NotImplemented => "not_implemented",
+            CursedErrorType::AlreadyExists => "already_exists",
+            CursedErrorType::AccessDenied => "access_denied",
+            CursedErrorType::NotSupported => "not_supported",
+            CursedErrorType::Interrupted => "interrupted",
+            CursedErrorType::NotEnough => "not_enough",
+            CursedErrorType::Timedout => "timed_out",
+            CursedErrorType::NotFound => "not_found",
+            CursedErrorType::Overflow => "overflow",
+            CursedErrorType::Refused => "refused",
+            CursedErrorType::Invalid => "invalid",
+            CursedErrorType::Aborted => "aborted",
+            CursedErrorType::Reset => "reset",
+            CursedErrorType::Parse => "parse",
+            CursedErrorType::Custom(phrase) => phrase,
+        }
+    }
+    /// parses the name returned by [`CursedErrorType::to_id`], never returns custom types
+    pub fn from_id(id: &str) -> Option<Self> {
+        Some(match id {
+            "not_implemented" => CursedErrorType::NotImplemented,
+            "already_exists" => CursedErrorType::AlreadyExists,
+            "access_denied" => CursedErrorType::AccessDenied,
+            "not_supported" => CursedErrorType::NotSupported,
+            "interrupted" => CursedErrorType::Interrupted,
+            "not_enough" => CursedErrorType::NotEnough,
+            "timed_out" => CursedErrorType::Timedout,
+            "not_found" => CursedErrorType::NotFound,
+            "overflow" => CursedErrorType::Overflow,
+            "refused" => CursedErrorType::Refused,
+            "invalid" => CursedErrorType::Invalid,
+            "aborted" => CursedErrorType::Aborted,
+            "reset" => CursedErrorType::Reset,
+            "parse" => CursedErrorType::Parse,
+            _ => return None,
+        })
+    }
+}
+
+/// equal when both the category and the type match
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedError::File(CursedErrorType::NotFound);
+///
+/// assert_eq!(error, (CursedCategory::File, CursedErrorType::NotFound));
+/// assert_ne!(error, (CursedCategory::Path, CursedErrorType::NotFound));
+/// assert_ne!(error, (CursedCategory::File, CursedErrorType::Invalid));
+/// assert_ne!(CursedError::Unknown(None), (CursedCategory::Unknown, CursedErrorType::Invalid));
+/// ```
+impl PartialEq<(CursedCategory, CursedErrorType)> for CursedError {
+    fn eq(&self, (category, error_type): &(CursedCategory, CursedErrorType)) -> bool {
+        self.category() == *category && self.get_type() == Some(*error_type)
+    }
+}
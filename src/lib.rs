@@ -1,163 +1,124 @@
-use std::io::ErrorKind;
+#![cfg_attr(feature = "nightly", feature(error_generic_member_access))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
-/// struct created for error handling
-/// 
-/// # Examples
-/// ```
-/// use curerr::*;
-/// 
-/// fn devide(a: i32, b: i32) -> Result<i32, CursedErrorHandle> {
-///     if b == 0 {
-///         return Err(CursedErrorHandle::new(
-///             CursedError::Argument(CursedErrorType::Invalid),
-///             "0 division!!!".to_string()
-///         ))
-///     }
-/// 
-///     Ok(a/b)
-/// }
-/// 
-/// let result = devide(6, 3).expect("division error");
-/// 
-/// assert_eq!(result, 2)
-/// ```
-pub struct CursedErrorHandle {
-    error: CursedError,
-    reason: String,
+/// compiles the items only with the `unstable` feature and marks them in the docs
+///
+/// Items behind it may change in minor releases. `tests/stable_api.rs` leaves them out
+/// of the snapshot of the stable api.
+macro_rules! unstable {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "unstable")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+            $item
+        )*
+    };
 }
 
-impl CursedErrorHandle {
-    pub fn new(error: CursedError, reason: String) -> Self {
-        Self { error, reason }
-    }
-    pub fn get_error(&self) -> &CursedError {
-        &self.error
-    }
-    pub fn get_reason(&self) -> &String {
-        &self.reason
-    }
+mod breaker;
+mod civil;
+pub mod compat;
+mod convert;
+mod database;
+mod dedup;
+mod deferred;
+mod ext;
+mod factory;
+pub mod faults;
+mod ffi;
+mod format;
+mod handle;
+mod history;
+mod intern;
+mod kind;
+mod macros;
+mod multi;
+mod net;
+mod normalize;
+mod policy;
+unstable! {
+    pub mod registry;
 }
+#[cfg(not(feature = "unstable"))]
+mod registry;
+mod repr;
+mod report;
+mod retry;
+mod scope;
+mod setting;
+mod taxonomy;
+mod trace;
+mod truncate;
+mod typed;
+mod view;
 
-impl std::fmt::Display for CursedErrorHandle {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} error: \"{}\"", self.error.to_string(), self.reason)
-    }
-}
-impl std::fmt::Debug for CursedErrorHandle {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple(&self.error.to_string())
-            .field(&self.reason)
-            .finish()
-        }
-}
-impl std::error::Error for CursedErrorHandle {}
+pub use breaker::CircuitBreaker;
+#[cfg(feature = "axum")]
+pub use convert::expose_internal_reasons;
+pub use convert::{
+    classify_by_text, error_kind_mappings, resolve, reverse_error_kind_mappings, try_wrap, try_wrap_with, wrap_any,
+    wrap_io, wrap_io_op,
+};
+pub use dedup::set_dedup_window;
+pub use deferred::{DeferGuard, DeferredErrors};
+pub use ext::{CursedIterExt, CursedResultBridge, IntoCursed, ResultExt};
+pub use factory::ErrorFactory;
+pub use ffi::{
+    curerr_clear_last_error, curerr_last_error_code, curerr_last_message, last_error_code, set_last_error,
+    take_last_error, with_last_error,
+};
+pub use format::{set_default_format_version, FormatVersion};
+#[cfg(feature = "metrics")]
+pub use handle::enable_metrics;
+pub use handle::{
+    add_error_hook, capture_backtraces, clear_docs_base_url, clear_error_hook, keep_original_kind, reveal_sensitive,
+    set_docs_base_url, set_error_hook, CursedErrorHandle, CursedResult, PeerAddr, Sensitive, StaticError,
+};
+pub use history::ErrorHistory;
+pub use intern::{intern_stats, set_intern_capacity, InternStats};
+pub use kind::{CursedCategory, CursedError, CursedErrorType, CursedSeverity};
+pub use macros::AsCursedError;
+pub use multi::MultiError;
+pub use normalize::NormalizedError;
+pub use policy::{clear_policy, set_policy, with_policy, ConversionPolicy};
+pub use report::{
+    run_main, CursedReport, ErrorReporter, ErrorStats, MainError, MainExit, MainResult, MultiReport, SamplingReporter,
+};
+pub use retry::{retry, with_deadline, RetryPolicy};
+#[cfg(feature = "tokio")]
+pub use retry::{retry_async, retry_async_until};
+pub use scope::{scope, ScopeGuard};
+pub use taxonomy::{taxonomy, Taxonomy, TaxonomyDiff, TaxonomyEntry};
+pub use trace::{record_trace, with_trace};
+pub use truncate::{set_max_reason_len, with_max_reason_len};
+pub use typed::{NotFoundError, PermissionError, TimeoutError};
+pub use view::CursedErrorRef;
 
-/// enum with kinds of errors
+/// the types and traits most code needs, `use curerr::prelude::*;`
+///
 /// # Examples
 /// ```
-/// use curerr::*;
-/// 
-/// let error = CursedErrorHandle::new(
-///     CursedError::Path(CursedErrorType::Invalid),
-///     "path is invalid".to_string()
-/// );
-/// 
-/// assert_eq!(format!("{}", error), "path invalid error: \"path is invalid\"".to_string());
+/// use curerr::prelude::*;
+///
+/// fn port(value: &str) -> CursedResult<u16> {
+///     let port: u16 = value.parse().context("while reading the port")?;
+///     if port == 0 {
+///         return Err(CursedErrorHandle::new(
+///             CursedError::Input(CursedErrorType::Invalid),
+///             "port 0 is reserved".to_string()
+///         ))
+///     }
+///     Ok(port)
+/// }
+///
+/// assert_eq!(port("80").unwrap(), 80);
+/// assert!(cursed_match!(port("http").unwrap_err(), Data(Parse)));
+/// assert!(cursed_match!(port("0").unwrap_err(), Input(Invalid)));
 /// ```
-pub enum CursedError {
-    Connection(CursedErrorType),
-    Address(CursedErrorType),
-    Memory(CursedErrorType),
-    Buffer(CursedErrorType),
-    Envvar(CursedErrorType),
-    Other(CursedErrorType),
-    Input(CursedErrorType),
-    File(CursedErrorType),
-    Path(CursedErrorType),
-    Data(CursedErrorType),
-    Call(CursedErrorType),
-    NoError,
-    Unknown
-}
-
-impl ToString for CursedError {
-    fn to_string(&self) -> String {
-        match self {
-            CursedError::Connection(err) => format!("connection {}", err.to_str()),
-            CursedError::Address(err) => format!("address {}", err.to_str()),
-            CursedError::Buffer(err) => format!("buffer {}", err.to_str()),
-            CursedError::Envvar(err) => format!("envvar {}", err.to_str()),
-            CursedError::Memory(err) => format!("memory {}", err.to_str()),
-            CursedError::Input(err) => format!("input {}", err.to_str()),
-            CursedError::File(err) => format!("file {}", err.to_str()),
-            CursedError::Path(err) => format!("path {}", err.to_str()),
-            CursedError::Call(err) => format!("call {}", err.to_str()), 
-            CursedError::Data(err) => format!("data {}", err.to_str()),
-            CursedError::Other(err) => err.to_str().to_string(),
-            CursedError::NoError => "no error".to_string(),
-            CursedError::Unknown => "unknown".to_string(),
-        }        
-    }
+pub mod prelude {
+    pub use crate::{
+        cursed_ensure_eq, cursed_ensure_ne, cursed_match, cursed_ns, wrap_any, CursedCategory, CursedError,
+        CursedErrorHandle, CursedErrorType, CursedIterExt, CursedResult, CursedResultBridge, IntoCursed, MultiError,
+        ResultExt,
+    };
 }
-
-/// enum with error types
-pub enum CursedErrorType {
-    NotImplemented,
-    AlreadyExists,
-    AccessDenied,
-    NotSupported,
-    Interrupted,
-    NotEnough,
-    Timedout,
-    Overflow,
-    NotFound,
-    Refused,
-    Invalid,
-    Aborted,
-    Reset,
-    Parse,
-}
-
-impl CursedErrorType {
-    pub fn to_str(&self) -> &'static str {
-        match self {
-            CursedErrorType::NotImplemented => "not implemented",
-            CursedErrorType::AlreadyExists => "already exists",
-            CursedErrorType::AccessDenied => "access denied",
-            CursedErrorType::NotSupported => "not supported", 
-            CursedErrorType::Interrupted => "interrupted",
-            CursedErrorType::NotEnough => "not enough",
-            CursedErrorType::Timedout => "timed out",
-            CursedErrorType::NotFound => "not found",
-            CursedErrorType::Overflow => "overflow",
-            CursedErrorType::Refused => "refused",
-            CursedErrorType::Invalid => "invalid",
-            CursedErrorType::Aborted => "aborted",
-            CursedErrorType::Reset => "reset",
-            CursedErrorType::Parse => "parse",
-        }
-    }
-}
-
-impl From<ErrorKind> for CursedError {
-    fn from(error: ErrorKind) -> Self {
-        match error {
-            ErrorKind::NotFound => Self::Other(CursedErrorType::NotFound),
-            ErrorKind::PermissionDenied => Self::Other(CursedErrorType::AccessDenied),
-            ErrorKind::ConnectionRefused => Self::Connection(CursedErrorType::Refused),
-            ErrorKind::ConnectionReset => Self::Connection(CursedErrorType::Reset),
-            ErrorKind::ConnectionAborted => Self::Connection(CursedErrorType::Aborted),
-            ErrorKind::NotConnected => Self::Connection(CursedErrorType::NotImplemented),
-            ErrorKind::AddrInUse => Self::Address(CursedErrorType::AlreadyExists),
-            ErrorKind::AddrNotAvailable => Self::Address(CursedErrorType::NotSupported),
-            ErrorKind::AlreadyExists => Self::Other(CursedErrorType::AlreadyExists),
-            ErrorKind::InvalidInput => Self::Input(CursedErrorType::Invalid),
-            ErrorKind::InvalidData => Self::Data(CursedErrorType::Invalid),
-            ErrorKind::TimedOut => Self::Call(CursedErrorType::Timedout),
-            ErrorKind::Interrupted => Self::Other(CursedErrorType::Interrupted),
-            ErrorKind::Unsupported => Self::Other(CursedErrorType::NotSupported),
-            ErrorKind::OutOfMemory => Self::Memory(CursedErrorType::NotEnough),
-            _ => Self::Unknown,
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,270 @@
+//! macros matching error kinds
+
+use crate::*;
+
+/// types that have a [`CursedError`] kind, used by [`cursed_match!`]
+pub trait AsCursedError {
+    fn as_cursed_error(&self) -> &CursedError;
+}
+
+impl AsCursedError for CursedError {
+    fn as_cursed_error(&self) -> &CursedError {
+        self
+    }
+}
+
+impl AsCursedError for CursedErrorHandle {
+    fn as_cursed_error(&self) -> &CursedError {
+        self.get_error()
+    }
+}
+
+impl<T: AsCursedError + ?Sized> AsCursedError for &T {
+    fn as_cursed_error(&self) -> &CursedError {
+        (**self).as_cursed_error()
+    }
+}
+
+/// checks whether the kind of a [`CursedError`] or [`CursedErrorHandle`] matches,
+/// without spelling out the full paths
+///
+/// Categories are separated by `|`, types inside the parentheses as well, `_` matches
+/// any type. [`CursedError::NoError`] and [`CursedError::Unknown`] are written without
+/// parentheses.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedErrorHandle::new(
+///     CursedError::Connection(CursedErrorType::Reset),
+///     "peer went away".to_string()
+/// );
+///
+/// assert!(cursed_match!(error, Connection(Refused | Reset)));
+/// assert!(cursed_match!(error, Address(_) | Connection(_)));
+/// assert!(cursed_match!(&error, Connection(Reset) | Unknown));
+/// assert!(!cursed_match!(error, Connection(Refused) | File(NotFound | AccessDenied)));
+/// assert!(cursed_match!(CursedError::NoError, NoError));
+///
+/// let errors = [
+///     CursedError::File(CursedErrorType::NotFound),
+///     CursedError::Path(CursedErrorType::Invalid),
+///     CursedError::Call(CursedErrorType::Timedout),
+/// ];
+/// let missing = errors.iter().filter(|err| cursed_match!(err, File(NotFound) | Path(_))).count();
+///
+/// assert_eq!(missing, 2);
+/// ```
+#[macro_export]
+macro_rules! cursed_match {
+    ($error:expr, $($category:ident $(( $($error_type:tt)|+ ))?)|+ $(,)?) => {
+        matches!(
+            $crate::AsCursedError::as_cursed_error(&$error),
+            $( $crate::__cursed_pattern!($category $(( $($error_type)|+ ))?) )|+
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cursed_pattern {
+    (Unknown) => { $crate::CursedError::Unknown(_) };
+    ($category:ident) => { $crate::CursedError::$category };
+    ($category:ident ( $($error_type:tt)|+ )) => {
+        $crate::CursedError::$category( $($crate::__cursed_type!($error_type))|+ )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cursed_type {
+    (_) => { _ };
+    ($error_type:ident) => { $crate::CursedErrorType::$error_type };
+}
+
+/// returns an error from the function unless both values are equal, the reason shows
+/// both expressions and their `Debug` output
+///
+/// The kind is written as `Category(Type)` and can be followed by format arguments that
+/// are prepended to the reason. Each operand is evaluated once.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// fn check_len(items: &[u8], expected: usize) -> CursedResult<()> {
+///     cursed_ensure_eq!(items.len(), expected, Input(Invalid), "while checking {} items", expected);
+///     Ok(())
+/// }
+///
+/// assert!(check_len(&[1, 2, 3], 3).is_ok());
+///
+/// let error = check_len(&[1, 2, 3], 5).unwrap_err();
+///
+/// assert_eq!(error.get_error(), &CursedError::Input(CursedErrorType::Invalid));
+/// assert_eq!(error.get_reason(), "while checking 5 items: expected items.len() == expected: `3` vs `5`");
+///
+/// fn check_next(counter: &mut u32) -> CursedResult<()> {
+///     cursed_ensure_eq!({ *counter += 1; *counter }, 2, Data(Invalid));
+///     Ok(())
+/// }
+///
+/// let mut counter = 0;
+/// let error = check_next(&mut counter).unwrap_err();
+///
+/// assert_eq!(counter, 1);
+/// assert_eq!(error.get_reason(), "expected { *counter += 1; *counter } == 2: `1` vs `2`");
+/// assert!(check_next(&mut counter).is_ok());
+/// assert_eq!(counter, 2);
+/// ```
+#[macro_export]
+macro_rules! cursed_ensure_eq {
+    ($left:expr, $right:expr, $category:ident($error_type:ident) $(, $($context:tt)+)?) => {
+        $crate::__cursed_ensure_cmp!(==, $left, $right, $category($error_type) $(, $($context)+)?)
+    };
+}
+
+/// returns an error from the function if both values are equal, the counterpart of
+/// [`cursed_ensure_eq!`]
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct UserId(u32);
+///
+/// fn transfer(from: UserId, to: UserId) -> CursedResult<()> {
+///     cursed_ensure_ne!(from, to, Input(Invalid), "can't transfer to the same account");
+///     Ok(())
+/// }
+///
+/// assert!(transfer(UserId(1), UserId(2)).is_ok());
+/// assert_eq!(
+///     transfer(UserId(7), UserId(7)).unwrap_err().get_reason(),
+///     "can't transfer to the same account: expected from != to: `UserId(7)` vs `UserId(7)`"
+/// );
+/// ```
+#[macro_export]
+macro_rules! cursed_ensure_ne {
+    ($left:expr, $right:expr, $category:ident($error_type:ident) $(, $($context:tt)+)?) => {
+        $crate::__cursed_ensure_cmp!(!=, $left, $right, $category($error_type) $(, $($context)+)?)
+    };
+}
+
+/// defines `fn cursed(error, reason)` in the calling module, a constructor of handles
+/// tagged with the [namespace](CursedErrorHandle::with_namespace)
+///
+/// # Examples
+/// ```
+/// mod cache {
+///     use curerr::*;
+///
+///     cursed_ns!("cache");
+///
+///     pub fn get(key: &str) -> CursedResult<String> {
+///         Err(cursed(CursedError::Connection(CursedErrorType::Refused), format!("can't get {}", key)))
+///     }
+/// }
+///
+/// let error = cache::get("user:7").unwrap_err();
+///
+/// assert_eq!(error.namespace(), Some("cache"));
+/// assert_eq!(error.report().to_string(), "[cache] connection refused error: \"can't get user:7\"");
+/// assert_eq!(error.location().unwrap().file(), file!());
+/// ```
+#[macro_export]
+macro_rules! cursed_ns {
+    ($namespace:expr) => {
+        /// creates a handle tagged with the namespace of this module
+        #[allow(dead_code)]
+        #[track_caller]
+        fn cursed(
+            error: $crate::CursedError,
+            reason: impl ::std::convert::Into<::std::string::String>
+        ) -> $crate::CursedErrorHandle {
+            $crate::CursedErrorHandle::new(error, reason.into()).with_namespace($namespace)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cursed_ensure_cmp {
+    ($op:tt, $left:expr, $right:expr, $category:ident($error_type:ident) $(, $($context:tt)+)?) => {
+        match (&$left, &$right) {
+            (left, right) => if !(*left $op *right) {
+                #[allow(unused_mut)]
+                let mut reason = ::std::format!(
+                    "expected {} {} {}: `{:?}` vs `{:?}`",
+                    ::std::stringify!($left),
+                    ::std::stringify!($op),
+                    ::std::stringify!($right),
+                    left,
+                    right
+                );
+                $( reason = ::std::format!("{}: {}", ::std::format!($($context)+), reason); )?
+
+                return ::std::result::Result::Err(::std::convert::From::from($crate::CursedErrorHandle::new(
+                    $crate::CursedError::$category($crate::CursedErrorType::$error_type),
+                    reason
+                )))
+            }
+        }
+    };
+}
+
+/// creates a [`DeferGuard`](crate::DeferGuard) that runs the cleanup statements when it
+/// is dropped and records their error in a [`DeferredErrors`](crate::DeferredErrors)
+/// instead of returning or panicking
+///
+/// The statements can use `?` like the body of a function returning [`CursedResult`](crate::CursedResult).
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// fn flush(ok: bool) -> CursedResult<()> {
+///     match ok {
+///         true => Ok(()),
+///         false => Err(CursedErrorHandle::new(CursedError::Io(CursedErrorType::Interrupted), "flush failed".to_string())),
+///     }
+/// }
+///
+/// let errors = DeferredErrors::new();
+/// {
+///     let _flush = defer_errors!(errors, flush(false).context("while closing the log")?);
+///     let _ok = defer_errors!(errors, flush(true)?);
+/// }
+///
+/// // the guard also records when dropped by a panic
+/// let result = std::panic::catch_unwind(|| {
+///     let _flush = defer_errors!(errors, flush(false)?);
+///     panic!("request handler failed");
+/// });
+/// assert!(result.is_err());
+///
+/// let drained = errors.drain();
+/// assert_eq!(drained.len(), 2);
+/// assert_eq!(drained.errors()[0].contexts(), ["while closing the log"]);
+/// assert_eq!(drained.errors()[1].get_reason(), "flush failed");
+///
+/// // dismissed guards don't run
+/// defer_errors!(errors, flush(false)?).dismiss();
+/// assert!(errors.drain().is_empty());
+/// ```
+#[macro_export]
+macro_rules! defer_errors {
+    ($errors:expr, $($cleanup:tt)+) => {
+        {
+            // a cleanup that always returns early or panics makes the `Ok` unreachable
+            #[allow(unreachable_code)]
+            let cleanup = || -> $crate::CursedResult<()> {
+                $($cleanup)+;
+                ::std::result::Result::Ok(())
+            };
+            $crate::DeferredErrors::defer(&$errors, cleanup)
+        }
+    };
+}
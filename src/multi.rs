@@ -0,0 +1,385 @@
+//! collections of errors
+
+use crate::*;
+use crate::report::MultiReport;
+
+/// collection of errors, e.g. every problem found while validating a form
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let mut errors = MultiError::new();
+/// for (field, value) in [("port", "http"), ("name", ""), ("timeout", "-1")] {
+///     if value.is_empty() {
+///         errors.push(CursedErrorHandle::new(
+///             CursedError::Input(CursedErrorType::NotEnough),
+///             format!("{} is empty", field)
+///         ));
+///     } else if value.parse::<u16>().is_err() {
+///         errors.push(CursedErrorHandle::new(
+///             CursedError::Input(CursedErrorType::Invalid),
+///             format!("{} is not a number", field)
+///         ));
+///     }
+/// }
+///
+/// assert_eq!(errors.len(), 3);
+/// assert_eq!(
+///     errors.to_string(),
+///     "3 errors:\n  \
+///      input invalid error: \"port is not a number\"\n  \
+///      input not enough error: \"name is empty\"\n  \
+///      input invalid error: \"timeout is not a number\""
+/// );
+///
+/// let groups = errors.group_by_kind();
+///
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].0, CursedError::Input(CursedErrorType::Invalid));
+/// assert_eq!(groups[0].1.len(), 2);
+/// assert_eq!(groups[1].1[0].get_reason(), "name is empty");
+///
+/// errors.dedup_by_kind();
+/// assert_eq!(errors.len(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiError {
+    errors: Vec<CursedErrorHandle>,
+    max_groups: usize,
+}
+
+/// number of distinct errors displayed unless changed by [`MultiError::with_max_groups`]
+const DEFAULT_MAX_GROUPS: usize = 20;
+
+impl Default for MultiError {
+    fn default() -> Self {
+        Self { errors: Vec::new(), max_groups: DEFAULT_MAX_GROUPS }
+    }
+}
+
+impl MultiError {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// limits how many distinct errors the [`Display`](std::fmt::Display) and
+    /// [`MultiError::report`] output show, 20 by default
+    ///
+    /// The rest is summarized as `… and N more`, where `N` counts the hidden distinct errors.
+    pub fn with_max_groups(mut self, max_groups: usize) -> Self {
+        self.max_groups = max_groups;
+        self
+    }
+    pub fn max_groups(&self) -> usize {
+        self.max_groups
+    }
+    pub fn push(&mut self, error: impl Into<CursedErrorHandle>) {
+        self.errors.push(error.into());
+    }
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+    pub fn errors(&self) -> &[CursedErrorHandle] {
+        &self.errors
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, CursedErrorHandle> {
+        self.errors.iter()
+    }
+    pub fn into_vec(self) -> Vec<CursedErrorHandle> {
+        self.errors
+    }
+    /// keeps only the first error of each kind, compared by [`CursedErrorHandle::same_kind`]
+    pub fn dedup_by_kind(&mut self) {
+        let mut kept: Vec<CursedErrorHandle> = Vec::new();
+        for error in std::mem::take(&mut self.errors) {
+            if !kept.iter().any(|kept| kept.same_kind(&error)) {
+                kept.push(error);
+            }
+        }
+        self.errors = kept;
+    }
+    /// errors grouped by [`CursedErrorHandle::same_kind`], in the order each kind first appears
+    pub fn group_by_kind(&self) -> Vec<(CursedError, Vec<&CursedErrorHandle>)> {
+        let mut groups: Vec<(CursedError, Vec<&CursedErrorHandle>)> = Vec::new();
+
+        for error in &self.errors {
+            match groups.iter_mut().find(|(_, group)| group[0].same_kind(error)) {
+                Some((_, group)) => group.push(error),
+                None => groups.push((error.kind(), vec![error])),
+            }
+        }
+        groups
+    }
+    /// errors with the same kind and [normalized reason](CursedErrorHandle::normalized_reason)
+    /// grouped together, in the order they are displayed
+    ///
+    /// The most severe groups come first, groups of the same severity are ordered by
+    /// their size and then by where their first error appears. [`MultiError::errors`]
+    /// still has every error.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let mut errors = MultiError::new();
+    /// for row in 0..40 {
+    ///     let (error, reason) = match row % 10 {
+    ///         0 => (CursedError::Connection(CursedErrorType::Reset), "upstream reset the connection".to_string()),
+    ///         1 | 2 | 3 => (CursedError::Data(CursedErrorType::Parse), "column 3 is not a date".to_string()),
+    ///         _ => (CursedError::Input(CursedErrorType::Invalid), format!("row {} has an empty name", row)),
+    ///     };
+    ///     errors.push(CursedErrorHandle::new(error, reason));
+    /// }
+    /// errors.push(CursedErrorHandle::new(CursedError::Input(CursedErrorType::Invalid), "name is too long".to_string()));
+    ///
+    /// assert_eq!(
+    ///     errors.to_string(),
+    ///     "41 errors:\n  \
+    ///      input invalid error: \"row 4 has an empty name\" (×24)\n  \
+    ///      data parse error: \"column 3 is not a date\" (×12)\n  \
+    ///      input invalid error: \"name is too long\"\n  \
+    ///      connection reset error: \"upstream reset the connection\" (×4)"
+    /// );
+    /// assert_eq!(
+    ///     errors.clone().with_max_groups(2).to_string(),
+    ///     "41 errors:\n  \
+    ///      input invalid error: \"row 4 has an empty name\" (×24)\n  \
+    ///      data parse error: \"column 3 is not a date\" (×12)\n  \
+    ///      … and 2 more"
+    /// );
+    ///
+    /// let groups = errors.group_duplicates();
+    ///
+    /// assert_eq!(groups.iter().map(Vec::len).collect::<Vec<_>>(), [24, 12, 1, 4]);
+    /// assert_eq!(groups[0][1].get_reason(), "row 5 has an empty name");
+    /// assert_eq!(errors.len(), 41);
+    /// ```
+    pub fn group_duplicates(&self) -> Vec<Vec<&CursedErrorHandle>> {
+        let mut indices = std::collections::HashMap::new();
+        let mut groups: Vec<Vec<&CursedErrorHandle>> = Vec::new();
+
+        for error in &self.errors {
+            let index = *indices.entry((error.kind(), error.normalized_reason())).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[index].push(error);
+        }
+
+        groups.sort_by(|a, b| {
+            b[0].get_error().severity().cmp(&a[0].get_error().severity()).then(b.len().cmp(&a.len()))
+        });
+        groups
+    }
+    /// multi-line description with the [report](CursedErrorHandle::report) of each distinct
+    /// error, grouped like the [`Display`](std::fmt::Display) output
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let errors: MultiError = [
+    ///     CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "lookup timed out".to_string())
+    ///         .with_op("resolve"),
+    ///     CursedErrorHandle::new(CursedError::Input(CursedErrorType::Invalid), "port 0 is reserved".to_string())
+    ///         .with_field("port", 0),
+    ///     CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "lookup timed out".to_string())
+    ///         .with_op("resolve"),
+    /// ].into_iter().collect();
+    ///
+    /// assert_eq!(
+    ///     errors.report().to_string(),
+    ///     "3 errors:\n  \
+    ///      input invalid error: \"port 0 is reserved\"\n    \
+    ///        port = 0\n  \
+    ///      call timed out error: \"lookup timed out\"\n    \
+    ///        op: resolve\n    \
+    ///        (×2)"
+    /// );
+    /// ```
+    pub fn report(&self) -> MultiReport<'_> {
+        MultiReport { errors: self }
+    }
+    /// errors grouped by category, see [`MultiError::counts`] for just the sizes
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let errors: MultiError = (0..50).map(|row| {
+    ///     let error = match row % 5 {
+    ///         0 | 1 | 2 => CursedError::Input(CursedErrorType::Invalid),
+    ///         3 => CursedError::Data(CursedErrorType::Parse),
+    ///         _ => CursedError::Connection(CursedErrorType::Reset),
+    ///     };
+    ///     CursedErrorHandle::new(error, format!("row {}", row))
+    /// }).collect();
+    ///
+    /// let groups = errors.group_by_category();
+    ///
+    /// assert_eq!(groups.len(), 3);
+    /// assert_eq!(groups[&CursedCategory::Input].len(), 30);
+    /// assert_eq!(groups[&CursedCategory::Data][0].get_reason(), "row 3");
+    ///
+    /// assert_eq!(errors.counts(), [
+    ///     (CursedCategory::Input, 30),
+    ///     (CursedCategory::Connection, 10),
+    ///     (CursedCategory::Data, 10),
+    /// ]);
+    ///
+    /// let (input, other) = errors.partition(|error| error.get_error().category() == CursedCategory::Input);
+    ///
+    /// assert_eq!(input.len(), 30);
+    /// assert_eq!(other.len(), 20);
+    /// assert_eq!(input.errors()[1].get_reason(), "row 1");
+    /// assert!(other.iter().all(|error| error.get_error().category() != CursedCategory::Input));
+    /// ```
+    pub fn group_by_category(&self) -> std::collections::HashMap<CursedCategory, Vec<&CursedErrorHandle>> {
+        let mut groups: std::collections::HashMap<_, Vec<_>> = std::collections::HashMap::new();
+        for error in &self.errors {
+            groups.entry(error.get_error().category()).or_default().push(error);
+        }
+        groups
+    }
+    /// number of errors of each category, the largest first and ties ordered by category
+    pub fn counts(&self) -> Vec<(CursedCategory, usize)> {
+        let mut counts: Vec<_> = self.group_by_category().into_iter()
+            .map(|(category, errors)| (category, errors.len()))
+            .collect();
+
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        counts
+    }
+    /// splits the errors into the ones matching `predicate` and the rest, keeping their order
+    pub fn partition(self, predicate: impl FnMut(&CursedErrorHandle) -> bool) -> (MultiError, MultiError) {
+        let (matching, rest) = self.errors.into_iter().partition(predicate);
+        (
+            Self { errors: matching, max_groups: self.max_groups },
+            Self { errors: rest, max_groups: self.max_groups },
+        )
+    }
+    /// removes the errors not matching `predicate`
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let mut errors = MultiError::new();
+    /// errors.push(CursedErrorHandle::new(CursedError::Input(CursedErrorType::Invalid), "age is negative".to_string()));
+    /// errors.push(CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "lookup timed out".to_string()));
+    ///
+    /// errors.retain(|error| cursed_match!(error, Input(_)));
+    ///
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors.errors()[0].get_reason(), "age is negative");
+    /// ```
+    pub fn retain(&mut self, predicate: impl FnMut(&CursedErrorHandle) -> bool) {
+        self.errors.retain(predicate);
+    }
+}
+
+/// the number of errors and each distinct error once, see [`MultiError::group_duplicates`]
+impl std::fmt::Display for MultiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}:", self.errors.len(), if self.errors.len() == 1 { "error" } else { "errors" })?;
+
+        let groups = self.group_duplicates();
+        for group in groups.iter().take(self.max_groups) {
+            write!(f, "\n  {}", group[0])?;
+            if group.len() > 1 {
+                write!(f, " (×{})", group.len())?;
+            }
+        }
+        if groups.len() > self.max_groups {
+            write!(f, "\n  … and {} more", groups.len() - self.max_groups)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MultiError {}
+
+impl From<Vec<CursedErrorHandle>> for MultiError {
+    fn from(errors: Vec<CursedErrorHandle>) -> Self {
+        Self { errors, max_groups: DEFAULT_MAX_GROUPS }
+    }
+}
+
+/// a single error as it is, otherwise a handle of the kind of the first
+/// [group](MultiError::group_duplicates) with the errors as the source
+///
+/// The reason is their number like `2 errors`, as is the `errors` field, the errors
+/// themselves are shown by the [`Display`](std::fmt::Display) output of the source.
+///
+/// An empty `MultiError` has no kind to take, it becomes `Unknown(None)` with the reason
+/// `0 errors`, so that it still is an error.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let errors: MultiError = [
+///     CursedErrorHandle::new(CursedError::Input(CursedErrorType::Invalid), "port is not a number".to_string()),
+///     CursedErrorHandle::new(CursedError::Memory(CursedErrorType::NotEnough), "cache is full".to_string()),
+/// ].into_iter().collect();
+/// let error = CursedErrorHandle::from(errors);
+///
+/// assert_eq!(error.get_error(), &CursedError::Memory(CursedErrorType::NotEnough));
+/// assert_eq!(error.get_reason(), "2 errors");
+/// assert_eq!(error.fields().collect::<Vec<_>>(), [("errors", "2".into())]);
+///
+/// let source = std::error::Error::source(&error).unwrap();
+/// assert_eq!(
+///     source.to_string(),
+///     "2 errors:\n  memory not enough error: \"cache is full\"\n  input invalid error: \"port is not a number\""
+/// );
+///
+/// let error = CursedErrorHandle::from(MultiError::new());
+/// assert_eq!(error.get_error(), &CursedError::Unknown(None));
+/// assert_eq!(error.get_reason(), "0 errors");
+/// ```
+impl From<MultiError> for CursedErrorHandle {
+    #[track_caller]
+    fn from(mut errors: MultiError) -> Self {
+        if errors.len() == 1 {
+            return errors.errors.pop().expect("there is one error")
+        }
+
+        let kind = errors.group_duplicates().first().map_or(CursedError::Unknown(None), |group| *group[0].get_error());
+        let count = errors.len();
+        Self::new(kind, format!("{} errors", count)).with_field("errors", count).with_source(errors)
+    }
+}
+
+impl<E: Into<CursedErrorHandle>> FromIterator<E> for MultiError {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        Self { errors: iter.into_iter().map(Into::into).collect(), max_groups: DEFAULT_MAX_GROUPS }
+    }
+}
+
+impl<E: Into<CursedErrorHandle>> Extend<E> for MultiError {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        self.errors.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+impl IntoIterator for MultiError {
+    type Item = CursedErrorHandle;
+    type IntoIter = std::vec::IntoIter<CursedErrorHandle>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a MultiError {
+    type Item = &'a CursedErrorHandle;
+    type IntoIter = std::slice::Iter<'a, CursedErrorHandle>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
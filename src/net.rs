@@ -0,0 +1,137 @@
+//! classification of failed socket operations, which read the io error differently
+//! depending on whether it came from connecting, binding or accepting
+
+use crate::*;
+use crate::handle::{Field, Inner};
+
+use std::io::ErrorKind;
+
+impl CursedErrorHandle {
+    /// creates error for a failed connect to `addr`, the address is kept as the
+    /// [peer](CursedErrorHandle::peer) and the `peer` field and the op is `connect`
+    ///
+    /// The kind follows the [`ErrorKind`] mapping, except that timeouts and io errors
+    /// become `Connection` errors of the same type. The io error is kept as the source,
+    /// so the reason doesn't repeat its message.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::net::{TcpListener, TcpStream};
+    ///
+    /// // a port that was just free is most likely still closed
+    /// let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    /// let error = CursedErrorHandle::connect_err(addr, TcpStream::connect(addr).unwrap_err());
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Refused));
+    /// assert_eq!(error.get_reason(), format!("failed to connect to {}", addr));
+    /// assert!(std::error::Error::source(&error).unwrap().is::<std::io::Error>());
+    /// assert_eq!(error.addr(), Some(addr));
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("peer", addr.to_string().into())]);
+    /// assert_eq!(error.op(), Some("connect"));
+    /// assert!(error.get_error().is_retryable());
+    ///
+    /// let error = CursedErrorHandle::connect_err("db.internal:5432", std::io::ErrorKind::TimedOut.into());
+    /// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Timedout));
+    /// assert_eq!(error.peer(), Some(&PeerAddr::Unresolved("db.internal:5432".to_string())));
+    /// ```
+    #[track_caller]
+    pub fn connect_err(addr: impl Into<PeerAddr>, error: std::io::Error) -> Self {
+        let kind = match CursedError::from(error.kind()) {
+            CursedError::Io(error_type) | CursedError::Call(error_type @ CursedErrorType::Timedout) => {
+                CursedError::Connection(error_type)
+            },
+            kind => kind,
+        };
+
+        let peer = addr.into();
+        let mut inner = socket_error(kind, format!("failed to connect to {}", peer), "connect", error);
+        inner.fields.push(("peer".into(), Field::Plain(peer.to_string())));
+        inner.peer = Some(peer);
+        Self::from_inner(inner)
+    }
+    /// creates error for a failed bind to the local `addr`, the address is kept as the
+    /// `local_addr` field and the op is `bind`
+    ///
+    /// An address in use is `Address(AlreadyExists)` and io errors, like the denied
+    /// permission for a privileged port, become `Address` errors of the same type,
+    /// other kinds follow the [`ErrorKind`] mapping. The io error is kept as the source,
+    /// so the reason doesn't repeat its message.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let addr = listener.local_addr().unwrap();
+    /// let error = CursedErrorHandle::bind_err(addr, TcpListener::bind(addr).unwrap_err());
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Address(CursedErrorType::AlreadyExists));
+    /// assert_eq!(error.get_reason(), format!("failed to bind {}", addr));
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("local_addr", addr.to_string().into())]);
+    /// assert_eq!(error.op(), Some("bind"));
+    /// assert_eq!(error.addr(), None);
+    ///
+    /// let error = CursedErrorHandle::bind_err("0.0.0.0:80", std::io::ErrorKind::PermissionDenied.into());
+    /// assert_eq!(error.get_error(), &CursedError::Address(CursedErrorType::AccessDenied));
+    /// ```
+    #[track_caller]
+    pub fn bind_err(addr: impl Into<PeerAddr>, error: std::io::Error) -> Self {
+        let kind = match (error.kind(), CursedError::from(error.kind())) {
+            (ErrorKind::AddrInUse, _) => CursedError::Address(CursedErrorType::AlreadyExists),
+            (_, CursedError::Io(error_type)) => CursedError::Address(error_type),
+            (_, kind) => kind,
+        };
+
+        let addr = addr.into().to_string();
+        let mut inner = socket_error(kind, format!("failed to bind {}", addr), "bind", error);
+        inner.fields.push(("local_addr".into(), Field::Plain(addr)));
+        Self::from_inner(inner)
+    }
+    /// creates error for a failed accept on a listener, the op is `accept`
+    ///
+    /// Interrupted calls and, on non-blocking listeners, the lack of a pending connection
+    /// are `Io(Interrupted)`, so they are [retryable](CursedError::is_retryable) and an
+    /// accept loop can go on. Other kinds follow the [`ErrorKind`] mapping. The io error
+    /// is kept as the source, so the reason doesn't repeat its message.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// listener.set_nonblocking(true).unwrap();
+    /// let error = CursedErrorHandle::accept_err(listener.accept().unwrap_err());
+    ///
+    /// assert_eq!(error.get_error(), &CursedError::Io(CursedErrorType::Interrupted));
+    /// assert_eq!(error.get_reason(), "failed to accept a connection");
+    /// assert_eq!(error.op(), Some("accept"));
+    /// assert!(error.get_error().is_retryable());
+    ///
+    /// let error = CursedErrorHandle::accept_err(std::io::ErrorKind::Interrupted.into());
+    /// assert!(error.get_error().is_retryable());
+    ///
+    /// let error = CursedErrorHandle::accept_err(std::io::ErrorKind::ConnectionAborted.into());
+    /// assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Aborted));
+    /// ```
+    #[track_caller]
+    pub fn accept_err(error: std::io::Error) -> Self {
+        let kind = match error.kind() {
+            ErrorKind::Interrupted | ErrorKind::WouldBlock => CursedError::Io(CursedErrorType::Interrupted),
+            kind => CursedError::from(kind),
+        };
+
+        Self::from_inner(socket_error(kind, "failed to accept a connection".to_string(), "accept", error))
+    }
+}
+
+#[track_caller]
+fn socket_error(kind: CursedError, reason: String, op: &'static str, error: std::io::Error) -> Inner {
+    let mut inner = Inner::new(kind, reason);
+    inner.op = Some(op);
+    inner.os_code = error.raw_os_error();
+    inner.source = Some(std::sync::Arc::new(error));
+    inner
+}
@@ -0,0 +1,78 @@
+//! the content of an error every string and binary form is checked against
+
+use crate::*;
+use crate::handle::Field;
+
+/// the parts of an error that its string and binary forms agree on, created by
+/// [`CursedErrorHandle::normalize`]
+///
+/// Decoding a form gives back the same normalized error, except for what the form
+/// leaves out on purpose:
+///
+/// | form | loses |
+/// |------|-------|
+/// | `serde` | nothing |
+/// | [`CursedErrorHandle::canonical_json`] | the order of the fields, they are sorted by key, and all but the last of fields with the same key |
+/// | [`CursedErrorHandle::encode`] | the fields |
+/// | [`Display`](std::fmt::Display) | the fields, and the kind of custom types in `Other` or in a custom category, which read back as `Unknown(None)` |
+/// | [`CursedError::code`] | the reason and fields, and the kind of custom categories, which read back as `Unknown(None)` |
+/// | [`CursedError::numeric_code`] | the reason and fields, and the kind of custom categories and types, which have no number |
+///
+/// The op, namespace, contexts, sources, os code and other attached data are not part of
+/// it. `tests/conformance.rs` checks every form against this table.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Parse), "bad \"port\"".to_string())
+///     .with_field("line", 3)
+///     .with_field_sensitive("token", "hunter2");
+///
+/// let mut bytes = Vec::new();
+/// error.encode(&mut bytes);
+/// let decoded = CursedErrorHandle::decode(&bytes).unwrap();
+///
+/// assert_eq!(decoded.normalize(), NormalizedError { fields: Vec::new(), ..error.normalize() });
+/// assert_eq!(error.normalize(), NormalizedError {
+///     category: CursedCategory::Data,
+///     error_type: Some(CursedErrorType::Parse),
+///     reason: "bad \"port\"".to_string(),
+///     fields: vec![
+///         ("line".to_string(), "3".to_string()),
+///         ("token".to_string(), "[REDACTED]".to_string()),
+///     ],
+///     code: "DATA_PARSE".to_string(),
+/// });
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NormalizedError {
+    pub category: CursedCategory,
+    /// `None` for [`CursedError::NoError`] and [`CursedError::Unknown`]
+    pub error_type: Option<CursedErrorType>,
+    pub reason: String,
+    /// in the order they were attached, sensitive values are always `[REDACTED]`
+    pub fields: Vec<(String, String)>,
+    /// the [`CursedError::code`]
+    pub code: String,
+}
+
+impl CursedErrorHandle {
+    /// the kind, reason, fields and code of the error, see [`NormalizedError`]
+    pub fn normalize(&self) -> NormalizedError {
+        let fields = self.inner().map(|inner| inner.fields.as_slice()).unwrap_or_default();
+
+        NormalizedError {
+            category: self.get_error().category(),
+            error_type: self.get_error().get_type(),
+            reason: self.get_reason().to_string(),
+            fields: fields.iter()
+                .map(|(key, value)| (key.to_string(), match value {
+                    Field::Plain(value) => value.clone(),
+                    Field::Sensitive(_) => "[REDACTED]".to_string(),
+                }))
+                .collect(),
+            code: self.code().into_owned(),
+        }
+    }
+}
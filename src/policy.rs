@@ -0,0 +1,187 @@
+//! overrides of the built in classifications, see [`set_policy`]
+
+use crate::*;
+
+use std::cell::RefCell;
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+static POLICY_SET: AtomicBool = AtomicBool::new(false);
+static POLICY: RwLock<Option<Arc<ConversionPolicy>>> = RwLock::new(None);
+
+/// number of [`with_policy`] calls running on all threads, so lookups skip the thread
+/// local while there are none
+static SCOPED_ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SCOPED: RefCell<Option<Arc<ConversionPolicy>>> = const { RefCell::new(None) };
+}
+
+/// mappings replacing the built in ones of single kinds, installed with [`set_policy`] or
+/// [`with_policy`]
+///
+/// Each table only has the entries that were set, every other kind keeps its built in
+/// mapping. An entry applies to the exact kind, an override of `Other(NotFound)` doesn't
+/// change custom categories with the same type. Setting an entry again replaces it.
+///
+/// The policy is read by [`CursedError::from`] an [`ErrorKind`] and so by every
+/// conversion of io errors, [`CursedError::http_status`], [`CursedError::exit_code`],
+/// [`CursedError::severity`] and [`CursedError::is_retryable`], along with the methods
+/// of handles and [`retry`] built on them. Codes and the [`taxonomy`] stay the built in
+/// ones.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::io::ErrorKind;
+///
+/// let policy = ConversionPolicy::new()
+///     .map_io_kind(ErrorKind::PermissionDenied, CursedError::Security(CursedErrorType::AccessDenied))
+///     .retryable(CursedError::Call(CursedErrorType::Timedout), false)
+///     .http_status(CursedError::Call(CursedErrorType::Timedout), 598);
+///
+/// with_policy(policy, || {
+///     assert_eq!(CursedError::from(ErrorKind::PermissionDenied), CursedError::Security(CursedErrorType::AccessDenied));
+///     assert!(!CursedError::Call(CursedErrorType::Timedout).is_retryable());
+///     assert_eq!(CursedError::Call(CursedErrorType::Timedout).http_status(), 598);
+///     assert_eq!(CursedError::Call(CursedErrorType::Timedout).exit_code(), 75);
+/// });
+///
+/// assert_eq!(CursedError::Call(CursedErrorType::Timedout).http_status(), 504);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConversionPolicy {
+    io_kinds: Vec<(ErrorKind, CursedError)>,
+    http_statuses: Vec<(CursedError, u16)>,
+    exit_codes: Vec<(CursedError, u8)>,
+    severities: Vec<(CursedError, CursedSeverity)>,
+    retryable: Vec<(CursedError, bool)>,
+}
+
+/// sets `value` for `key`, replacing an earlier entry
+fn set_entry<K: PartialEq, V>(table: &mut Vec<(K, V)>, key: K, value: V) {
+    match table.iter_mut().find(|(entry, _)| *entry == key) {
+        Some(entry) => entry.1 = value,
+        None => table.push((key, value)),
+    }
+}
+
+fn find_entry<K: PartialEq, V: Copy>(table: &[(K, V)], key: &K) -> Option<V> {
+    table.iter().find(|(entry, _)| entry == key).map(|(_, value)| *value)
+}
+
+impl ConversionPolicy {
+    /// a policy without overrides
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// converts io errors of `io_kind` into `error`
+    pub fn map_io_kind(mut self, io_kind: ErrorKind, error: CursedError) -> Self {
+        set_entry(&mut self.io_kinds, io_kind, error);
+        self
+    }
+    /// maps `error` to the HTTP status `status`
+    pub fn http_status(mut self, error: CursedError, status: u16) -> Self {
+        set_entry(&mut self.http_statuses, error, status);
+        self
+    }
+    /// maps `error` to the process exit code `exit_code`
+    pub fn exit_code(mut self, error: CursedError, exit_code: u8) -> Self {
+        set_entry(&mut self.exit_codes, error, exit_code);
+        self
+    }
+    /// gives `error` the severity `severity`
+    pub fn severity(mut self, error: CursedError, severity: CursedSeverity) -> Self {
+        set_entry(&mut self.severities, error, severity);
+        self
+    }
+    /// marks `error` as transient or not
+    pub fn retryable(mut self, error: CursedError, retryable: bool) -> Self {
+        set_entry(&mut self.retryable, error, retryable);
+        self
+    }
+}
+
+/// installs `policy` for every thread, replacing the previous one
+///
+/// Threads inside [`with_policy`] keep the policy of the call. Without a policy, each
+/// classification costs two relaxed atomic loads more, with one it takes a read lock.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// set_policy(ConversionPolicy::new().severity(CursedError::Io(CursedErrorType::Reset), CursedSeverity::Warning));
+/// assert_eq!(CursedError::Io(CursedErrorType::Reset).severity(), CursedSeverity::Warning);
+///
+/// clear_policy();
+/// assert_eq!(CursedError::Io(CursedErrorType::Reset).severity(), CursedSeverity::Error);
+/// ```
+pub fn set_policy(policy: ConversionPolicy) {
+    let mut slot = POLICY.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *slot = Some(Arc::new(policy));
+    POLICY_SET.store(true, Ordering::Release);
+}
+
+/// removes the policy installed by [`set_policy`]
+pub fn clear_policy() {
+    let mut slot = POLICY.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *slot = None;
+    POLICY_SET.store(false, Ordering::Release);
+}
+
+/// runs `f` with `policy` on this thread instead of the one of [`set_policy`], so tests
+/// running in parallel can each use their own
+///
+/// Kinds without an entry in `policy` keep their built in mappings, also when the
+/// policy of [`set_policy`] has one. Calls nest, the innermost policy applies, and the
+/// previous one is restored when `f` returns or panics.
+pub fn with_policy<R>(policy: ConversionPolicy, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Arc<ConversionPolicy>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SCOPED.with(|scoped| *scoped.borrow_mut() = previous);
+            SCOPED_ACTIVE.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    SCOPED_ACTIVE.fetch_add(1, Ordering::Relaxed);
+    let _restore = Restore(SCOPED.with(|scoped| scoped.borrow_mut().replace(Arc::new(policy))));
+    f()
+}
+
+/// the entry of the policy applying to this thread, `None` to use the built in mapping
+fn lookup<T>(get: impl Fn(&ConversionPolicy) -> Option<T>) -> Option<T> {
+    if SCOPED_ACTIVE.load(Ordering::Relaxed) > 0 {
+        if let Some(found) = SCOPED.with(|scoped| scoped.borrow().as_deref().map(&get)) {
+            return found
+        }
+    }
+    if !POLICY_SET.load(Ordering::Relaxed) {
+        return None
+    }
+    POLICY.read().unwrap_or_else(|poisoned| poisoned.into_inner()).as_deref().and_then(get)
+}
+
+pub(crate) fn io_kind(io_kind: ErrorKind) -> Option<CursedError> {
+    lookup(|policy| find_entry(&policy.io_kinds, &io_kind))
+}
+
+pub(crate) fn http_status(error: &CursedError) -> Option<u16> {
+    lookup(|policy| find_entry(&policy.http_statuses, error))
+}
+
+pub(crate) fn exit_code(error: &CursedError) -> Option<u8> {
+    lookup(|policy| find_entry(&policy.exit_codes, error))
+}
+
+pub(crate) fn severity(error: &CursedError) -> Option<CursedSeverity> {
+    lookup(|policy| find_entry(&policy.severities, error))
+}
+
+pub(crate) fn retryable(error: &CursedError) -> Option<bool> {
+    lookup(|policy| find_entry(&policy.retryable, error))
+}
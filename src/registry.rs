@@ -0,0 +1,336 @@
+//! the codes and mappings of every error kind as data, read by the methods of [`CursedError`]
+//!
+//! Every combination of a predefined category and type has its own row, so a kind added
+//! later needs a decision for each mapping instead of getting one from a catch-all arm.
+//! Tests of crates depending on the published codes can compare the tables between
+//! versions.
+//!
+//! The module needs the `unstable` feature while the columns may still change.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "unstable")] {
+//! use curerr::*;
+//! use curerr::registry::REGISTRY;
+//!
+//! let spec = REGISTRY.iter().find(|spec| spec.error == CursedError::Call(CursedErrorType::Timedout)).unwrap();
+//!
+//! assert_eq!(spec.code, "CALL_TIMED_OUT");
+//! assert_eq!(spec.numeric_code, 1107);
+//! assert_eq!(spec.severity, CursedSeverity::Warning);
+//! assert!(spec.retryable);
+//! assert_eq!(spec.http_status, 504);
+//! assert_eq!(spec.exit_code, 75);
+//!
+//! assert_eq!(REGISTRY.len(), CursedError::all_combinations().len());
+//! # }
+//! ```
+
+use crate::*;
+use crate::kind::{ALL_ERRORS, TYPED_CATEGORIES};
+
+/// the mappings of one error kind
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ErrorSpec {
+    pub error: CursedError,
+    /// [`CursedError::code`]
+    pub code: &'static str,
+    /// [`CursedError::numeric_code`]
+    pub numeric_code: u32,
+    /// [`CursedError::severity`]
+    pub severity: CursedSeverity,
+    /// [`CursedError::is_retryable`]
+    pub retryable: bool,
+    /// [`CursedError::http_status`]
+    pub http_status: u16,
+    /// [`CursedError::exit_code`]
+    pub exit_code: u8,
+}
+
+const fn spec(
+    error: CursedError,
+    code: &'static str,
+    numeric_code: u32,
+    severity: CursedSeverity,
+    retryable: bool,
+    http_status: u16,
+    exit_code: u8,
+) -> ErrorSpec {
+    ErrorSpec { error, code, numeric_code, severity, retryable, http_status, exit_code }
+}
+
+/// a row for every error of [`CursedError::all_combinations`], in the same order
+///
+/// Custom categories use the row of [`CursedError::Other`] with the same type except for
+/// the codes, which contain their name.
+pub const REGISTRY: &[ErrorSpec] = {
+    use CursedError::*;
+    use CursedErrorType::*;
+    use CursedSeverity::*;
+
+    &[
+        spec(Connection(NotImplemented), "CONNECTION_NOT_IMPLEMENTED", 101, Error, false, 501, 69),
+        spec(Connection(AlreadyExists), "CONNECTION_ALREADY_EXISTS", 102, Error, false, 409, 69),
+        spec(Connection(AccessDenied), "CONNECTION_ACCESS_DENIED", 103, Error, false, 403, 77),
+        spec(Connection(NotSupported), "CONNECTION_NOT_SUPPORTED", 104, Error, false, 501, 69),
+        spec(Connection(Interrupted), "CONNECTION_INTERRUPTED", 105, Warning, true, 503, 75),
+        spec(Connection(NotEnough), "CONNECTION_NOT_ENOUGH", 106, Error, false, 503, 69),
+        spec(Connection(Timedout), "CONNECTION_TIMED_OUT", 107, Warning, true, 504, 75),
+        spec(Connection(Overflow), "CONNECTION_OVERFLOW", 108, Error, false, 500, 69),
+        spec(Connection(NotFound), "CONNECTION_NOT_FOUND", 109, Error, false, 404, 69),
+        spec(Connection(Refused), "CONNECTION_REFUSED", 110, Warning, true, 502, 69),
+        spec(Connection(Invalid), "CONNECTION_INVALID", 111, Error, false, 500, 69),
+        spec(Connection(Aborted), "CONNECTION_ABORTED", 112, Warning, true, 502, 69),
+        spec(Connection(Reset), "CONNECTION_RESET", 113, Warning, true, 502, 69),
+        spec(Connection(Parse), "CONNECTION_PARSE", 114, Error, false, 500, 69),
+
+        spec(Address(NotImplemented), "ADDRESS_NOT_IMPLEMENTED", 201, Error, false, 501, 69),
+        spec(Address(AlreadyExists), "ADDRESS_ALREADY_EXISTS", 202, Error, false, 409, 69),
+        spec(Address(AccessDenied), "ADDRESS_ACCESS_DENIED", 203, Error, false, 403, 77),
+        spec(Address(NotSupported), "ADDRESS_NOT_SUPPORTED", 204, Error, false, 501, 69),
+        spec(Address(Interrupted), "ADDRESS_INTERRUPTED", 205, Warning, true, 503, 75),
+        spec(Address(NotEnough), "ADDRESS_NOT_ENOUGH", 206, Error, false, 503, 69),
+        spec(Address(Timedout), "ADDRESS_TIMED_OUT", 207, Warning, true, 504, 75),
+        spec(Address(Overflow), "ADDRESS_OVERFLOW", 208, Error, false, 500, 69),
+        spec(Address(NotFound), "ADDRESS_NOT_FOUND", 209, Error, false, 404, 69),
+        spec(Address(Refused), "ADDRESS_REFUSED", 210, Error, false, 502, 69),
+        spec(Address(Invalid), "ADDRESS_INVALID", 211, Error, false, 500, 69),
+        spec(Address(Aborted), "ADDRESS_ABORTED", 212, Error, false, 502, 69),
+        spec(Address(Reset), "ADDRESS_RESET", 213, Error, false, 502, 69),
+        spec(Address(Parse), "ADDRESS_PARSE", 214, Error, false, 500, 69),
+
+        spec(Memory(NotImplemented), "MEMORY_NOT_IMPLEMENTED", 301, Critical, false, 501, 71),
+        spec(Memory(AlreadyExists), "MEMORY_ALREADY_EXISTS", 302, Critical, false, 409, 71),
+        spec(Memory(AccessDenied), "MEMORY_ACCESS_DENIED", 303, Critical, false, 403, 77),
+        spec(Memory(NotSupported), "MEMORY_NOT_SUPPORTED", 304, Critical, false, 501, 71),
+        spec(Memory(Interrupted), "MEMORY_INTERRUPTED", 305, Critical, true, 503, 75),
+        spec(Memory(NotEnough), "MEMORY_NOT_ENOUGH", 306, Critical, false, 503, 71),
+        spec(Memory(Timedout), "MEMORY_TIMED_OUT", 307, Critical, true, 504, 75),
+        spec(Memory(Overflow), "MEMORY_OVERFLOW", 308, Critical, false, 500, 71),
+        spec(Memory(NotFound), "MEMORY_NOT_FOUND", 309, Critical, false, 404, 71),
+        spec(Memory(Refused), "MEMORY_REFUSED", 310, Critical, false, 502, 71),
+        spec(Memory(Invalid), "MEMORY_INVALID", 311, Critical, false, 500, 71),
+        spec(Memory(Aborted), "MEMORY_ABORTED", 312, Critical, false, 502, 71),
+        spec(Memory(Reset), "MEMORY_RESET", 313, Critical, false, 502, 71),
+        spec(Memory(Parse), "MEMORY_PARSE", 314, Critical, false, 500, 71),
+
+        spec(Buffer(NotImplemented), "BUFFER_NOT_IMPLEMENTED", 401, Error, false, 501, 74),
+        spec(Buffer(AlreadyExists), "BUFFER_ALREADY_EXISTS", 402, Error, false, 409, 74),
+        spec(Buffer(AccessDenied), "BUFFER_ACCESS_DENIED", 403, Error, false, 403, 77),
+        spec(Buffer(NotSupported), "BUFFER_NOT_SUPPORTED", 404, Error, false, 501, 74),
+        spec(Buffer(Interrupted), "BUFFER_INTERRUPTED", 405, Warning, true, 503, 75),
+        spec(Buffer(NotEnough), "BUFFER_NOT_ENOUGH", 406, Error, false, 503, 74),
+        spec(Buffer(Timedout), "BUFFER_TIMED_OUT", 407, Warning, true, 504, 75),
+        spec(Buffer(Overflow), "BUFFER_OVERFLOW", 408, Error, false, 500, 74),
+        spec(Buffer(NotFound), "BUFFER_NOT_FOUND", 409, Error, false, 404, 74),
+        spec(Buffer(Refused), "BUFFER_REFUSED", 410, Error, false, 502, 74),
+        spec(Buffer(Invalid), "BUFFER_INVALID", 411, Error, false, 500, 74),
+        spec(Buffer(Aborted), "BUFFER_ABORTED", 412, Error, false, 502, 74),
+        spec(Buffer(Reset), "BUFFER_RESET", 413, Error, false, 502, 74),
+        spec(Buffer(Parse), "BUFFER_PARSE", 414, Error, false, 500, 74),
+
+        spec(Envvar(NotImplemented), "ENVVAR_NOT_IMPLEMENTED", 501, Error, false, 501, 78),
+        spec(Envvar(AlreadyExists), "ENVVAR_ALREADY_EXISTS", 502, Error, false, 409, 78),
+        spec(Envvar(AccessDenied), "ENVVAR_ACCESS_DENIED", 503, Error, false, 403, 77),
+        spec(Envvar(NotSupported), "ENVVAR_NOT_SUPPORTED", 504, Error, false, 501, 78),
+        spec(Envvar(Interrupted), "ENVVAR_INTERRUPTED", 505, Warning, true, 503, 75),
+        spec(Envvar(NotEnough), "ENVVAR_NOT_ENOUGH", 506, Error, false, 503, 78),
+        spec(Envvar(Timedout), "ENVVAR_TIMED_OUT", 507, Warning, true, 504, 75),
+        spec(Envvar(Overflow), "ENVVAR_OVERFLOW", 508, Error, false, 500, 78),
+        spec(Envvar(NotFound), "ENVVAR_NOT_FOUND", 509, Error, false, 404, 78),
+        spec(Envvar(Refused), "ENVVAR_REFUSED", 510, Error, false, 502, 78),
+        spec(Envvar(Invalid), "ENVVAR_INVALID", 511, Error, false, 500, 78),
+        spec(Envvar(Aborted), "ENVVAR_ABORTED", 512, Error, false, 502, 78),
+        spec(Envvar(Reset), "ENVVAR_RESET", 513, Error, false, 502, 78),
+        spec(Envvar(Parse), "ENVVAR_PARSE", 514, Error, false, 500, 78),
+
+        spec(Other(NotImplemented), "OTHER_NOT_IMPLEMENTED", 601, Error, false, 501, 1),
+        spec(Other(AlreadyExists), "OTHER_ALREADY_EXISTS", 602, Error, false, 409, 1),
+        spec(Other(AccessDenied), "OTHER_ACCESS_DENIED", 603, Error, false, 403, 77),
+        spec(Other(NotSupported), "OTHER_NOT_SUPPORTED", 604, Error, false, 501, 1),
+        spec(Other(Interrupted), "OTHER_INTERRUPTED", 605, Warning, true, 503, 75),
+        spec(Other(NotEnough), "OTHER_NOT_ENOUGH", 606, Error, false, 503, 1),
+        spec(Other(Timedout), "OTHER_TIMED_OUT", 607, Warning, true, 504, 75),
+        spec(Other(Overflow), "OTHER_OVERFLOW", 608, Error, false, 500, 1),
+        spec(Other(NotFound), "OTHER_NOT_FOUND", 609, Error, false, 404, 1),
+        spec(Other(Refused), "OTHER_REFUSED", 610, Error, false, 502, 1),
+        spec(Other(Invalid), "OTHER_INVALID", 611, Error, false, 500, 1),
+        spec(Other(Aborted), "OTHER_ABORTED", 612, Error, false, 502, 1),
+        spec(Other(Reset), "OTHER_RESET", 613, Error, false, 502, 1),
+        spec(Other(Parse), "OTHER_PARSE", 614, Error, false, 500, 1),
+
+        spec(Input(NotImplemented), "INPUT_NOT_IMPLEMENTED", 701, Error, false, 501, 64),
+        spec(Input(AlreadyExists), "INPUT_ALREADY_EXISTS", 702, Error, false, 409, 64),
+        spec(Input(AccessDenied), "INPUT_ACCESS_DENIED", 703, Error, false, 403, 64),
+        spec(Input(NotSupported), "INPUT_NOT_SUPPORTED", 704, Error, false, 501, 64),
+        spec(Input(Interrupted), "INPUT_INTERRUPTED", 705, Warning, true, 503, 64),
+        spec(Input(NotEnough), "INPUT_NOT_ENOUGH", 706, Error, false, 503, 64),
+        spec(Input(Timedout), "INPUT_TIMED_OUT", 707, Warning, true, 504, 64),
+        spec(Input(Overflow), "INPUT_OVERFLOW", 708, Error, false, 413, 64),
+        spec(Input(NotFound), "INPUT_NOT_FOUND", 709, Error, false, 404, 64),
+        spec(Input(Refused), "INPUT_REFUSED", 710, Error, false, 502, 64),
+        spec(Input(Invalid), "INPUT_INVALID", 711, Error, false, 400, 64),
+        spec(Input(Aborted), "INPUT_ABORTED", 712, Error, false, 502, 64),
+        spec(Input(Reset), "INPUT_RESET", 713, Error, false, 502, 64),
+        spec(Input(Parse), "INPUT_PARSE", 714, Error, false, 400, 64),
+
+        spec(File(NotImplemented), "FILE_NOT_IMPLEMENTED", 801, Error, false, 501, 74),
+        spec(File(AlreadyExists), "FILE_ALREADY_EXISTS", 802, Error, false, 409, 74),
+        spec(File(AccessDenied), "FILE_ACCESS_DENIED", 803, Error, false, 403, 77),
+        spec(File(NotSupported), "FILE_NOT_SUPPORTED", 804, Error, false, 501, 74),
+        spec(File(Interrupted), "FILE_INTERRUPTED", 805, Warning, true, 503, 75),
+        spec(File(NotEnough), "FILE_NOT_ENOUGH", 806, Error, false, 503, 74),
+        spec(File(Timedout), "FILE_TIMED_OUT", 807, Warning, true, 504, 75),
+        spec(File(Overflow), "FILE_OVERFLOW", 808, Error, false, 500, 74),
+        spec(File(NotFound), "FILE_NOT_FOUND", 809, Error, false, 404, 66),
+        spec(File(Refused), "FILE_REFUSED", 810, Error, false, 502, 74),
+        spec(File(Invalid), "FILE_INVALID", 811, Error, false, 500, 74),
+        spec(File(Aborted), "FILE_ABORTED", 812, Error, false, 502, 74),
+        spec(File(Reset), "FILE_RESET", 813, Error, false, 502, 74),
+        spec(File(Parse), "FILE_PARSE", 814, Error, false, 500, 74),
+
+        spec(Path(NotImplemented), "PATH_NOT_IMPLEMENTED", 901, Error, false, 501, 1),
+        spec(Path(AlreadyExists), "PATH_ALREADY_EXISTS", 902, Error, false, 409, 1),
+        spec(Path(AccessDenied), "PATH_ACCESS_DENIED", 903, Error, false, 403, 77),
+        spec(Path(NotSupported), "PATH_NOT_SUPPORTED", 904, Error, false, 501, 1),
+        spec(Path(Interrupted), "PATH_INTERRUPTED", 905, Warning, true, 503, 75),
+        spec(Path(NotEnough), "PATH_NOT_ENOUGH", 906, Error, false, 503, 1),
+        spec(Path(Timedout), "PATH_TIMED_OUT", 907, Warning, true, 504, 75),
+        spec(Path(Overflow), "PATH_OVERFLOW", 908, Error, false, 500, 1),
+        spec(Path(NotFound), "PATH_NOT_FOUND", 909, Error, false, 404, 66),
+        spec(Path(Refused), "PATH_REFUSED", 910, Error, false, 502, 1),
+        spec(Path(Invalid), "PATH_INVALID", 911, Error, false, 500, 1),
+        spec(Path(Aborted), "PATH_ABORTED", 912, Error, false, 502, 1),
+        spec(Path(Reset), "PATH_RESET", 913, Error, false, 502, 1),
+        spec(Path(Parse), "PATH_PARSE", 914, Error, false, 500, 1),
+
+        spec(Data(NotImplemented), "DATA_NOT_IMPLEMENTED", 1001, Error, false, 501, 65),
+        spec(Data(AlreadyExists), "DATA_ALREADY_EXISTS", 1002, Error, false, 409, 65),
+        spec(Data(AccessDenied), "DATA_ACCESS_DENIED", 1003, Error, false, 403, 65),
+        spec(Data(NotSupported), "DATA_NOT_SUPPORTED", 1004, Error, false, 501, 65),
+        spec(Data(Interrupted), "DATA_INTERRUPTED", 1005, Warning, true, 503, 65),
+        spec(Data(NotEnough), "DATA_NOT_ENOUGH", 1006, Error, false, 503, 65),
+        spec(Data(Timedout), "DATA_TIMED_OUT", 1007, Warning, true, 504, 65),
+        spec(Data(Overflow), "DATA_OVERFLOW", 1008, Error, false, 500, 65),
+        spec(Data(NotFound), "DATA_NOT_FOUND", 1009, Error, false, 404, 65),
+        spec(Data(Refused), "DATA_REFUSED", 1010, Error, false, 502, 65),
+        spec(Data(Invalid), "DATA_INVALID", 1011, Error, false, 422, 65),
+        spec(Data(Aborted), "DATA_ABORTED", 1012, Error, false, 502, 65),
+        spec(Data(Reset), "DATA_RESET", 1013, Error, false, 502, 65),
+        spec(Data(Parse), "DATA_PARSE", 1014, Error, false, 422, 65),
+
+        spec(Call(NotImplemented), "CALL_NOT_IMPLEMENTED", 1101, Error, false, 501, 1),
+        spec(Call(AlreadyExists), "CALL_ALREADY_EXISTS", 1102, Error, false, 409, 1),
+        spec(Call(AccessDenied), "CALL_ACCESS_DENIED", 1103, Error, false, 403, 77),
+        spec(Call(NotSupported), "CALL_NOT_SUPPORTED", 1104, Error, false, 501, 1),
+        spec(Call(Interrupted), "CALL_INTERRUPTED", 1105, Warning, true, 503, 75),
+        spec(Call(NotEnough), "CALL_NOT_ENOUGH", 1106, Error, false, 503, 1),
+        spec(Call(Timedout), "CALL_TIMED_OUT", 1107, Warning, true, 504, 75),
+        spec(Call(Overflow), "CALL_OVERFLOW", 1108, Error, false, 500, 1),
+        spec(Call(NotFound), "CALL_NOT_FOUND", 1109, Error, false, 404, 1),
+        spec(Call(Refused), "CALL_REFUSED", 1110, Error, false, 502, 1),
+        spec(Call(Invalid), "CALL_INVALID", 1111, Error, false, 500, 1),
+        spec(Call(Aborted), "CALL_ABORTED", 1112, Error, false, 502, 1),
+        spec(Call(Reset), "CALL_RESET", 1113, Error, false, 502, 1),
+        spec(Call(Parse), "CALL_PARSE", 1114, Error, false, 500, 1),
+
+        spec(Io(NotImplemented), "IO_NOT_IMPLEMENTED", 1201, Error, false, 501, 74),
+        spec(Io(AlreadyExists), "IO_ALREADY_EXISTS", 1202, Error, false, 409, 74),
+        spec(Io(AccessDenied), "IO_ACCESS_DENIED", 1203, Error, false, 403, 77),
+        spec(Io(NotSupported), "IO_NOT_SUPPORTED", 1204, Error, false, 501, 74),
+        spec(Io(Interrupted), "IO_INTERRUPTED", 1205, Warning, true, 503, 75),
+        spec(Io(NotEnough), "IO_NOT_ENOUGH", 1206, Error, false, 503, 74),
+        spec(Io(Timedout), "IO_TIMED_OUT", 1207, Warning, true, 504, 75),
+        spec(Io(Overflow), "IO_OVERFLOW", 1208, Error, false, 500, 74),
+        spec(Io(NotFound), "IO_NOT_FOUND", 1209, Error, false, 404, 66),
+        spec(Io(Refused), "IO_REFUSED", 1210, Error, false, 502, 74),
+        spec(Io(Invalid), "IO_INVALID", 1211, Error, false, 500, 74),
+        spec(Io(Aborted), "IO_ABORTED", 1212, Error, false, 502, 74),
+        spec(Io(Reset), "IO_RESET", 1213, Error, false, 502, 74),
+        spec(Io(Parse), "IO_PARSE", 1214, Error, false, 500, 74),
+
+        spec(Security(NotImplemented), "SECURITY_NOT_IMPLEMENTED", 1301, Error, false, 501, 77),
+        spec(Security(AlreadyExists), "SECURITY_ALREADY_EXISTS", 1302, Error, false, 409, 77),
+        spec(Security(AccessDenied), "SECURITY_ACCESS_DENIED", 1303, Error, false, 403, 77),
+        spec(Security(NotSupported), "SECURITY_NOT_SUPPORTED", 1304, Error, false, 501, 77),
+        spec(Security(Interrupted), "SECURITY_INTERRUPTED", 1305, Warning, true, 503, 75),
+        spec(Security(NotEnough), "SECURITY_NOT_ENOUGH", 1306, Error, false, 503, 77),
+        spec(Security(Timedout), "SECURITY_TIMED_OUT", 1307, Warning, true, 504, 75),
+        spec(Security(Overflow), "SECURITY_OVERFLOW", 1308, Error, false, 500, 77),
+        spec(Security(NotFound), "SECURITY_NOT_FOUND", 1309, Error, false, 404, 77),
+        spec(Security(Refused), "SECURITY_REFUSED", 1310, Error, false, 502, 77),
+        spec(Security(Invalid), "SECURITY_INVALID", 1311, Error, false, 500, 77),
+        spec(Security(Aborted), "SECURITY_ABORTED", 1312, Error, false, 502, 77),
+        spec(Security(Reset), "SECURITY_RESET", 1313, Error, false, 502, 77),
+        spec(Security(Parse), "SECURITY_PARSE", 1314, Error, false, 500, 77),
+
+        spec(Database(NotImplemented), "DATABASE_NOT_IMPLEMENTED", 1401, Error, false, 501, 69),
+        spec(Database(AlreadyExists), "DATABASE_ALREADY_EXISTS", 1402, Error, false, 409, 69),
+        spec(Database(AccessDenied), "DATABASE_ACCESS_DENIED", 1403, Error, false, 403, 77),
+        spec(Database(NotSupported), "DATABASE_NOT_SUPPORTED", 1404, Error, false, 501, 69),
+        spec(Database(Interrupted), "DATABASE_INTERRUPTED", 1405, Warning, true, 503, 75),
+        spec(Database(NotEnough), "DATABASE_NOT_ENOUGH", 1406, Error, false, 503, 69),
+        spec(Database(Timedout), "DATABASE_TIMED_OUT", 1407, Warning, true, 504, 75),
+        spec(Database(Overflow), "DATABASE_OVERFLOW", 1408, Error, false, 500, 69),
+        spec(Database(NotFound), "DATABASE_NOT_FOUND", 1409, Error, false, 404, 69),
+        spec(Database(Refused), "DATABASE_REFUSED", 1410, Warning, true, 503, 69),
+        spec(Database(Invalid), "DATABASE_INVALID", 1411, Error, false, 500, 69),
+        spec(Database(Aborted), "DATABASE_ABORTED", 1412, Warning, true, 409, 69),
+        spec(Database(Reset), "DATABASE_RESET", 1413, Warning, true, 503, 69),
+        spec(Database(Parse), "DATABASE_PARSE", 1414, Error, false, 500, 69),
+
+        spec(NoError, "NO_ERROR", 0, Info, false, 200, 0),
+        spec(Unknown(None), "UNKNOWN", 9999, Error, false, 500, 1),
+    ]
+};
+
+/// the mappings of custom types for every predefined category, the row of
+/// `File(Custom("custom"))` is the one of `File(Custom("rate limited"))` except for the code
+pub const CUSTOM_TYPES: &[ErrorSpec] = {
+    use CursedError::*;
+    use CursedSeverity::*;
+
+    &[
+        spec(Connection(CursedErrorType::Custom("custom")), "CONNECTION_CUSTOM", 199, Error, false, 500, 69),
+        spec(Address(CursedErrorType::Custom("custom")), "ADDRESS_CUSTOM", 299, Error, false, 500, 69),
+        spec(Memory(CursedErrorType::Custom("custom")), "MEMORY_CUSTOM", 399, Critical, false, 500, 71),
+        spec(Buffer(CursedErrorType::Custom("custom")), "BUFFER_CUSTOM", 499, Error, false, 500, 74),
+        spec(Envvar(CursedErrorType::Custom("custom")), "ENVVAR_CUSTOM", 599, Error, false, 500, 78),
+        spec(Other(CursedErrorType::Custom("custom")), "OTHER_CUSTOM", 699, Error, false, 500, 1),
+        spec(Input(CursedErrorType::Custom("custom")), "INPUT_CUSTOM", 799, Error, false, 500, 64),
+        spec(File(CursedErrorType::Custom("custom")), "FILE_CUSTOM", 899, Error, false, 500, 74),
+        spec(Path(CursedErrorType::Custom("custom")), "PATH_CUSTOM", 999, Error, false, 500, 1),
+        spec(Data(CursedErrorType::Custom("custom")), "DATA_CUSTOM", 1099, Error, false, 500, 65),
+        spec(Call(CursedErrorType::Custom("custom")), "CALL_CUSTOM", 1199, Error, false, 500, 1),
+        spec(Io(CursedErrorType::Custom("custom")), "IO_CUSTOM", 1299, Error, false, 500, 74),
+        spec(Security(CursedErrorType::Custom("custom")), "SECURITY_CUSTOM", 1399, Error, false, 500, 77),
+        spec(Database(CursedErrorType::Custom("custom")), "DATABASE_CUSTOM", 1499, Error, false, 500, 69),
+    ]
+};
+
+const _: () = {
+    assert!(REGISTRY.len() == ALL_ERRORS.len(), "every error needs a row in the registry");
+    let mut index = 0;
+    while index < REGISTRY.len() {
+        assert!(REGISTRY[index].error.index() as usize == index, "registry rows are out of order");
+        index += 1;
+    }
+
+    assert!(CUSTOM_TYPES.len() == TYPED_CATEGORIES.len(), "every category needs a row for custom types");
+};
+
+/// the row deciding the mappings of `error`
+pub(crate) fn spec_of(error: &CursedError) -> &'static ErrorSpec {
+    let error = match *error {
+        CursedError::Custom { kind, .. } => CursedError::Other(kind),
+        error => error,
+    };
+
+    match error.get_type() {
+        Some(CursedErrorType::Custom(_)) => {
+            let category = TYPED_CATEGORIES.iter().position(|category| *category == error.category()).unwrap_or(0);
+            &CUSTOM_TYPES[category]
+        },
+        _ => &REGISTRY[error.index() as usize],
+    }
+}
@@ -0,0 +1,686 @@
+//! rendering and collecting errors: reports, rate limited and sampled reporting and statistics
+
+use crate::*;
+
+/// multi-line description of a [`CursedErrorHandle`], created by [`CursedErrorHandle::report`]
+pub struct CursedReport<'a> {
+    pub(crate) handle: &'a CursedErrorHandle,
+    pub(crate) redact_patterns: &'a [&'a str],
+}
+
+impl<'a> CursedReport<'a> {
+    unstable! {
+        /// masks every occurrence of the patterns in the output with `[REDACTED]`
+        ///
+        /// # Examples
+        /// ```
+        /// use curerr::*;
+        ///
+        /// let error = CursedErrorHandle::new(
+        ///     CursedError::Connection(CursedErrorType::Refused),
+        ///     "can't connect to postgres://admin:hunter2@db".to_string()
+        /// ).with_field("dsn", "postgres://admin:hunter2@db");
+        ///
+        /// assert_eq!(
+        ///     error.report().redact_patterns(&["hunter2"]).to_string(),
+        ///     "connection refused error: \"can't connect to postgres://admin:[REDACTED]@db\"\n  \
+        ///      dsn = postgres://admin:[REDACTED]@db"
+        /// );
+        /// ```
+        pub fn redact_patterns(mut self, patterns: &'a [&'a str]) -> Self {
+            self.redact_patterns = patterns;
+            self
+        }
+    }
+    fn write(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        if let Some(namespace) = self.handle.namespace() {
+            write!(f, "[{}] ", namespace)?;
+        }
+        write!(f, "{} error: \"{}\"", self.handle.get_error(), self.handle.get_reason().replace('\n', "\n  "))?;
+
+        if let Some(op) = self.handle.op() {
+            write!(f, "\n  op: {}", op)?;
+        }
+
+        for context in self.handle.contexts() {
+            write!(f, "\n  context: {}", context)?;
+        }
+        for (key, value) in self.handle.fields() {
+            write!(f, "\n  {} = {}", key, value)?;
+        }
+        if let Some(os_code) = self.handle.os_code() {
+            write!(f, "\n  os error: {}", os_code)?;
+        }
+        for (index, location) in self.handle.trace().enumerate() {
+            let separator = if index == 0 { "\n  at" } else { " ->" };
+            write!(f, "{} {}:{}", separator, location.file(), location.line())?;
+        }
+
+        for error in self.handle.reported_sources() {
+            match error.downcast_ref::<CursedErrorHandle>().and_then(CursedErrorHandle::namespace) {
+                Some(namespace) => write!(f, "\ncaused by: [{}] {}", namespace, error)?,
+                None => write!(f, "\ncaused by: {}", error)?,
+            }
+        }
+        if let Some(help) = self.handle.get_help() {
+            write!(f, "\nhelp: {}", help.replace('\n', "\n      "))?;
+        }
+        if let Some(url) = self.handle.docs_url() {
+            write!(f, "\ndocs: {}", url)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for CursedReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.redact_patterns.is_empty() {
+            return self.write(f);
+        }
+
+        let mut output = String::new();
+        self.write(&mut output)?;
+        for pattern in self.redact_patterns.iter().filter(|pattern| !pattern.is_empty()) {
+            output = output.replace(pattern, "[REDACTED]");
+        }
+        f.write_str(&output)
+    }
+}
+
+/// multi-line description of a [`MultiError`], created by [`MultiError::report`]
+pub struct MultiReport<'a> {
+    pub(crate) errors: &'a MultiError,
+}
+
+impl std::fmt::Display for MultiReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (errors, max_groups) = (self.errors.len(), self.errors.max_groups());
+        write!(f, "{} {}:", errors, if errors == 1 { "error" } else { "errors" })?;
+
+        let groups = self.errors.group_duplicates();
+        for group in groups.iter().take(max_groups) {
+            write!(f, "\n  {}", group[0].report().to_string().replace('\n', "\n  "))?;
+            if group.len() > 1 {
+                write!(f, "\n    (×{})", group.len())?;
+            }
+        }
+        if groups.len() > max_groups {
+            write!(f, "\n  … and {} more", groups.len() - max_groups)?;
+        }
+        Ok(())
+    }
+}
+
+/// result to return from `fn main`, errors are printed as their [report](CursedErrorHandle::report)
+///
+/// The standard library exits with code 1 for any error returned from `main`, convert the
+/// result into a [`MainExit`] or use [`run_main`] to exit with the
+/// [exit code](CursedErrorHandle::exit_code) of the error.
+///
+/// # Examples
+/// ```no_run
+/// fn main() -> curerr::MainResult {
+///     let config = curerr::wrap_io("read", "app.toml", || std::fs::read_to_string("app.toml"))?;
+///     println!("{}", config);
+///     Ok(())
+/// }
+/// ```
+pub type MainResult<T = ()> = Result<T, MainError>;
+
+/// [`MainResult`] to return from `fn main` that prints the report of the error to stderr
+/// and exits with its [exit code](CursedErrorHandle::exit_code)
+///
+/// # Examples
+/// ```no_run
+/// use curerr::*;
+///
+/// fn run() -> MainResult {
+///     let port = std::env::var("PORT").map_err(|_| CursedErrorHandle::new(
+///         CursedError::Envvar(CursedErrorType::NotFound),
+///         "PORT is not set".to_string()
+///     ))?;
+///     println!("listening on {}", port);
+///     Ok(())
+/// }
+///
+/// // exits with 78 when PORT is not set
+/// fn main() -> MainExit {
+///     run().into()
+/// }
+/// ```
+pub struct MainExit<T = ()>(pub MainResult<T>);
+
+impl<T, E> From<Result<T, E>> for MainExit<T> where MainError: From<E> {
+    #[track_caller]
+    fn from(result: Result<T, E>) -> Self {
+        MainExit(result.map_err(MainError::from))
+    }
+}
+
+impl<T: std::process::Termination> std::process::Termination for MainExit<T> {
+    fn report(self) -> std::process::ExitCode {
+        match self.0 {
+            Ok(value) => value.report(),
+            Err(MainError(error)) => {
+                eprintln!("{}", error.report());
+                std::process::ExitCode::from(error.exit_code())
+            },
+        }
+    }
+}
+
+/// error of [`MainResult`], converted from anything that converts to [`CursedErrorHandle`]
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let error = MainError::from(
+///     CursedErrorHandle::new(CursedError::Envvar(CursedErrorType::NotFound), "HOME is not set".to_string())
+///         .help("export HOME")
+/// );
+///
+/// assert_eq!(format!("{:?}", error), "envvar not found error: \"HOME is not set\"\nhelp: export HOME");
+/// assert_eq!(error.handle().exit_code(), 78);
+/// ```
+pub struct MainError(CursedErrorHandle);
+
+impl MainError {
+    pub fn handle(&self) -> &CursedErrorHandle {
+        &self.0
+    }
+    pub fn into_handle(self) -> CursedErrorHandle {
+        self.0
+    }
+}
+
+impl<E> From<E> for MainError where CursedErrorHandle: From<E> {
+    #[track_caller]
+    fn from(error: E) -> Self {
+        MainError(CursedErrorHandle::from(error))
+    }
+}
+
+/// the report, which is what `fn main` prints
+impl std::fmt::Debug for MainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0.report(), f)
+    }
+}
+
+impl std::fmt::Display for MainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// runs `main`, prints the report of the error it returns to stderr and exits with
+/// the [exit code](CursedErrorHandle::exit_code) of the error
+///
+/// # Examples
+/// ```no_run
+/// use curerr::*;
+/// use std::process::ExitCode;
+///
+/// fn run() -> CursedResult<()> {
+///     let home = std::env::var("HOME").context("while reading HOME")?;
+///     println!("{}", home);
+///     Ok(())
+/// }
+///
+/// fn main() -> ExitCode {
+///     run_main(run)
+/// }
+/// ```
+pub fn run_main(main: impl FnOnce() -> CursedResult<()>) -> std::process::ExitCode {
+    std::process::Termination::report(MainExit(main().map_err(MainError)))
+}
+
+type ReporterSink = Box<dyn Fn(&str) + Send + Sync>;
+type ReporterClock = Box<dyn Fn() -> std::time::Instant + Send + Sync>;
+
+/// reports errors at most `max_per_window` times per window for each
+/// [fingerprint](CursedErrorRef::fingerprint) of their view, counting the rest
+///
+/// The fingerprint of the view leaves out the fields, so errors that only differ in them
+/// are reported as similar. Lines are the [`Display`](std::fmt::Display) of the view.
+/// When the window of a fingerprint rolls over, a summary like
+/// `suppressed 4 312 similar errors: ...` is reported before the next error. Lines go to
+/// stderr unless a sink is set.
+///
+/// At most 1024 fingerprints are tracked. Past that the windows that rolled over are
+/// dropped, or the oldest one if none did, after reporting their summaries.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::sync::{Arc, Mutex};
+/// use std::time::{Duration, Instant};
+///
+/// let lines = Arc::new(Mutex::new(Vec::new()));
+/// let now = Arc::new(Mutex::new(Instant::now()));
+///
+/// let reporter = ErrorReporter::new(Duration::from_secs(60), 2)
+///     .with_sink({
+///         let lines = lines.clone();
+///         move |line| lines.lock().unwrap().push(line.to_string())
+///     })
+///     .with_clock({
+///         let now = now.clone();
+///         move || *now.lock().unwrap()
+///     });
+///
+/// for id in 0..1002 {
+///     reporter.report(&CursedErrorHandle::new(
+///         CursedError::Connection(CursedErrorType::Refused),
+///         format!("peer {} refused", id)
+///     ));
+/// }
+/// assert_eq!(lines.lock().unwrap().len(), 2);
+///
+/// *now.lock().unwrap() += Duration::from_secs(61);
+/// reporter.report(&CursedErrorHandle::new(
+///     CursedError::Connection(CursedErrorType::Refused),
+///     "peer 1002 refused".to_string()
+/// ));
+///
+/// assert_eq!(*lines.lock().unwrap(), [
+///     "connection refused error: \"peer 0 refused\"",
+///     "connection refused error: \"peer 1 refused\"",
+///     "suppressed 1 000 similar errors: connection refused error: \"peer 1001 refused\"",
+///     "connection refused error: \"peer 1002 refused\"",
+/// ]);
+///
+/// for _ in 0..3 {
+///     reporter.report(&CursedErrorHandle::new_static(&const { StaticError::new(CursedError::Memory(CursedErrorType::NotEnough), "heap") }));
+/// }
+/// reporter.flush();
+///
+/// assert_eq!(
+///     lines.lock().unwrap().last().unwrap(),
+///     "suppressed 1 similar error: memory not enough error: \"heap\""
+/// );
+/// ```
+pub struct ErrorReporter {
+    window: std::time::Duration,
+    max_per_window: u32,
+    sink: ReporterSink,
+    clock: ReporterClock,
+    windows: std::sync::Mutex<std::collections::HashMap<u64, ReporterWindow>>,
+}
+
+struct ReporterWindow {
+    start: std::time::Instant,
+    reported: u32,
+    suppressed: u64,
+    /// the line of the last suppressed error, its buffer is reused
+    last: String,
+}
+
+impl ErrorReporter {
+    /// number of tracked fingerprints after which finished windows are dropped, and the
+    /// oldest one when none has finished
+    const MAX_WINDOWS: usize = 1024;
+
+    pub fn new(window: std::time::Duration, max_per_window: u32) -> Self {
+        Self {
+            window,
+            max_per_window,
+            sink: Box::new(|line| eprintln!("{}", line)),
+            clock: Box::new(std::time::Instant::now),
+            windows: std::sync::Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+    /// passes the reported lines to `sink` instead of stderr
+    pub fn with_sink(mut self, sink: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.sink = Box::new(sink);
+        self
+    }
+    /// uses `clock` instead of [`std::time::Instant::now`]
+    pub fn with_clock(mut self, clock: impl Fn() -> std::time::Instant + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+    /// reports `error`, a handle or any other error with a [`CursedErrorRef`], unless similar
+    /// errors were already reported too often in the current window
+    pub fn report<'a>(&self, error: impl Into<CursedErrorRef<'a>>) {
+        use std::fmt::Write;
+
+        let error = error.into();
+        let fingerprint = error.fingerprint();
+        let now = (self.clock)();
+        let mut lines = Vec::new();
+
+        {
+            let mut windows = self.windows.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            if windows.len() >= Self::MAX_WINDOWS {
+                windows.retain(|_, window| {
+                    let finished = now.saturating_duration_since(window.start) >= self.window;
+                    if finished {
+                        lines.extend(window.summary());
+                    }
+                    !finished
+                });
+            }
+            if windows.len() >= Self::MAX_WINDOWS && !windows.contains_key(&fingerprint) {
+                let oldest = windows.iter().min_by_key(|(_, window)| window.start).map(|(key, _)| *key);
+                if let Some(window) = oldest.and_then(|key| windows.remove(&key)) {
+                    lines.extend(window.summary());
+                }
+            }
+
+            let window = windows.entry(fingerprint).or_insert(ReporterWindow {
+                start: now,
+                reported: 0,
+                suppressed: 0,
+                last: String::new(),
+            });
+            if now.saturating_duration_since(window.start) >= self.window {
+                lines.extend(window.summary());
+                *window = ReporterWindow { start: now, reported: 0, suppressed: 0, last: String::new() };
+            }
+
+            if window.reported < self.max_per_window {
+                window.reported += 1;
+                lines.push(error.to_string());
+            } else {
+                window.suppressed += 1;
+                window.last.clear();
+                let _ = write!(window.last, "{}", error);
+            }
+        }
+
+        for line in lines {
+            (self.sink)(&line);
+        }
+    }
+    /// reports the summaries of all suppressed errors and forgets the windows
+    pub fn flush(&self) {
+        let lines: Vec<_> = self.windows.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .drain()
+            .filter_map(|(_, window)| window.summary())
+            .collect();
+
+        for line in lines {
+            (self.sink)(&line);
+        }
+    }
+}
+
+impl ReporterWindow {
+    fn summary(&self) -> Option<String> {
+        if self.suppressed == 0 {
+            return None
+        }
+        let digits = self.suppressed.to_string();
+
+        let mut count = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                count.push(' ');
+            }
+            count.push(digit);
+        }
+
+        Some(format!("suppressed {} similar {}: {}", count, if self.suppressed == 1 { "error" } else { "errors" }, self.last))
+    }
+}
+
+/// counts errors by their kind, for a summary of what went wrong without a metrics backend
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::sync::Arc;
+///
+/// let stats = Arc::new(ErrorStats::new());
+///
+/// let threads: Vec<_> = (0..4).map(|thread| {
+///     let stats = stats.clone();
+///     std::thread::spawn(move || {
+///         for i in 0..25 {
+///             let error = match (thread + i) % 5 {
+///                 0 | 1 | 2 => CursedError::Connection(CursedErrorType::Refused),
+///                 3 => CursedError::Call(CursedErrorType::Timedout),
+///                 _ => CursedError::NoError,
+///             };
+///             stats.record(&CursedErrorHandle::new(error, format!("request {}", i)));
+///         }
+///     })
+/// }).collect();
+/// for thread in threads {
+///     thread.join().unwrap();
+/// }
+///
+/// assert_eq!(stats.total(), 100);
+/// assert_eq!(stats.count(CursedCategory::Connection, CursedErrorType::Refused), 60);
+/// assert_eq!(stats.count(CursedCategory::Call, CursedErrorType::Timedout), 20);
+/// assert_eq!(stats.count(CursedCategory::NoError, None), 20);
+/// assert_eq!(stats.count(CursedCategory::File, CursedErrorType::NotFound), 0);
+///
+/// assert_eq!(stats.top_n(2), [
+///     (CursedError::Connection(CursedErrorType::Refused), 60),
+///     (CursedError::Call(CursedErrorType::Timedout), 20),
+/// ]);
+/// assert_eq!(
+///     stats.to_string(),
+///     " 60  connection refused\n \
+///       20  call timed out\n \
+///       20  no error\n\
+///      100  total"
+/// );
+/// ```
+///
+/// Installed as an error hook it counts every error created afterwards.
+///
+/// ```
+/// use curerr::*;
+/// use std::sync::Arc;
+///
+/// let stats = Arc::new(ErrorStats::new());
+/// ErrorStats::install(stats.clone());
+///
+/// let _ = "x".parse::<u8>().map_err(wrap_any);
+/// let _ = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Parse), "bad header".to_string());
+/// clear_error_hook();
+/// let _ = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Parse), "not counted".to_string());
+///
+/// assert_eq!(stats.count(CursedCategory::Data, CursedErrorType::Parse), 2);
+/// ```
+#[derive(Default)]
+pub struct ErrorStats {
+    counts: std::sync::Mutex<std::collections::HashMap<CursedError, u64>>,
+}
+
+impl ErrorStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// adds `stats` as an error hook, next to the ones of [`add_error_hook`]
+    ///
+    /// Static handles don't call the hook, so they are only counted when they get modified.
+    pub fn install(stats: std::sync::Arc<ErrorStats>) {
+        add_error_hook(move |error| stats.record(error));
+    }
+    /// counts the kind of `error`, a handle or any other error with a [`CursedErrorRef`]
+    pub fn record<'a>(&self, error: impl Into<CursedErrorRef<'a>>) {
+        let error = error.into().kind();
+        let mut counts = self.counts.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *counts.entry(error).or_default() += 1;
+    }
+    /// number of recorded errors of the category and type, `None` for errors without a type
+    pub fn count(&self, category: CursedCategory, error_type: impl Into<Option<CursedErrorType>>) -> u64 {
+        let error_type = error_type.into();
+        let counts = self.counts.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        counts.iter()
+            .filter(|(error, _)| error.category() == category && error.get_type() == error_type)
+            .map(|(_, count)| count)
+            .sum()
+    }
+    pub fn total(&self) -> u64 {
+        let counts = self.counts.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        counts.values().sum()
+    }
+    /// the `n` most frequent kinds, ties are ordered by name
+    pub fn top_n(&self, n: usize) -> Vec<(CursedError, u64)> {
+        let mut top: Vec<_> = {
+            let counts = self.counts.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            counts.iter().map(|(error, count)| (*error, *count)).collect()
+        };
+
+        top.sort_by_cached_key(|(error, count)| (std::cmp::Reverse(*count), error.to_string()));
+        top.truncate(n);
+        top
+    }
+    pub fn clear(&self) {
+        self.counts.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+    }
+}
+
+/// one line per kind, the most frequent first, followed by the total
+impl std::fmt::Display for ErrorStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total = self.total();
+        let width = total.to_string().len();
+
+        for (error, count) in self.top_n(usize::MAX) {
+            writeln!(f, "{:>width$}  {}", count, error, width = width)?;
+        }
+        write!(f, "{:>width$}  total", total, width = width)
+    }
+}
+
+type SamplingSink = Box<dyn Fn(CursedErrorRef<'_>) + Send + Sync>;
+
+/// passes a share of the errors of each [severity](CursedError::severity) to a sink, e.g.
+/// every error but only 1% of the warnings about transient failures
+///
+/// The shares are 1 unless set with [`SamplingReporter::with_rate`]. Errors tagged
+/// [`SamplingReporter::FORCE_TAG`] in their `tags` field, like the ones of an
+/// [`ErrorFactory`] with that tag, are always passed on. Each thread samples with its own
+/// pseudo random generator, so there is no locking. The sampled out errors are still
+/// counted, see [`SamplingReporter::sampled_out`].
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+///
+/// let passed = Arc::new(AtomicU64::new(0));
+/// let reporter = SamplingReporter::new({
+///     let passed = passed.clone();
+///     move |_| { passed.fetch_add(1, Ordering::Relaxed); }
+/// }).with_rate(CursedSeverity::Warning, 0.01);
+///
+/// let reset = CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Reset), "reset by peer".to_string());
+/// let broken = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), "bad frame".to_string());
+/// for _ in 0..10_000 {
+///     reporter.report(&reset);
+///     reporter.report(&broken);
+/// }
+///
+/// assert_eq!(reporter.reported(CursedSeverity::Error), 10_000);
+/// let warnings = reporter.reported(CursedSeverity::Warning);
+/// assert!((50..=150).contains(&warnings), "{} warnings reported", warnings);
+/// assert_eq!(reporter.reported(CursedSeverity::Warning) + reporter.sampled_out(CursedSeverity::Warning), 10_000);
+/// assert_eq!(passed.load(Ordering::Relaxed), 10_000 + reporter.reported(CursedSeverity::Warning));
+///
+/// let forced = ErrorFactory::new().tag("force").err_kind(
+///     CursedError::Connection(CursedErrorType::Reset),
+///     "reset during the migration"
+/// );
+/// let (reported, sampled_out) = (reporter.reported(CursedSeverity::Warning), reporter.sampled_out(CursedSeverity::Warning));
+/// for _ in 0..100 {
+///     reporter.report(&forced);
+/// }
+///
+/// assert_eq!(reporter.reported(CursedSeverity::Warning), reported + 100);
+/// assert_eq!(reporter.sampled_out(CursedSeverity::Warning), sampled_out);
+/// ```
+pub struct SamplingReporter {
+    sink: SamplingSink,
+    rates: [f64; 4],
+    reported: [std::sync::atomic::AtomicU64; 4],
+    sampled_out: [std::sync::atomic::AtomicU64; 4],
+}
+
+/// seeds of the per thread generators of [`SamplingReporter`]
+static SAMPLING_SEED: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0x9e3779b97f4a7c15);
+
+thread_local! {
+    static SAMPLING_STATE: std::cell::Cell<u64> = std::cell::Cell::new({
+        // splitmix64 of a counter, so every thread gets a different nonzero state
+        let seed = SAMPLING_SEED.fetch_add(0x9e3779b97f4a7c15, std::sync::atomic::Ordering::Relaxed);
+        let seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        let seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d049bb133111eb);
+        (seed ^ (seed >> 31)) | 1
+    });
+}
+
+/// uniform number in `[0, 1)` from the xorshift64* generator of the thread
+fn sample() -> f64 {
+    SAMPLING_STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        (x.wrapping_mul(0x2545f4914f6cdd1d) >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
+
+impl SamplingReporter {
+    /// tag of the errors that are passed on whatever their share is
+    pub const FORCE_TAG: &'static str = "force";
+
+    /// passes the views of the sampled in errors to `sink`
+    pub fn new(sink: impl Fn(CursedErrorRef<'_>) + Send + Sync + 'static) -> Self {
+        Self {
+            sink: Box::new(sink),
+            rates: [1.0; 4],
+            reported: Default::default(),
+            sampled_out: Default::default(),
+        }
+    }
+    /// share of the errors of `severity` passed on, clamped to `0.0..=1.0`
+    pub fn with_rate(mut self, severity: CursedSeverity, rate: f64) -> Self {
+        self.rates[severity as usize] = rate.clamp(0.0, 1.0);
+        self
+    }
+    /// adds `reporter` as an error hook, next to the ones of [`add_error_hook`]
+    pub fn install(reporter: std::sync::Arc<SamplingReporter>) {
+        add_error_hook(move |error| reporter.report(error));
+    }
+    /// passes `error`, a handle or any other error with a [`CursedErrorRef`], on if it is
+    /// forced or sampled in, otherwise only counts it
+    pub fn report<'a>(&self, error: impl Into<CursedErrorRef<'a>>) {
+        let error = error.into();
+        let severity = error.severity as usize;
+        let rate = self.rates[severity];
+
+        let passed = rate >= 1.0 || is_forced(&error) || (rate > 0.0 && sample() < rate);
+        let counter = if passed { &self.reported[severity] } else { &self.sampled_out[severity] };
+        counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        if passed {
+            (self.sink)(error);
+        }
+    }
+    /// number of errors of `severity` passed to the sink
+    pub fn reported(&self, severity: CursedSeverity) -> u64 {
+        self.reported[severity as usize].load(std::sync::atomic::Ordering::Relaxed)
+    }
+    /// number of errors of `severity` that were only counted
+    pub fn sampled_out(&self, severity: CursedSeverity) -> u64 {
+        self.sampled_out[severity as usize].load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+fn is_forced(error: &CursedErrorRef<'_>) -> bool {
+    error.tags.is_some_and(|tags| tags.split(", ").any(|tag| tag == SamplingReporter::FORCE_TAG))
+}
@@ -0,0 +1,166 @@
+//! the JSON form of errors and handles, written by `serde` and [`CursedErrorHandle::canonical_json`]
+
+use crate::*;
+use crate::handle::{write_json_str, Field};
+
+use std::borrow::Cow;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct ErrorRepr<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub(crate) category: Cow<'a, str>,
+    #[cfg_attr(feature = "serde", serde(rename = "type", borrow))]
+    pub(crate) error_type: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub(crate) custom: bool,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub(crate) io_kind: Option<Cow<'a, str>>,
+}
+
+impl ErrorRepr<'_> {
+    pub(crate) fn new(error: &CursedError) -> Self {
+        Self {
+            category: Cow::Borrowed(error.category().to_id()),
+            error_type: error.get_type().map(|error_type| Cow::Borrowed(error_type.to_id())),
+            custom: matches!(error, CursedError::Custom { .. }),
+            io_kind: match error {
+                CursedError::Unknown(Some(kind)) => Some(Cow::Owned(format!("{:?}", kind))),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct HandleRepr<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten, borrow))]
+    pub(crate) error: ErrorRepr<'a>,
+    #[cfg_attr(feature = "serde", serde(default, skip_deserializing))]
+    pub(crate) code: Cow<'a, str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub(crate) reason: Cow<'a, str>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", borrow))]
+    pub(crate) op: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", borrow))]
+    pub(crate) namespace: Option<Cow<'a, str>>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub(crate) os_code: Option<i32>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub(crate) retry_after_ms: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty", with = "fields"))]
+    pub(crate) fields: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+}
+
+impl<'a> HandleRepr<'a> {
+    /// the repr of `handle`, with sensitive fields redacted
+    pub(crate) fn new(handle: &'a CursedErrorHandle) -> Self {
+        HandleRepr {
+            error: ErrorRepr::new(handle.get_error()),
+            code: handle.code(),
+            reason: Cow::Borrowed(handle.get_reason()),
+            op: handle.op().map(Cow::Borrowed),
+            namespace: handle.namespace().map(Cow::Borrowed),
+            os_code: handle.os_code(),
+            retry_after_ms: handle.retry_after().map(|delay| u64::try_from(delay.as_millis()).unwrap_or(u64::MAX)),
+            fields: handle.inner().map(|inner| inner.fields.as_slice()).unwrap_or_default()
+                .iter()
+                .map(|(key, value)| (Cow::Borrowed(key.as_ref()), match value {
+                    Field::Plain(value) => Cow::Borrowed(value.as_str()),
+                    Field::Sensitive(_) => Cow::Borrowed("[REDACTED]"),
+                }))
+                .collect(),
+        }
+    }
+    /// the repr of a handle with the parts of `view` and no fields
+    pub(crate) fn from_view(view: &CursedErrorRef<'a>) -> Self {
+        HandleRepr {
+            error: ErrorRepr::new(&view.kind()),
+            code: view.code(),
+            reason: Cow::Borrowed(view.reason),
+            op: view.op.map(Cow::Borrowed),
+            namespace: view.namespace.map(Cow::Borrowed),
+            os_code: view.os_code,
+            retry_after_ms: None,
+            fields: Vec::new(),
+        }
+    }
+    /// the same JSON as `serde` writes, with every key sorted and of fields with the same
+    /// key only the last one
+    pub(crate) fn to_canonical_json(&self) -> String {
+        let string = |text: &str| {
+            let mut json = String::new();
+            write_json_str(&mut json, text);
+            json
+        };
+
+        let mut entries = vec![
+            ("category", string(&self.error.category)),
+            ("type", self.error.error_type.as_deref().map_or_else(|| "null".to_string(), string)),
+            ("code", string(&self.code)),
+            ("reason", string(&self.reason)),
+        ];
+        if self.error.custom {
+            entries.push(("custom", "true".to_string()));
+        }
+        if let Some(io_kind) = &self.error.io_kind {
+            entries.push(("io_kind", string(io_kind)));
+        }
+        if let Some(op) = &self.op {
+            entries.push(("op", string(op)));
+        }
+        if let Some(namespace) = &self.namespace {
+            entries.push(("namespace", string(namespace)));
+        }
+        if let Some(os_code) = self.os_code {
+            entries.push(("os_code", os_code.to_string()));
+        }
+        if let Some(delay) = self.retry_after_ms {
+            entries.push(("retry_after_ms", delay.to_string()));
+        }
+        if !self.fields.is_empty() {
+            // stable, so after reversing the last field of each key comes first
+            let mut fields: Vec<_> = self.fields.iter().rev().collect();
+            fields.sort_by(|(first, _), (second, _)| first.cmp(second));
+            fields.dedup_by(|(first, _), (second, _)| first == second);
+
+            let fields: Vec<_> = fields.into_iter().map(|(key, value)| format!("{}:{}", string(key), string(value))).collect();
+            entries.push(("fields", format!("{{{}}}", fields.join(","))));
+        }
+        entries.sort_by_key(|(key, _)| *key);
+
+        let entries: Vec<_> = entries.into_iter().map(|(key, value)| format!("{}:{}", string(key), value)).collect();
+        format!("{{{}}}", entries.join(","))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod fields {
+    use std::borrow::Cow;
+
+    type Fields<'a> = Vec<(Cow<'a, str>, Cow<'a, str>)>;
+
+    pub fn serialize<S: serde::Serializer>(fields: &[(Cow<str>, Cow<str>)], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(fields.iter().map(|(key, value)| (key, value)))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Fields<'de>, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Fields<'de>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a map of fields")
+            }
+            fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut fields = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(field) = map.next_entry()? {
+                    fields.push(field);
+                }
+                Ok(fields)
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
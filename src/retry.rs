@@ -0,0 +1,342 @@
+//! retrying fallible operations
+
+use crate::*;
+
+/// how [`retry`] repeats a failing operation
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::exponential(5, Duration::from_millis(10), Duration::from_millis(50));
+///
+/// assert_eq!(policy.delay_for(1), Duration::from_millis(10));
+/// assert_eq!(policy.delay_for(3), Duration::from_millis(40));
+/// assert_eq!(policy.delay_for(4), Duration::from_millis(50));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    delay: std::time::Duration,
+    max_delay: Option<std::time::Duration>,
+    predicate: Option<fn(&CursedErrorHandle) -> bool>,
+    sleep: Option<fn(std::time::Duration)>,
+}
+
+impl RetryPolicy {
+    /// retries up to `max_attempts` in total, sleeping `delay` between attempts
+    pub fn fixed(max_attempts: u32, delay: std::time::Duration) -> Self {
+        Self { max_attempts, delay, max_delay: None, predicate: None, sleep: None }
+    }
+    /// retries up to `max_attempts` in total, doubling the delay after each attempt up to `max_delay`
+    pub fn exponential(max_attempts: u32, delay: std::time::Duration, max_delay: std::time::Duration) -> Self {
+        Self { max_attempts, delay, max_delay: Some(max_delay), predicate: None, sleep: None }
+    }
+    /// retries only errors accepted by `predicate` instead of [`CursedError::is_retryable`]
+    /// ones and those with a [retry hint](CursedErrorHandle::retry_after)
+    pub fn retry_if(mut self, predicate: fn(&CursedErrorHandle) -> bool) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+    /// waits between attempts with `sleep` instead of [`std::thread::sleep`], e.g. a fake
+    /// clock in tests
+    pub fn with_sleep(mut self, sleep: fn(std::time::Duration)) -> Self {
+        self.sleep = Some(sleep);
+        self
+    }
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    /// delay before the attempt following the failed `attempt` (counted from 1)
+    pub fn delay_for(&self, attempt: u32) -> std::time::Duration {
+        match self.max_delay {
+            Some(max_delay) => self.delay
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+                .min(max_delay),
+            None => self.delay,
+        }
+    }
+    /// delay before the attempt following the failed `attempt`, the
+    /// [retry hint](CursedErrorHandle::retry_after) of `error` if it has one, capped at
+    /// the maximum delay of an exponential policy
+    pub fn delay_after(&self, attempt: u32, error: &CursedErrorHandle) -> std::time::Duration {
+        match (error.retry_after(), self.max_delay) {
+            (Some(hint), Some(max_delay)) => hint.min(max_delay),
+            (Some(hint), None) => hint,
+            (None, _) => self.delay_for(attempt),
+        }
+    }
+    /// whether `error` should be retried under this policy
+    pub fn should_retry(&self, error: &CursedErrorHandle) -> bool {
+        match self.predicate {
+            Some(predicate) => predicate(error),
+            None => error.get_error().is_retryable() || error.retry_after().is_some(),
+        }
+    }
+}
+
+/// runs `f` until it succeeds, fails with a non retryable error or runs out of attempts
+///
+/// Non retryable errors are returned as is, on exhaustion the last error is returned
+/// as the source of an error with the same kind noting the attempt count. An error
+/// with a [retry hint](CursedErrorHandle::retry_after) is retried after the hinted
+/// delay instead of the one of the policy, but never after more than its maximum.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::time::Duration;
+///
+/// let mut calls = 0;
+/// let result = retry(RetryPolicy::fixed(3, Duration::from_millis(1)), || {
+///     calls += 1;
+///     if calls < 3 {
+///         return Err(CursedErrorHandle::new(
+///             CursedError::Connection(CursedErrorType::Refused),
+///             "upstream is down".to_string()
+///         ))
+///     }
+///     Ok(calls)
+/// });
+///
+/// assert_eq!(result.unwrap(), 3);
+///
+/// let mut calls = 0;
+/// let error = retry(RetryPolicy::fixed(3, Duration::from_millis(1)), || -> CursedResult<()> {
+///     calls += 1;
+///     Err(CursedErrorHandle::new(
+///         CursedError::File(CursedErrorType::NotFound),
+///         "config.toml".to_string()
+///     ))
+/// }).unwrap_err();
+///
+/// assert_eq!(calls, 1);
+/// assert_eq!(error.get_reason(), "config.toml");
+///
+/// let error = retry(RetryPolicy::fixed(2, Duration::from_millis(1)), || -> CursedResult<()> {
+///     Err(CursedErrorHandle::new(
+///         CursedError::Call(CursedErrorType::Timedout),
+///         "slow".to_string()
+///     ))
+/// }).unwrap_err();
+///
+/// assert_eq!(error.get_reason(), "gave up after 2 attempts");
+/// ```
+///
+/// Honoring the hint of a throttled call, with a fake clock:
+/// ```
+/// use curerr::*;
+/// use std::cell::RefCell;
+/// use std::time::Duration;
+///
+/// thread_local! {
+///     static SLEPT: RefCell<Vec<Duration>> = const { RefCell::new(Vec::new()) };
+/// }
+///
+/// let policy = RetryPolicy::exponential(4, Duration::from_millis(100), Duration::from_secs(10))
+///     .with_sleep(|delay| SLEPT.with(|slept| slept.borrow_mut().push(delay)));
+///
+/// let mut calls = 0;
+/// let result = retry(policy, || {
+///     calls += 1;
+///     match calls {
+///         1 => Err(CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Reset), "reset".to_string())),
+///         2 => Err(CursedErrorHandle::from_http_status(429, "slow down".to_string(), Some("5")))
+///             .context("while listing buckets"),
+///         3 => Err(CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Reset), "reset".to_string())),
+///         _ => Ok("buckets"),
+///     }
+/// });
+///
+/// assert_eq!(result.unwrap(), "buckets");
+/// assert_eq!(SLEPT.with(|slept| slept.take()), [
+///     Duration::from_millis(100),
+///     Duration::from_secs(5),
+///     Duration::from_millis(400),
+/// ]);
+/// ```
+#[track_caller]
+pub fn retry<T>(policy: RetryPolicy, mut f: impl FnMut() -> CursedResult<T>) -> CursedResult<T> {
+    let mut attempt = 1;
+
+    loop {
+        let error = match f() {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        if !policy.should_retry(&error) {
+            return Err(error)
+        }
+        if attempt >= policy.max_attempts {
+            return Err(gave_up(error, attempt))
+        }
+
+        let delay = policy.delay_after(attempt, &error);
+        match policy.sleep {
+            Some(sleep) => sleep(delay),
+            None => std::thread::sleep(delay),
+        }
+        attempt += 1;
+    }
+}
+
+/// [`retry`] for async operations, sleeping between attempts with the tokio timer
+///
+/// The [sleep](RetryPolicy::with_sleep) of the policy isn't used, tests can pause the
+/// tokio clock instead. See [`retry_async_until`] to stop waiting on shutdown.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::time::Duration;
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+/// let mut calls = 0;
+/// let result = retry_async(RetryPolicy::fixed(3, Duration::from_millis(1)), || {
+///     calls += 1;
+///     let calls = calls;
+///     async move {
+///         match calls {
+///             1 => Err(CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Reset), "reset".to_string())),
+///             _ => Ok(calls),
+///         }
+///     }
+/// }).await;
+///
+/// assert_eq!(result.unwrap(), 2);
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn retry_async<T, F, Fut>(policy: RetryPolicy, f: F) -> CursedResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = CursedResult<T>>,
+{
+    retry_async_until(policy, std::future::pending(), f).await
+}
+
+/// [`retry_async`] that stops once `cancel` completes, e.g. a shutdown signal, instead
+/// of waiting out the delay before the next attempt
+///
+/// Cancellation is checked between attempts only, a running attempt is awaited. A
+/// cancelled retry fails with `Call(Aborted)` noting the attempt count, with the last
+/// error as the source.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::time::Duration;
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+/// let (shutdown, signal) = tokio::sync::oneshot::channel::<()>();
+/// let policy = RetryPolicy::fixed(10, Duration::from_secs(3600));
+///
+/// let retrying = tokio::spawn(retry_async_until(policy, async { signal.await.ok(); }, || async {
+///     Err::<(), _>(CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "down".to_string()))
+/// }));
+/// tokio::time::sleep(Duration::from_millis(10)).await;
+/// shutdown.send(()).unwrap();
+///
+/// let error = retrying.await.unwrap().unwrap_err();
+/// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Aborted));
+/// assert_eq!(error.get_reason(), "retry cancelled after 1 attempt");
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn retry_async_until<T, F, Fut>(
+    policy: RetryPolicy,
+    cancel: impl std::future::Future<Output = ()>,
+    mut f: F,
+) -> CursedResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = CursedResult<T>>,
+{
+    use std::future::Future;
+    use std::task::Poll;
+
+    let mut cancel = std::pin::pin!(cancel);
+    let mut attempt = 1;
+
+    loop {
+        let error = match f().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        if !policy.should_retry(&error) {
+            return Err(error)
+        }
+        if attempt >= policy.max_attempts {
+            return Err(gave_up(error, attempt))
+        }
+
+        let mut sleep = std::pin::pin!(tokio::time::sleep(policy.delay_after(attempt, &error)));
+        let cancelled = std::future::poll_fn(|cx| match cancel.as_mut().poll(cx) {
+            Poll::Ready(()) => Poll::Ready(true),
+            Poll::Pending => sleep.as_mut().poll(cx).map(|()| false),
+        }).await;
+
+        if cancelled {
+            return Err(CursedErrorHandle::new(
+                CursedError::Call(CursedErrorType::Aborted),
+                format!("retry cancelled after {} {}", attempt, if attempt == 1 { "attempt" } else { "attempts" })
+            ).with_source(error))
+        }
+        attempt += 1;
+    }
+}
+
+/// the error of an exhausted retry, with the same kind as the last one
+#[track_caller]
+fn gave_up(error: CursedErrorHandle, attempts: u32) -> CursedErrorHandle {
+    CursedErrorHandle::new(
+        *error.get_error(),
+        format!("gave up after {} {}", attempts, if attempts == 1 { "attempt" } else { "attempts" })
+    ).with_source(error)
+}
+
+/// runs `f` and fails with `Call(Timedout)` if it took longer than `limit`
+///
+/// The closure runs to completion on the current thread, the deadline is only checked
+/// once it returns, so this does not interrupt a hanging operation. A late result is
+/// discarded, a late error becomes the source of the timeout error.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+/// use std::time::Duration;
+///
+/// let result = with_deadline(Duration::from_secs(10), || Ok(2 + 2));
+/// assert_eq!(result.unwrap(), 4);
+///
+/// let error = with_deadline(Duration::from_millis(1), || {
+///     std::thread::sleep(Duration::from_millis(20));
+///     Ok(())
+/// }).unwrap_err();
+///
+/// assert!(matches!(error.get_error(), CursedError::Call(CursedErrorType::Timedout)));
+/// assert!(error.get_reason().starts_with("call timed out after "));
+/// assert!(error.get_reason().ends_with(" (limit 1ms)"));
+/// assert_eq!(error.timeout_limit(), Some(Duration::from_millis(1)));
+/// assert!(error.timeout_elapsed().unwrap() >= Duration::from_millis(20));
+/// ```
+#[track_caller]
+pub fn with_deadline<T>(limit: std::time::Duration, f: impl FnOnce() -> CursedResult<T>) -> CursedResult<T> {
+    let start = std::time::Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+
+    if elapsed <= limit {
+        return result
+    }
+
+    let error = CursedErrorHandle::timed_out_after("call", limit, Some(elapsed));
+
+    match result {
+        Ok(_) => Err(error),
+        Err(source) => Err(error.with_source(source)),
+    }
+}
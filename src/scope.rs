@@ -0,0 +1,99 @@
+//! contexts added to every error created inside a region, see [`scope`]
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// number of live guards of all threads, so creating errors skips the thread local
+/// while there are none
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SCOPES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// adds `context` to every [`CursedErrorHandle`](crate::CursedErrorHandle) created on this
+/// thread until the returned guard is dropped
+///
+/// Scopes nest, the errors get the contexts of all live scopes, the outermost first,
+/// as if [`push_context`](crate::CursedErrorHandle::push_context) was called on them
+/// while returning through each scope. Contexts pushed later are displayed before them.
+///
+/// While no scope is alive on any thread, creating an error doesn't touch the thread
+/// local.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// fn read_shard(shard: u32) -> CursedResult<Vec<u8>> {
+///     let _scope = scope(format!("while reading shard {}", shard));
+///     Err(CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), "shard.db is missing".to_string()))
+/// }
+///
+/// fn sync() -> CursedResult<()> {
+///     let _scope = scope("while syncing");
+///     let error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), "bad header".to_string());
+///     assert_eq!(error.contexts(), ["while syncing"]);
+///
+///     read_shard(7)?;
+///     Ok(())
+/// }
+///
+/// let error = sync().unwrap_err();
+///
+/// assert_eq!(error.contexts(), ["while syncing", "while reading shard 7"]);
+/// assert_eq!(
+///     error.to_string(),
+///     "file not found error: \"while syncing: while reading shard 7: shard.db is missing\""
+/// );
+///
+/// // the guards are gone, and other threads never saw them
+/// let error = CursedErrorHandle::new(CursedError::Unknown(None), "outside".to_string());
+/// assert!(error.contexts().is_empty());
+///
+/// let _scope = scope("while serving");
+/// let other = std::thread::spawn(|| CursedErrorHandle::new(CursedError::Unknown(None), String::new()));
+/// assert!(other.join().unwrap().contexts().is_empty());
+/// ```
+pub fn scope(context: impl Into<String>) -> ScopeGuard {
+    let depth = SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        scopes.push(context.into());
+        scopes.len() - 1
+    });
+    ACTIVE.fetch_add(1, Ordering::Relaxed);
+
+    ScopeGuard { depth, _thread: std::marker::PhantomData }
+}
+
+/// guard returned by [`scope`], removes the scope and the ones nested in it when dropped
+///
+/// The guard belongs to the thread that created it, so it is neither [`Send`] nor
+/// [`Sync`].
+#[must_use = "the scope ends when the guard is dropped"]
+pub struct ScopeGuard {
+    depth: usize,
+    _thread: std::marker::PhantomData<*const ()>,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        // ignored while the thread locals are destroyed
+        let _ = SCOPES.try_with(|scopes| scopes.borrow_mut().truncate(self.depth));
+        ACTIVE.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Debug for ScopeGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScopeGuard").field("depth", &self.depth).finish()
+    }
+}
+
+/// contexts of the live scopes of this thread, the outermost first
+pub(crate) fn contexts() -> Vec<String> {
+    if ACTIVE.load(Ordering::Relaxed) == 0 {
+        return Vec::new()
+    }
+    SCOPES.try_with(|scopes| scopes.borrow().clone()).unwrap_or_default()
+}
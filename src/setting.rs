@@ -0,0 +1,42 @@
+//! settings overridden on one thread while a closure runs, like [`with_policy`](crate::with_policy)
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::LocalKey;
+
+/// the value of a setting on threads inside [`Scoped::with`]
+pub(crate) struct Scoped<T: 'static> {
+    /// number of calls running on all threads, so reading skips the thread local while
+    /// there are none
+    active: AtomicUsize,
+    value: &'static LocalKey<Cell<Option<T>>>,
+}
+
+impl<T: Copy> Scoped<T> {
+    pub(crate) const fn new(value: &'static LocalKey<Cell<Option<T>>>) -> Self {
+        Self { active: AtomicUsize::new(0), value }
+    }
+    /// the value of the innermost call on this thread, `None` to use the global one
+    pub(crate) fn get(&self) -> Option<T> {
+        match self.active.load(Ordering::Relaxed) {
+            0 => None,
+            _ => self.value.with(Cell::get),
+        }
+    }
+    /// runs `f` with `value` on this thread, the previous value is restored when `f`
+    /// returns or panics
+    pub(crate) fn with<R>(&'static self, value: T, f: impl FnOnce() -> R) -> R {
+        struct Restore<T: Copy + 'static>(&'static Scoped<T>, Option<T>);
+
+        impl<T: Copy> Drop for Restore<T> {
+            fn drop(&mut self) {
+                self.0.value.with(|value| value.set(self.1));
+                self.0.active.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+
+        self.active.fetch_add(1, Ordering::Relaxed);
+        let _restore = Restore(self, self.value.with(|previous| previous.replace(Some(value))));
+        f()
+    }
+}
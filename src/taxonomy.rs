@@ -0,0 +1,168 @@
+//! snapshots of the codes and mappings of every error kind, compared between versions to
+//! check an upgrade doesn't change a mapping callers depend on
+
+use crate::registry::{ErrorSpec, CUSTOM_TYPES, REGISTRY};
+
+/// the codes and mappings of every error kind of this version of the crate
+///
+/// With the `serde` feature the snapshot can be stored, e.g. as JSON next to the tests of
+/// a service, and compared with [`Taxonomy::diff`] after upgrading. The crate diffs its
+/// own taxonomy against `tests/taxonomy.json`.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let taxonomy = taxonomy();
+/// let entry = taxonomy.get("CALL_TIMED_OUT").unwrap();
+///
+/// assert_eq!(entry.phrase, "call timed out");
+/// assert_eq!(entry.numeric_code, 1107);
+/// assert_eq!(entry.severity, "warning");
+/// assert!(entry.retryable);
+/// assert_eq!((entry.http_status, entry.exit_code), (504, 75));
+///
+/// assert!(taxonomy.diff(&curerr::taxonomy()).is_empty());
+/// ```
+pub fn taxonomy() -> Taxonomy {
+    Taxonomy {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        entries: REGISTRY.iter().chain(CUSTOM_TYPES).map(TaxonomyEntry::from_spec).collect(),
+    }
+}
+
+/// a snapshot of the codes and mappings of every error kind, made by [`taxonomy`]
+///
+/// The entries use plain strings and numbers instead of the kinds of the crate, so a
+/// snapshot of another version can be read even when it has kinds this one doesn't.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Taxonomy {
+    /// the version of the crate the snapshot was made with
+    pub version: String,
+    /// a row for every predefined kind followed by one for the custom types of each
+    /// category
+    pub entries: Vec<TaxonomyEntry>,
+}
+
+/// the mappings of one error kind in a [`Taxonomy`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaxonomyEntry {
+    /// [`CursedError::code`](crate::CursedError::code), which identifies the entry between
+    /// versions
+    pub code: String,
+    /// the Display output of the kind
+    pub phrase: String,
+    /// [`CursedError::numeric_code`](crate::CursedError::numeric_code)
+    pub numeric_code: u32,
+    /// the id of [`CursedError::severity`](crate::CursedError::severity), e.g. `"warning"`
+    pub severity: String,
+    /// [`CursedError::is_retryable`](crate::CursedError::is_retryable)
+    pub retryable: bool,
+    /// [`CursedError::http_status`](crate::CursedError::http_status)
+    pub http_status: u16,
+    /// [`CursedError::exit_code`](crate::CursedError::exit_code)
+    pub exit_code: u8,
+}
+
+impl TaxonomyEntry {
+    fn from_spec(spec: &ErrorSpec) -> Self {
+        TaxonomyEntry {
+            code: spec.code.to_string(),
+            phrase: spec.error.to_string(),
+            numeric_code: spec.numeric_code,
+            severity: spec.severity.to_id().to_string(),
+            retryable: spec.retryable,
+            http_status: spec.http_status,
+            exit_code: spec.exit_code,
+        }
+    }
+}
+
+/// the entries that differ between two [`Taxonomy`] snapshots, made by [`Taxonomy::diff`]
+///
+/// Display lists an entry per line, `+` for added, `-` for removed and `~` for changed
+/// ones with each mapping that changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaxonomyDiff {
+    /// entries only the newer snapshot has
+    pub added: Vec<TaxonomyEntry>,
+    /// entries only the older snapshot has
+    pub removed: Vec<TaxonomyEntry>,
+    /// entries with the same code and other mappings, the older one first
+    pub changed: Vec<(TaxonomyEntry, TaxonomyEntry)>,
+}
+
+impl Taxonomy {
+    /// the entry with [`TaxonomyEntry::code`] `code`
+    pub fn get(&self, code: &str) -> Option<&TaxonomyEntry> {
+        self.entries.iter().find(|entry| entry.code == code)
+    }
+    /// what changed from this snapshot to the newer `other`, matching entries by their code
+    ///
+    /// The versions of the snapshots aren't compared.
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let mut old = taxonomy();
+    /// old.entries.iter_mut().find(|entry| entry.code == "FILE_NOT_FOUND").unwrap().http_status = 410;
+    ///
+    /// let diff = old.diff(&taxonomy());
+    /// assert_eq!(diff.changed.len(), 1);
+    /// assert_eq!(diff.to_string(), "~ FILE_NOT_FOUND: http_status 410 -> 404\n");
+    /// ```
+    pub fn diff(&self, other: &Taxonomy) -> TaxonomyDiff {
+        let mut diff = TaxonomyDiff::default();
+        for entry in &self.entries {
+            match other.get(&entry.code) {
+                None => diff.removed.push(entry.clone()),
+                Some(new) if new != entry => diff.changed.push((entry.clone(), new.clone())),
+                Some(_) => {},
+            }
+        }
+        diff.added = other.entries.iter().filter(|entry| self.get(&entry.code).is_none()).cloned().collect();
+        diff
+    }
+}
+
+impl TaxonomyDiff {
+    /// whether the snapshots have the same entries
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl std::fmt::Display for TaxonomyDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.added {
+            writeln!(f, "+ {}", entry.code)?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "- {}", entry.code)?;
+        }
+        for (old, new) in &self.changed {
+            write!(f, "~ {}:", old.code)?;
+            let mut separator = " ";
+            let mut column = |name: &str, old: &dyn std::fmt::Display, new: &dyn std::fmt::Display| {
+                let (old, new) = (old.to_string(), new.to_string());
+                if old == new {
+                    return Ok(())
+                }
+                let result = write!(f, "{}{} {} -> {}", separator, name, old, new);
+                separator = ", ";
+                result
+            };
+            column("phrase", &old.phrase, &new.phrase)?;
+            column("numeric_code", &old.numeric_code, &new.numeric_code)?;
+            column("severity", &old.severity, &new.severity)?;
+            column("retryable", &old.retryable, &new.retryable)?;
+            column("http_status", &old.http_status, &new.http_status)?;
+            column("exit_code", &old.exit_code, &new.exit_code)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
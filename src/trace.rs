@@ -0,0 +1,120 @@
+//! the locations an error was passed through, see [`record_trace`]
+
+use crate::*;
+use crate::handle::Inner;
+use crate::setting::Scoped;
+
+use std::cell::Cell;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static RECORD_TRACE: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static SCOPED_RECORD: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// the switch of [`with_trace`]
+static SCOPED: Scoped<bool> = Scoped::new(&SCOPED_RECORD);
+
+/// the most locations kept per error
+const MAX_TRACE_LEN: usize = 8;
+
+/// records the location of every [`push_context`](CursedErrorHandle::push_context) and
+/// [`with_op`](CursedErrorHandle::with_op) call on an error, including the ones of
+/// [`ResultExt`], as a cheap propagation path where a backtrace would be too slow
+///
+/// The locations come from `#[track_caller]`, so nothing is symbolized. They are read with
+/// [`CursedErrorHandle::trace`] and shown in the report as an `at` line. An error keeps
+/// the first 8 locations, later calls aren't recorded. Disabled by default, errors
+/// created while disabled have an empty trace. Use [`with_trace`] to record on a single
+/// thread.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// fn read_config() -> CursedResult<String> {
+///     Err(CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), "app.toml".to_string()))
+/// }
+///
+/// fn load() -> CursedResult<String> {
+///     read_config().context("while loading")
+/// }
+///
+/// record_trace(true);
+/// let line = line!() + 1;
+/// let error = load().op("start").unwrap_err();
+/// record_trace(false);
+///
+/// assert_eq!(error.trace().len(), 2);
+/// assert_eq!(error.trace().last().map(|location| location.line()), Some(line));
+/// assert!(error.report().to_string().ends_with(&format!("\n  at {0}:{1} -> {0}:{2}", file!(), line - 5, line)));
+/// ```
+pub fn record_trace(record: bool) {
+    RECORD_TRACE.store(record, Ordering::Relaxed);
+}
+
+/// runs `f` recording traces on this thread if `record` is set, instead of following
+/// [`record_trace`], so tests running in parallel can each choose
+///
+/// Calls nest, the innermost one applies, and the previous choice is restored when `f`
+/// returns or panics.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let refused = || CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "down".to_string()).with_op("dial");
+///
+/// assert_eq!(with_trace(true, refused).trace().len(), 1);
+/// assert_eq!(with_trace(true, || with_trace(false, refused)).trace().len(), 0);
+/// assert_eq!(refused().trace().len(), 0);
+/// ```
+pub fn with_trace<R>(record: bool, f: impl FnOnce() -> R) -> R {
+    SCOPED.with(record, f)
+}
+
+/// whether traces are recorded on this thread
+fn recording() -> bool {
+    SCOPED.get().unwrap_or_else(|| RECORD_TRACE.load(Ordering::Relaxed))
+}
+
+/// the locations of an error, kept inline so recording them doesn't allocate
+#[derive(Clone, Copy)]
+pub(crate) struct Trace {
+    locations: [Option<&'static Location<'static>>; MAX_TRACE_LEN],
+    len: usize,
+}
+
+impl Trace {
+    pub(crate) const fn new() -> Self {
+        Self { locations: [None; MAX_TRACE_LEN], len: 0 }
+    }
+    fn iter(&self) -> impl ExactSizeIterator<Item = &'static Location<'static>> + DoubleEndedIterator + '_ {
+        self.locations[..self.len].iter().map(|location| location.expect("locations below the length are set"))
+    }
+}
+
+impl Inner {
+    /// appends the location of the caller while recording and the trace isn't full
+    #[track_caller]
+    pub(crate) fn push_trace(&mut self) {
+        if recording() && self.trace.len < MAX_TRACE_LEN {
+            self.trace.locations[self.trace.len] = Some(Location::caller());
+            self.trace.len += 1;
+        }
+    }
+}
+
+impl CursedErrorHandle {
+    /// the locations recorded while [`record_trace`] is enabled, the innermost first
+    pub fn trace(&self) -> impl ExactSizeIterator<Item = &'static Location<'static>> + DoubleEndedIterator + '_ {
+        static EMPTY: Trace = Trace::new();
+
+        match self.inner() {
+            Some(inner) => inner.trace.iter(),
+            None => EMPTY.iter(),
+        }
+    }
+}
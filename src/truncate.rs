@@ -0,0 +1,123 @@
+//! cap on the length of reasons, see [`set_max_reason_len`]
+
+use crate::setting::Scoped;
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// the cap in bytes, `0` while disabled
+static MAX_REASON_LEN: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SCOPED_CAP: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// the cap of [`with_max_reason_len`]
+static SCOPED: Scoped<usize> = Scoped::new(&SCOPED_CAP);
+
+/// cuts reasons longer than `len` bytes when errors are created, so one interpolated
+/// response body can't blow up logs and reports
+///
+/// The cut reason is at most `len` bytes including a marker like
+/// `… [truncated 41943040 bytes]` with the number of removed bytes. It ends on a char
+/// boundary, so no code point is split, and the length of the original reason is kept
+/// as the `reason_len` field. A cap shorter than the marker cuts without it.
+///
+/// The cap applies to every constructor, [`CursedErrorHandle::set_reason`], deserialized
+/// and [decoded](CursedErrorHandle::decode) errors. Reasons of
+/// [`CursedErrorHandle::new_static`] are written at compile time and kept. Disabled by
+/// default and by `0`. While disabled, reasons are passed through without being
+/// measured or copied. Use [`with_max_reason_len`] for the cap of a single thread.
+///
+/// [`CursedErrorHandle::set_reason`]: crate::CursedErrorHandle::set_reason
+/// [`CursedErrorHandle::new_static`]: crate::CursedErrorHandle::new_static
+/// [decoded]: crate::CursedErrorHandle::decode
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// set_max_reason_len(64);
+///
+/// let body = "x".repeat(40 * 1024 * 1024);
+/// let error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), format!("bad response: {}", body));
+///
+/// assert_eq!(error.get_reason().len(), 64);
+/// assert_eq!(error.get_reason(), "bad response: xxxxxxxxxxxxxxxxxxxx… [truncated 41943020 bytes]");
+/// assert_eq!(error.fields().collect::<Vec<_>>(), [("reason_len", "41943054".into())]);
+///
+/// let error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), "short".to_string());
+/// assert_eq!(error.get_reason(), "short");
+/// assert_eq!(error.fields().count(), 0);
+/// ```
+pub fn set_max_reason_len(len: usize) {
+    MAX_REASON_LEN.store(len, Ordering::Relaxed);
+}
+
+/// runs `f` with the cap `len` on this thread instead of the one of [`set_max_reason_len`],
+/// so tests running in parallel can each use their own
+///
+/// `0` disables the cap on the thread. Calls nest, the innermost cap applies, and the
+/// previous one is restored when `f` returns or panics.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let invalid = || CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), "x".repeat(100));
+///
+/// with_max_reason_len(32, || {
+///     assert_eq!(invalid().get_reason(), "xxxxxxx… [truncated 93 bytes]");
+///     assert_eq!(with_max_reason_len(0, invalid).get_reason().len(), 100);
+/// });
+/// assert_eq!(invalid().get_reason().len(), 100);
+/// ```
+pub fn with_max_reason_len<R>(len: usize, f: impl FnOnce() -> R) -> R {
+    SCOPED.with(len, f)
+}
+
+/// the cap in bytes, `usize::MAX` while disabled
+pub(crate) fn max_reason_len() -> usize {
+    match SCOPED.get().unwrap_or_else(|| MAX_REASON_LEN.load(Ordering::Relaxed)) {
+        0 => usize::MAX,
+        max => max,
+    }
+}
+
+/// the reason cut to the cap, with the original length if it was cut
+pub(crate) fn truncate(reason: Cow<'static, str>) -> (Cow<'static, str>, Option<usize>) {
+    match cut(&reason, max_reason_len()) {
+        Some(truncated) => (Cow::Owned(truncated), Some(reason.len())),
+        None => (reason, None),
+    }
+}
+
+/// `reason` cut to at most `max` bytes including the marker, `None` if it fits
+pub(crate) fn cut(reason: &str, max: usize) -> Option<String> {
+    if reason.len() <= max {
+        return None
+    }
+
+    let len = reason.len();
+    let mut marker = format!("… [truncated {} bytes]", len);
+    // the count of removed bytes can be a digit shorter than the length
+    let mut end = max.saturating_sub(marker.len());
+    while !reason.is_char_boundary(end) {
+        end -= 1;
+    }
+    marker = format!("… [truncated {} bytes]", len - end);
+    if marker.len() > max {
+        end = max;
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+        marker.clear();
+    }
+
+    // a new string, so the memory of the long one is freed
+    let mut truncated = String::with_capacity(end + marker.len());
+    truncated.push_str(&reason[..end]);
+    truncated.push_str(&marker);
+    Some(truncated)
+}
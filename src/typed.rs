@@ -0,0 +1,178 @@
+//! narrow error types for functions that can only fail one way, each holding a handle of
+//! a single error type
+
+use crate::*;
+
+macro_rules! typed_error {
+    ($(#[$meta:meta])* $name:ident, $error_type:ident) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        pub struct $name(CursedErrorHandle);
+
+        impl $name {
+            #[doc = concat!("creates an error of `category` with the [`CursedErrorType::", stringify!($error_type), "`] type")]
+            ///
+            /// [`CursedCategory::NoError`] and [`CursedCategory::Unknown`] have no types,
+            /// they are replaced by [`CursedCategory::Other`].
+            #[track_caller]
+            pub fn new(category: CursedCategory, reason: impl Into<String>) -> Self {
+                let category = match category {
+                    CursedCategory::NoError | CursedCategory::Unknown => CursedCategory::Other,
+                    category => category,
+                };
+                $name(CursedErrorHandle::new(category.with_type(CursedErrorType::$error_type), reason.into()))
+            }
+            /// attaches a key-value field, see [`CursedErrorHandle::with_field`]
+            pub fn with_field(self, key: impl Into<std::borrow::Cow<'static, str>>, value: impl std::fmt::Display) -> Self {
+                $name(self.0.with_field(key, value))
+            }
+            /// tags the error with the name of the failed operation, see [`CursedErrorHandle::with_op`]
+            #[track_caller]
+            pub fn with_op(self, op: &'static str) -> Self {
+                $name(self.0.with_op(op))
+            }
+            /// the handle, the same as converting it with `into`
+            pub fn into_handle(self) -> CursedErrorHandle {
+                self.0
+            }
+        }
+
+        #[doc = concat!("accepts handles of any category with the [`CursedErrorType::", stringify!($error_type), "`] type")]
+        /// and returns the others unchanged
+        impl TryFrom<CursedErrorHandle> for $name {
+            type Error = CursedErrorHandle;
+
+            fn try_from(error: CursedErrorHandle) -> Result<Self, CursedErrorHandle> {
+                match error.get_error().get_type() {
+                    Some(CursedErrorType::$error_type) => Ok($name(error)),
+                    _ => Err(error),
+                }
+            }
+        }
+
+        impl From<$name> for CursedErrorHandle {
+            fn from(error: $name) -> Self {
+                error.0
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = CursedErrorHandle;
+
+            fn deref(&self) -> &CursedErrorHandle {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl std::error::Error for $name {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                std::error::Error::source(&self.0)
+            }
+        }
+    };
+}
+
+typed_error! {
+    /// an error that can only be a timeout, a [`CursedErrorHandle`] with the
+    /// [`CursedErrorType::Timedout`] type of any category
+    ///
+    /// The handle is reachable through `Deref` and converts back with `?` or `into`, so
+    /// callers can widen the error into the shared [`CursedResult`].
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// fn wait_for_lock() -> Result<(), TimeoutError> {
+    ///     Err(TimeoutError::new(CursedCategory::Call, "lock held for 30s").with_field("lock", "jobs"))
+    /// }
+    ///
+    /// fn run_job() -> CursedResult<()> {
+    ///     wait_for_lock()?;
+    ///     Ok(())
+    /// }
+    ///
+    /// let error = wait_for_lock().unwrap_err();
+    /// assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Timedout));
+    /// assert!(error.get_error().is_retryable());
+    ///
+    /// let error = run_job().unwrap_err();
+    /// assert_eq!(error.get_reason(), "lock held for 30s");
+    /// assert_eq!(error.fields().collect::<Vec<_>>(), [("lock", "jobs".into())]);
+    ///
+    /// let refused = CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "upstream is down".to_string());
+    /// assert_eq!(TimeoutError::try_from(refused).unwrap_err().get_reason(), "upstream is down");
+    /// ```
+    TimeoutError, Timedout
+}
+
+typed_error! {
+    /// an error that can only be a missing entity, a [`CursedErrorHandle`] with the
+    /// [`CursedErrorType::NotFound`] type of any category
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// fn find_user(id: u64) -> Result<&'static str, NotFoundError> {
+    ///     match id {
+    ///         1 => Ok("ferris"),
+    ///         id => Err(NotFoundError::new(CursedCategory::Database, format!("no user {}", id))),
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(find_user(7).unwrap_err().to_string(), "database not found error: \"no user 7\"");
+    /// assert_eq!(find_user(7).unwrap_err().get_error().http_status(), 404);
+    /// ```
+    NotFoundError, NotFound
+}
+
+typed_error! {
+    /// an error that can only be a denied access, a [`CursedErrorHandle`] with the
+    /// [`CursedErrorType::AccessDenied`] type of any category
+    ///
+    /// # Examples
+    /// ```
+    /// use curerr::*;
+    ///
+    /// let error = PermissionError::new(CursedCategory::File, "/etc/shadow").with_op("read_secrets");
+    ///
+    /// assert_eq!(error.to_string(), "read_secrets: file access denied error: \"/etc/shadow\"");
+    /// assert_eq!(error.exit_code(), 77);
+    /// ```
+    PermissionError, AccessDenied
+}
+
+/// the handle of `error` if it holds one of the typed errors, taken out of the `Option`
+pub(crate) fn take_typed(error: &mut dyn std::any::Any) -> Option<CursedErrorHandle> {
+    if let Some(error) = error.downcast_mut::<Option<TimeoutError>>() {
+        return error.take().map(Into::into)
+    }
+    if let Some(error) = error.downcast_mut::<Option<NotFoundError>>() {
+        return error.take().map(Into::into)
+    }
+    error.downcast_mut::<Option<PermissionError>>()?.take().map(Into::into)
+}
+
+/// the handle of `error` if it is one of the typed errors
+pub(crate) fn typed_handle<'a>(error: &'a (dyn std::error::Error + 'static)) -> Option<&'a CursedErrorHandle> {
+    if let Some(error) = error.downcast_ref::<TimeoutError>() {
+        return Some(&error.0)
+    }
+    if let Some(error) = error.downcast_ref::<NotFoundError>() {
+        return Some(&error.0)
+    }
+    error.downcast_ref::<PermissionError>().map(|error| &error.0)
+}
@@ -0,0 +1,181 @@
+//! flat borrowed view of an error, for callbacks and FFI that only inspect it
+
+use crate::*;
+use crate::handle::{fingerprint, write_escaped, Field, Inner};
+use crate::repr::HandleRepr;
+
+/// the classification and reason of an error borrowed from its owner, created by
+/// [`CursedErrorHandle::as_ref_view`] without allocating
+///
+/// Code that only inspects errors, like [`ErrorStats`], the reporters or the
+/// [FFI](crate::set_last_error) functions, can take a view and so also accept errors of
+/// other types that build one. The fields other than the `tags` of an [`ErrorFactory`] and
+/// the sources are not part of it. A view becomes a handle with `CursedErrorHandle::from`.
+///
+/// # Examples
+/// ```
+/// use curerr::*;
+///
+/// let mut error = CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "db is down".to_string())
+///     .with_op("connect")
+///     .with_namespace("storage");
+/// error.push_context("while starting");
+/// let view = error.as_ref_view();
+///
+/// assert_eq!(view.category, CursedCategory::Connection);
+/// assert_eq!(view.error_type, Some(CursedErrorType::Refused));
+/// assert_eq!(view.reason, "db is down");
+/// assert_eq!(view.code(), "CONNECTION_REFUSED");
+/// assert_eq!(view.severity, CursedSeverity::Warning);
+/// assert_eq!((view.op, view.namespace, view.os_code), (Some("connect"), Some("storage"), None));
+/// assert_eq!(view.contexts, ["while starting"]);
+/// assert_eq!(view.to_string(), error.to_string());
+/// assert_eq!(view, error);
+///
+/// // an error that isn't a handle
+/// let view = CursedErrorRef {
+///     category: CursedCategory::Custom("billing"),
+///     error_type: Some(CursedErrorType::Invalid),
+///     io_kind: None,
+///     reason: "card expired",
+///     severity: CursedSeverity::Error,
+///     os_code: None,
+///     op: Some("charge"),
+///     namespace: None,
+///     contexts: &[],
+///     tags: None,
+/// };
+/// let error = CursedErrorHandle::from(view);
+///
+/// assert_eq!(error.get_error(), &CursedError::Custom { category: "billing", kind: CursedErrorType::Invalid });
+/// assert_eq!(view.code(), "BILLING_INVALID");
+/// assert_eq!(error, view);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CursedErrorRef<'a> {
+    pub category: CursedCategory,
+    /// `None` for [`CursedCategory::NoError`] and [`CursedCategory::Unknown`]
+    pub error_type: Option<CursedErrorType>,
+    /// the converted [`std::io::ErrorKind`] of a [`CursedError::Unknown`] error
+    pub io_kind: Option<std::io::ErrorKind>,
+    pub reason: &'a str,
+    pub severity: CursedSeverity,
+    pub os_code: Option<i32>,
+    pub op: Option<&'a str>,
+    pub namespace: Option<&'a str>,
+    /// the outermost first, like [`CursedErrorHandle::contexts`]
+    pub contexts: &'a [String],
+    /// the `tags` field, like the one set by [`ErrorFactory::tag`], `None` when it is sensitive
+    pub tags: Option<&'a str>,
+}
+
+impl CursedErrorRef<'_> {
+    /// the kind of the error, a category that needs a type without one is `Unknown`
+    pub fn kind(&self) -> CursedError {
+        match (self.category, self.error_type) {
+            (CursedCategory::NoError, _) => CursedError::NoError,
+            (category, Some(error_type)) if category != CursedCategory::Unknown => category.with_type(error_type),
+            _ => CursedError::Unknown(self.io_kind),
+        }
+    }
+    /// the [`CursedError::code`] of the kind, only built for custom categories and types
+    pub fn code(&self) -> std::borrow::Cow<'static, str> {
+        self.kind().code()
+    }
+    /// the [`CursedErrorHandle::fingerprint`] of a handle with the parts of the view,
+    /// the same as the one of the owner when it has no fields
+    pub fn fingerprint(&self) -> u64 {
+        fingerprint(HandleRepr::from_view(self))
+    }
+    /// increments the `curerr_errors_total` counter, see [`CursedErrorHandle::record_metric`]
+    #[cfg(feature = "metrics")]
+    pub fn record_metric(&self) {
+        let error_type = self.error_type.map_or("none", |err| err.to_id());
+
+        metrics::counter!(
+            "curerr_errors_total",
+            "category" => self.category.to_id(),
+            "type" => error_type
+        ).increment(1);
+    }
+}
+
+/// the view as a handle shows it in the version 1 format, with the op, kind, contexts and
+/// reason
+impl std::fmt::Display for CursedErrorRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(op) = self.op {
+            write!(f, "{}: ", op)?;
+        }
+        write!(f, "{} error: \"", self.kind())?;
+        for context in self.contexts {
+            write_escaped(f, context)?;
+            f.write_str(": ")?;
+        }
+        write_escaped(f, self.reason)?;
+        f.write_str("\"")
+    }
+}
+
+impl CursedErrorHandle {
+    /// borrows the error as a [`CursedErrorRef`]
+    pub fn as_ref_view(&self) -> CursedErrorRef<'_> {
+        let error = self.get_error();
+        let tags = self.inner().and_then(|inner| inner.fields.iter().rev().find(|(key, _)| key == "tags"));
+
+        CursedErrorRef {
+            category: error.category(),
+            error_type: error.get_type(),
+            io_kind: match error {
+                CursedError::Unknown(kind) => *kind,
+                _ => None,
+            },
+            reason: self.get_reason(),
+            severity: error.severity(),
+            os_code: self.os_code(),
+            op: self.op(),
+            namespace: self.namespace(),
+            contexts: self.contexts(),
+            tags: match tags {
+                Some((_, Field::Plain(tags))) => Some(tags),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a CursedErrorHandle> for CursedErrorRef<'a> {
+    fn from(error: &'a CursedErrorHandle) -> Self {
+        error.as_ref_view()
+    }
+}
+
+/// creates a handle with every part of the view, the tags as its `tags` field
+///
+/// The op and namespace are kept as `'static` names like the ones of deserialized handles,
+/// or as `op` and `namespace` fields once too many names are kept.
+impl From<CursedErrorRef<'_>> for CursedErrorHandle {
+    #[track_caller]
+    fn from(view: CursedErrorRef<'_>) -> Self {
+        let mut inner = Inner::new(view.kind(), view.reason.to_string());
+        inner.os_code = view.os_code;
+        inner.set_read_names(view.op, view.namespace);
+        inner.contexts = view.contexts.to_vec();
+        if let Some(tags) = view.tags {
+            inner.fields.push(("tags".into(), Field::Plain(tags.to_string())));
+        }
+        CursedErrorHandle::from_inner(inner)
+    }
+}
+/// equal when every field of the view is the same in the view of the handle
+impl PartialEq<CursedErrorHandle> for CursedErrorRef<'_> {
+    fn eq(&self, other: &CursedErrorHandle) -> bool {
+        *self == other.as_ref_view()
+    }
+}
+
+impl PartialEq<CursedErrorRef<'_>> for CursedErrorHandle {
+    fn eq(&self, other: &CursedErrorRef<'_>) -> bool {
+        self.as_ref_view() == *other
+    }
+}
@@ -0,0 +1,44 @@
+//! helpers shared by the integration tests, each binary uses some of them
+
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+static TURN: Mutex<()> = Mutex::new(());
+
+/// makes the tests of a binary that change a process wide setting without a scoped form,
+/// like the error hook, take turns until the guard is dropped
+pub fn take_turn() -> MutexGuard<'static, ()> {
+    TURN.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// compares `output` with the committed snapshot at `file` in the crate, on a mismatch
+/// `diff` is given the committed text and describes the change
+///
+/// With the environment variable `update` set a changed snapshot is rewritten and the
+/// test fails anyway, so a run updating snapshots can't pass by accident.
+pub fn check_snapshot(file: &str, update: &str, output: &str, diff: impl FnOnce(&str) -> String) {
+    let snapshot = Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+    let expected = std::fs::read_to_string(&snapshot).unwrap_or_default();
+    if output == expected {
+        return
+    }
+
+    if std::env::var_os(update).is_some() {
+        std::fs::write(&snapshot, output).unwrap();
+        panic!("rewrote {}, rerun without {} to check it", file, update);
+    }
+    panic!("{} changed, rerun with {}=1 if intended:\n{}", file, update, diff(&expected));
+}
+
+/// the first 20 lines that differ between `expected` and `output`
+pub fn changed_lines(expected: &str, output: &str) -> String {
+    let (lines, expected): (Vec<_>, Vec<_>) = (output.lines().collect(), expected.lines().collect());
+    let changed: Vec<_> = (0..lines.len().max(expected.len()))
+        .filter(|&index| lines.get(index) != expected.get(index))
+        .map(|index| format!("- {}\n+ {}", expected.get(index).unwrap_or(&""), lines.get(index).unwrap_or(&"")))
+        .take(20)
+        .collect();
+    changed.join("\n")
+}
@@ -0,0 +1,282 @@
+//! the conversions and the classification checked against the public table, with the
+//! rows of every enabled integration
+
+#![cfg(feature = "std-conversions")]
+
+use curerr::*;
+use std::error::Error;
+
+type Sample = (CursedErrorHandle, CursedErrorHandle);
+
+/// an error converted by its `From` impl and the same error classified by `wrap_any`
+fn converted<E: Error + Send + Sync + 'static>(make: impl Fn() -> E) -> Sample
+where
+    CursedErrorHandle: From<E>,
+{
+    (make().into(), wrap_any(make()))
+}
+
+/// an error without a `From` impl, classified by `wrap_any` both times
+fn wrapped<E: Error + Send + Sync + 'static>(make: impl Fn() -> E) -> Sample {
+    (wrap_any(make()), wrap_any(make()))
+}
+
+/// an error only the `From` impl knows, converted both times
+#[allow(dead_code)]
+fn from_only<E>(make: impl Fn() -> E) -> Sample
+where
+    CursedErrorHandle: From<E>,
+{
+    (make().into(), make().into())
+}
+
+fn sample(name: &str) -> Option<Sample> {
+    if let Some(kind) = name.strip_prefix("std::io::Error(").and_then(|kind| kind.strip_suffix(')')) {
+        let (kind, _) = error_kind_mappings().iter().find(|(mapped, _)| format!("{:?}", mapped) == kind)?;
+        return Some(converted(|| std::io::Error::from(*kind)))
+    }
+
+    Some(match name {
+        "std::num::ParseIntError(InvalidDigit)" => converted(|| "8o".parse::<u16>().unwrap_err()),
+        "std::num::ParseIntError(PosOverflow)" => converted(|| "70000".parse::<u16>().unwrap_err()),
+        "std::num::ParseIntError(NegOverflow)" => converted(|| "-129".parse::<i8>().unwrap_err()),
+        "std::num::ParseIntError(Empty)" => converted(|| "".parse::<u16>().unwrap_err()),
+        "std::num::ParseIntError(Zero)" => converted(|| "0".parse::<std::num::NonZeroU8>().unwrap_err()),
+        "std::num::ParseFloatError" => converted(|| "x".parse::<f64>().unwrap_err()),
+        "std::num::TryFromIntError" => wrapped(|| u8::try_from(300_i32).unwrap_err()),
+        "std::str::ParseBoolError" => converted(|| "yes".parse::<bool>().unwrap_err()),
+        "std::char::ParseCharError" => converted(|| "xy".parse::<char>().unwrap_err()),
+        "std::char::TryFromCharError" => converted(|| u8::try_from('\u{1F980}').unwrap_err()),
+        "std::str::Utf8Error" => wrapped(|| String::from_utf8(vec![0xff]).unwrap_err().utf8_error()),
+        "std::string::FromUtf8Error" => wrapped(|| String::from_utf8(vec![0xff]).unwrap_err()),
+        "std::string::FromUtf16Error" => wrapped(|| String::from_utf16(&[0xd800]).unwrap_err()),
+        "std::net::AddrParseError" => wrapped(|| "localhost".parse::<std::net::IpAddr>().unwrap_err()),
+        "std::alloc::LayoutError" => converted(|| std::alloc::Layout::array::<u64>(usize::MAX).unwrap_err()),
+        "std::collections::TryReserveError" => wrapped(|| Vec::<u8>::new().try_reserve(usize::MAX).unwrap_err()),
+        "std::time::SystemTimeError" => {
+            wrapped(|| std::time::UNIX_EPOCH.duration_since(std::time::SystemTime::now()).unwrap_err())
+        },
+        "std::env::VarError(NotPresent)" => wrapped(|| std::env::VarError::NotPresent),
+        "std::env::VarError(NotUnicode)" => wrapped(|| std::env::VarError::NotUnicode("\u{FFFD}".into())),
+        name => return integration_sample(name),
+    })
+}
+
+fn integration_sample(name: &str) -> Option<Sample> {
+    #[cfg(feature = "base64")]
+    {
+        use base64::Engine;
+
+        let decode = |input: &'static str| move || base64::engine::general_purpose::STANDARD.decode(input).unwrap_err();
+        match name {
+            "base64::DecodeError(InvalidByte)" => return Some(from_only(decode("a!=="))),
+            "base64::DecodeError(InvalidLength)" => return Some(from_only(decode("abcde"))),
+            "base64::DecodeError(InvalidPadding)" => return Some(from_only(decode("ab="))),
+            _ => (),
+        }
+    }
+    #[cfg(feature = "chrono")]
+    {
+        let parse = |input: &'static str| move || chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d").unwrap_err();
+        match name {
+            "chrono::ParseError(OutOfRange)" => return Some(from_only(parse("2026-02-30"))),
+            "chrono::ParseError(Invalid)" => return Some(from_only(parse("2026-xx-01"))),
+            _ => (),
+        }
+    }
+    #[cfg(feature = "hex")]
+    match name {
+        "hex::FromHexError(InvalidHexCharacter)" => return Some(from_only(|| hex::decode("zz").unwrap_err())),
+        "hex::FromHexError(OddLength)" => return Some(from_only(|| hex::decode("abc").unwrap_err())),
+        "hex::FromHexError(InvalidStringLength)" => {
+            return Some(from_only(|| hex::decode_to_slice("abcd", &mut [0; 1]).unwrap_err()))
+        },
+        _ => (),
+    }
+    #[cfg(feature = "regex")]
+    match name {
+        "regex::Error(Syntax)" => {
+            let pattern = String::from("(unclosed");
+            return Some(from_only(|| regex::Regex::new(&pattern).unwrap_err()))
+        },
+        "regex::Error(CompiledTooBig)" => {
+            return Some(from_only(|| regex::RegexBuilder::new(r"\w{100}").size_limit(16).build().unwrap_err()))
+        },
+        _ => (),
+    }
+    #[cfg(feature = "rustls")]
+    {
+        use rustls::{AlertDescription, CertificateError, Error};
+
+        let make: Option<fn() -> Error> = match name {
+            "rustls::Error(InvalidCertificate(Revoked))" => {
+                Some(|| Error::InvalidCertificate(CertificateError::Revoked))
+            },
+            "rustls::Error(InvalidCertificate(Expired))" => {
+                Some(|| Error::InvalidCertificate(CertificateError::Expired))
+            },
+            "rustls::Error(AlertReceived(HandshakeFailure))" => {
+                Some(|| Error::AlertReceived(AlertDescription::HandshakeFailure))
+            },
+            "rustls::Error(DecryptError)" => Some(|| Error::DecryptError),
+            "rustls::Error(HandshakeNotComplete)" => Some(|| Error::HandshakeNotComplete),
+            _ => None,
+        };
+        if let Some(make) = make {
+            return Some(converted(make))
+        }
+    }
+    #[cfg(feature = "time")]
+    {
+        use time::macros::format_description;
+
+        let parse = |input: &'static str| {
+            move || time::Date::parse(input, format_description!("[year]-[month]-[day]")).unwrap_err()
+        };
+        match name {
+            "time::error::Parse(ComponentRange)" => return Some(from_only(parse("2026-02-30"))),
+            "time::error::Parse(ParseFromDescription)" => return Some(from_only(parse("2026-13-01"))),
+            "time::error::ComponentRange" => {
+                return Some(from_only(|| time::Date::from_calendar_date(2026, time::Month::February, 30).unwrap_err()))
+            },
+            "time::error::Format" => {
+                return Some(from_only(|| time::Time::MIDNIGHT.format(format_description!("[year]")).unwrap_err()))
+            },
+            _ => (),
+        }
+    }
+    #[cfg(feature = "tokio")]
+    {
+        let runtime = || tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let join_error = |panics: bool| move || runtime().block_on(async {
+            let task = tokio::spawn(async move {
+                if panics {
+                    panic!("boom")
+                }
+                std::future::pending::<()>().await
+            });
+            if !panics {
+                task.abort();
+            }
+            task.await.unwrap_err()
+        });
+        match name {
+            "tokio::task::JoinError(Cancelled)" => return Some(from_only(join_error(false))),
+            "tokio::task::JoinError(Panic)" => return Some(from_only(join_error(true))),
+            "tokio::time::error::Elapsed" => return Some(from_only(|| runtime().block_on(async {
+                tokio::time::timeout(std::time::Duration::ZERO, std::future::pending::<()>()).await.unwrap_err()
+            }))),
+            "tokio::sync::AcquireError" => return Some(from_only(|| {
+                let semaphore = tokio::sync::Semaphore::new(0);
+                semaphore.close();
+                semaphore.try_acquire().map(drop).unwrap_err();
+                runtime().block_on(semaphore.acquire()).map(drop).unwrap_err()
+            })),
+            _ => (),
+        }
+    }
+    #[cfg(feature = "tonic")]
+    if let Some(code) = name.strip_prefix("tonic::Status(").and_then(|code| code.strip_suffix(')')) {
+        let code = (0..=16).map(tonic::Code::from).find(|mapped| format!("{:?}", mapped) == code)?;
+        let status = tonic::Status::new(code, "sample");
+        return Some((CursedErrorHandle::from_status(&status), CursedErrorHandle::from_status(&status)))
+    }
+    #[cfg(feature = "toml")]
+    match name {
+        "toml::de::Error(syntax)" => return Some(from_only(|| toml::from_str::<toml::Table>("a = ").unwrap_err())),
+        "toml::de::Error(invalid type)" => {
+            return Some(from_only(|| toml::from_str::<std::collections::HashMap<String, u8>>("a = 'x'").unwrap_err()))
+        },
+        "toml::ser::Error" => return Some(from_only(|| toml::to_string(&1_u8).unwrap_err())),
+        _ => (),
+    }
+    #[cfg(feature = "url")]
+    {
+        let parse = |input: &'static str| move || url::Url::parse(input).unwrap_err();
+        match name {
+            "url::ParseError(InvalidPort)" => return Some(from_only(parse("https://example.com:99999/"))),
+            "url::ParseError(RelativeUrlWithoutBase)" => return Some(from_only(parse("/relative"))),
+            "url::ParseError(InvalidDomainCharacter)" => return Some(from_only(parse("http://exa mple.com/"))),
+            _ => (),
+        }
+    }
+    #[cfg(feature = "walkdir")]
+    match name {
+        "walkdir::Error(NotFound)" => {
+            let missing = || walkdir::WalkDir::new("/definitely/not/here").into_iter().next().unwrap().unwrap_err();
+            return Some(from_only(missing))
+        },
+        #[cfg(unix)]
+        "walkdir::Error(loop)" => return Some(from_only(symlink_loop)),
+        _ => (),
+    }
+    #[cfg(feature = "yaml")]
+    match name {
+        "serde_yaml::Error(syntax)" => {
+            return Some(from_only(|| serde_yaml::from_str::<serde_yaml::Value>("a: [").unwrap_err()))
+        },
+        "serde_yaml::Error(invalid type)" => {
+            let invalid = || serde_yaml::from_str::<std::collections::HashMap<String, u8>>("a: x").unwrap_err();
+            return Some(from_only(invalid))
+        },
+        _ => (),
+    }
+
+    let _ = name;
+    None
+}
+
+/// the error of walking a directory holding a link to itself
+#[cfg(all(feature = "walkdir", unix))]
+fn symlink_loop() -> walkdir::Error {
+    let dir = std::env::temp_dir().join(format!("curerr-compat-loop-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::os::unix::fs::symlink(&dir, dir.join("self")).unwrap();
+
+    let error = walkdir::WalkDir::new(&dir).follow_links(true).into_iter().find_map(Result::err).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    error
+}
+
+/// rows checked in their own tests, like the handshakes of tests/tls.rs
+fn checked_elsewhere(name: &str) -> bool {
+    name.starts_with("native_tls::") || (cfg!(not(unix)) && name == "walkdir::Error(loop)")
+}
+
+#[test]
+fn conversions_match_the_table() {
+    let skipped = compat::assert_all(|name| Some(sample(name)?.0));
+    assert!(skipped.iter().all(|name| checked_elsewhere(name)), "no sample for {:?}", skipped);
+}
+
+#[test]
+fn classification_matches_the_table() {
+    let skipped = compat::assert_all(|name| Some(sample(name)?.1));
+    assert!(skipped.iter().all(|name| checked_elsewhere(name)), "no sample for {:?}", skipped);
+}
+
+#[test]
+fn io_mappings_are_in_the_table() {
+    for (kind, error) in error_kind_mappings() {
+        assert_eq!(compat::expected(&format!("std::io::Error({:?})", kind)), Some(*error));
+    }
+}
+
+#[test]
+fn integrations_are_listed_with_their_features() {
+    let listed = |prefix: &str| compat::classifications().iter().any(|(name, _, _)| name.starts_with(prefix));
+
+    assert!(listed("std::num::"));
+    assert_eq!(listed("url::"), cfg!(feature = "url"));
+    assert_eq!(listed("tonic::"), cfg!(feature = "tonic"));
+    assert_eq!(listed("rustls::"), cfg!(feature = "rustls"));
+}
+
+#[test]
+#[should_panic(expected = "std::num::ParseIntError(Empty) is classified as data parse, the table expects input not enough")]
+fn mismatches_are_reported() {
+    compat::assert_classification("std::num::ParseIntError(Empty)", || {
+        CursedErrorHandle::new(CursedError::Data(CursedErrorType::Parse), String::new())
+    });
+}
@@ -0,0 +1,169 @@
+//! every string and binary form checked against the normalized error, with what each
+//! form loses on purpose
+
+#![cfg(feature = "proptest")]
+
+use curerr::*;
+use proptest::prelude::*;
+
+/// a form of the error and what is left of the normalized error after reading it back
+struct Form {
+    name: &'static str,
+    /// writes the error in the form and reads it back
+    round_trip: fn(&CursedErrorHandle) -> NormalizedError,
+    /// the part of the normalized error the form keeps
+    keeps: fn(NormalizedError) -> NormalizedError,
+}
+
+/// the table of the docs of [`NormalizedError`]
+fn forms() -> Vec<Form> {
+    let mut forms = Vec::new();
+
+    #[cfg(feature = "serde")]
+    forms.extend([
+        Form {
+            name: "serde",
+            round_trip: |error| {
+                let json = serde_json::to_string(error).unwrap();
+                serde_json::from_str::<CursedErrorHandle>(&json).unwrap().normalize()
+            },
+            keeps: |normalized| normalized,
+        },
+        Form {
+            name: "canonical_json",
+            round_trip: |error| serde_json::from_str::<CursedErrorHandle>(&error.canonical_json()).unwrap().normalize(),
+            keeps: |mut normalized| {
+                normalized.fields.reverse();
+                normalized.fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+                normalized.fields.dedup_by(|(a, _), (b, _)| a == b);
+                normalized
+            },
+        },
+    ]);
+
+    forms.extend([
+        Form {
+            name: "encode",
+            round_trip: |error| {
+                let mut bytes = Vec::new();
+                error.encode(&mut bytes);
+                CursedErrorHandle::decode(&bytes).unwrap().normalize()
+            },
+            keeps: |normalized| NormalizedError { fields: Vec::new(), ..normalized },
+        },
+        Form {
+            name: "Display",
+            round_trip: |error| parse_display(&error.to_string()).normalize(),
+            keeps: |normalized| {
+                let custom_type = matches!(normalized.error_type, Some(CursedErrorType::Custom(_)));
+                let normalized = NormalizedError { fields: Vec::new(), ..normalized };
+                match normalized.category {
+                    CursedCategory::Other | CursedCategory::Custom(_) if custom_type => unknown(normalized),
+                    _ => normalized,
+                }
+            },
+        },
+        Form {
+            name: "code",
+            round_trip: |error| kind_only(CursedError::from_code(&error.code())),
+            keeps: |normalized| {
+                let normalized = NormalizedError { reason: String::new(), fields: Vec::new(), ..normalized };
+                match normalized.category {
+                    CursedCategory::Custom(_) => unknown(normalized),
+                    _ => normalized,
+                }
+            },
+        },
+        Form {
+            name: "numeric_code",
+            round_trip: |error| {
+                kind_only(CursedError::from_numeric_code(error.get_error().numeric_code()).unwrap_or(CursedError::Unknown(None)))
+            },
+            keeps: |normalized| {
+                let custom_type = matches!(normalized.error_type, Some(CursedErrorType::Custom(_)));
+                let normalized = NormalizedError { reason: String::new(), fields: Vec::new(), ..normalized };
+                match normalized.category {
+                    CursedCategory::Custom(_) => unknown(normalized),
+                    _ if custom_type => unknown(normalized),
+                    _ => normalized,
+                }
+            },
+        },
+    ]);
+    forms
+}
+
+/// the normalized error with the kind `Unknown(None)`
+fn unknown(normalized: NormalizedError) -> NormalizedError {
+    NormalizedError { reason: normalized.reason, fields: normalized.fields, ..kind_only(CursedError::Unknown(None)) }
+}
+
+fn kind_only(error: CursedError) -> NormalizedError {
+    CursedErrorHandle::new(error, String::new()).normalize()
+}
+
+/// reads back `kind error: "reason"` or `kind error` without a reason, a kind that
+/// doesn't parse is `Unknown(None)`
+fn parse_display(display: &str) -> CursedErrorHandle {
+    let (kind, reason) = match display.split_once(" error: \"") {
+        Some((kind, reason)) => (kind, reason.strip_suffix('"').unwrap()),
+        None => (display.strip_suffix(" error").unwrap(), ""),
+    };
+
+    let mut unescaped = String::with_capacity(reason.len());
+    let mut chars = reason.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next().unwrap() {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                c => c,
+            },
+            c => c,
+        });
+    }
+
+    CursedErrorHandle::new(kind.parse().unwrap_or(CursedError::Unknown(None)), unescaped)
+}
+
+fn reasons() -> impl Strategy<Value = String> {
+    prop_oneof![
+        4 => "\\PC{0,64}",
+        1 => Just(String::new()),
+        1 => Just("the \"quoted\" \\ path\nsecond line\r\n\tindented".to_string()),
+        1 => Just("größe überschritten, 日本語, 🚀".to_string()),
+        1 => Just("ä\"\n".repeat(64 * 1024 / 4)),
+    ]
+}
+
+fn handles() -> impl Strategy<Value = CursedErrorHandle> {
+    let fields = prop::collection::vec(("[a-z_]{1,8}", "\\PC{0,16}", any::<bool>()), 0..4);
+
+    (any::<CursedError>(), reasons(), fields).prop_map(|(error, reason, fields)| {
+        fields.into_iter().fold(CursedErrorHandle::new(error, reason), |error, (key, value, sensitive)| match sensitive {
+            true => error.with_field_sensitive(key, value),
+            false => error.with_field(key, value),
+        })
+    })
+}
+
+proptest! {
+    #[test]
+    fn every_form_keeps_what_it_documents(error in handles()) {
+        for form in forms() {
+            prop_assert_eq!((form.round_trip)(&error), (form.keeps)(error.normalize()), "form {}", form.name);
+        }
+    }
+}
+
+#[test]
+fn long_reason_keeps_every_byte() {
+    let reason = "ä\"\n".repeat(64 * 1024 / 4);
+    let error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), reason.clone())
+        .with_field("size", reason.len());
+
+    for form in forms().iter().filter(|form| !form.name.contains("code")) {
+        assert_eq!((form.round_trip)(&error).reason, reason, "form {}", form.name);
+    }
+}
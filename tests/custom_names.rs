@@ -0,0 +1,71 @@
+//! custom names read from input once the process keeps as many as it will, which is
+//! global, so everything is checked in a single test
+
+use curerr::*;
+
+fn fields(error: &CursedErrorHandle) -> Vec<(&str, String)> {
+    error.fields().map(|(key, value)| (key, value.into_owned())).collect()
+}
+
+#[test]
+fn new_names_are_refused_or_kept_in_fields() {
+    let kept = CursedError::from_code("FILE_KEPT_0");
+    assert_eq!(kept, CursedError::File(CursedErrorType::Custom("kept 0")));
+    for n in 1..1000 {
+        if CursedError::from_code(&format!("FILE_KEPT_{}", n)) == CursedError::Unknown(None) {
+            assert_eq!(n, 256);
+            break
+        }
+    }
+    assert_eq!(CursedError::from_code("FILE_KEPT_256"), CursedError::Unknown(None));
+    assert_eq!(CursedError::from_code("FILE_KEPT_0"), kept);
+    assert_eq!(CursedError::from_code("FILE_NOT_FOUND"), CursedError::File(CursedErrorType::NotFound));
+
+    assert_eq!("file kept 0".parse::<CursedError>().unwrap(), kept);
+    let refused = "file brand new".parse::<CursedError>().unwrap_err();
+    assert_eq!(refused.get_error(), &CursedError::Data(CursedErrorType::Overflow));
+    assert!("queue refused".parse::<CursedError>().is_err());
+    assert_eq!("queue".parse::<CursedError>().ok(), None);
+
+    let error = CursedErrorHandle::new(
+        CursedError::Custom { category: "brand new", kind: CursedErrorType::Custom("also new") },
+        "lost names".to_string()
+    );
+    let mut bytes = Vec::new();
+    error.encode(&mut bytes);
+    let decoded = CursedErrorHandle::decode(&bytes).unwrap();
+    assert_eq!(decoded.get_error(), &CursedError::Unknown(None));
+    assert_eq!(decoded.get_reason(), "lost names");
+    assert_eq!(fields(&decoded), [
+        ("unknown_code", "9899".to_string()),
+        ("unknown_category", "brand new".to_string()),
+        ("unknown_type", "also new".to_string()),
+    ]);
+
+    let view = CursedErrorRef { op: Some("fresh op"), ..error.as_ref_view() };
+    let owned = CursedErrorHandle::from(view);
+    assert_eq!(owned.op(), None);
+    assert_eq!(fields(&owned), [("op", "fresh op".to_string())]);
+
+    #[cfg(feature = "serde")]
+    {
+        let json = r#"{"category":"file","type":"brand new","reason":"x","op":"fresh op","namespace":"kept 0"}"#;
+        let error: CursedErrorHandle = serde_json::from_str(json).unwrap();
+        assert_eq!(error.get_error(), &CursedError::Unknown(None));
+        assert_eq!(error.op(), None);
+        assert_eq!(error.namespace(), Some("kept 0"));
+        assert_eq!(fields(&error), [("op", "fresh op".to_string()), ("unknown_type", "brand new".to_string())]);
+
+        let json = r#"{"category":"brand new","type":"refused","custom":true,"reason":"x"}"#;
+        let error: CursedErrorHandle = serde_json::from_str(json).unwrap();
+        assert_eq!(error.get_error(), &CursedError::Unknown(None));
+        assert_eq!(fields(&error), [("unknown_category", "brand new".to_string())]);
+
+        assert_eq!(serde_json::from_str::<CursedErrorType>(r#""kept 0""#).unwrap(), CursedErrorType::Custom("kept 0"));
+        assert!(serde_json::from_str::<CursedErrorType>(r#""brand new""#).is_err());
+        assert_eq!(
+            serde_json::from_str::<CursedError>(r#"{"category":"file","type":"brand new"}"#).unwrap(),
+            CursedError::Unknown(None)
+        );
+    }
+}
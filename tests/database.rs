@@ -0,0 +1,93 @@
+//! the errors of a made up driver mapped through the `db_*` constructors, the way a
+//! service maps the errors of sqlx or postgres
+
+use curerr::*;
+
+/// what a driver reports, with postgres sqlstates where the driver would have them
+enum DriverError {
+    PoolTimedOut,
+    Io(std::io::Error),
+    Database { sqlstate: &'static str, constraint: Option<&'static str>, message: &'static str },
+    StatementTimeout { statement: &'static str },
+}
+
+impl From<DriverError> for CursedErrorHandle {
+    fn from(error: DriverError) -> Self {
+        match error {
+            DriverError::PoolTimedOut => CursedErrorHandle::db_unavailable("no connection left in the pool"),
+            DriverError::Io(error) => CursedErrorHandle::db_unavailable(format!("lost the connection: {}", error)),
+            DriverError::StatementTimeout { statement } => CursedErrorHandle::db_timeout(statement),
+            DriverError::Database { sqlstate: "40001" | "40P01", .. } => CursedErrorHandle::db_serialization_failure(),
+            DriverError::Database { sqlstate: "23505", constraint: Some(constraint), .. } => {
+                CursedErrorHandle::db_constraint_violation(constraint)
+            },
+            DriverError::Database { sqlstate: "57014", .. } => CursedErrorHandle::db_timeout("unknown"),
+            DriverError::Database { message, .. } => {
+                CursedErrorHandle::new(CursedError::Database(CursedErrorType::Invalid), message.to_string())
+            },
+        }
+    }
+}
+
+#[test]
+fn driver_errors_map_to_database_kinds() {
+    let cases = [
+        (DriverError::PoolTimedOut, CursedErrorType::Refused, true, 503),
+        (DriverError::Io(std::io::ErrorKind::ConnectionReset.into()), CursedErrorType::Refused, true, 503),
+        (DriverError::StatementTimeout { statement: "load_orders" }, CursedErrorType::Timedout, true, 504),
+        (
+            DriverError::Database { sqlstate: "57014", constraint: None, message: "canceling statement due to statement timeout" },
+            CursedErrorType::Timedout,
+            true,
+            504,
+        ),
+        (
+            DriverError::Database { sqlstate: "40001", constraint: None, message: "could not serialize access" },
+            CursedErrorType::Aborted,
+            true,
+            409,
+        ),
+        (
+            DriverError::Database { sqlstate: "40P01", constraint: None, message: "deadlock detected" },
+            CursedErrorType::Aborted,
+            true,
+            409,
+        ),
+        (
+            DriverError::Database { sqlstate: "23505", constraint: Some("users_email_key"), message: "duplicate key" },
+            CursedErrorType::AlreadyExists,
+            false,
+            409,
+        ),
+        (
+            DriverError::Database { sqlstate: "42601", constraint: None, message: "syntax error at or near \"SELEC\"" },
+            CursedErrorType::Invalid,
+            false,
+            500,
+        ),
+    ];
+
+    for (driver_error, error_type, retryable, http_status) in cases {
+        let error = CursedErrorHandle::from(driver_error);
+
+        assert_eq!(error.get_error(), &CursedError::Database(error_type), "{}", error);
+        assert_eq!(error.get_error().category(), CursedCategory::Database);
+        assert_eq!(error.get_error().is_retryable(), retryable, "{}", error);
+        assert_eq!(error.get_error().http_status(), http_status, "{}", error);
+    }
+}
+
+#[test]
+fn constraint_and_query_are_fields() {
+    let error = CursedErrorHandle::from(DriverError::Database {
+        sqlstate: "23505",
+        constraint: Some("orders_pkey"),
+        message: "duplicate key value violates unique constraint",
+    });
+    assert_eq!(error.fields().collect::<Vec<_>>(), [("constraint", "orders_pkey".into())]);
+    assert_eq!(error.op(), Some("db"));
+
+    let error = CursedErrorHandle::from(DriverError::StatementTimeout { statement: "load_orders" });
+    assert_eq!(error.fields().collect::<Vec<_>>(), [("query", "load_orders".into())]);
+    assert_eq!(error.code(), "DATABASE_TIMED_OUT");
+}
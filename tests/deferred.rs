@@ -0,0 +1,83 @@
+//! errors recorded by `Drop` impls, on a normal scope exit and while unwinding
+
+use curerr::*;
+
+struct Connection {
+    name: &'static str,
+    errors: DeferredErrors,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let reason = match std::thread::panicking() {
+            true => format!("{} closed while unwinding", self.name),
+            false => format!("{} closed", self.name),
+        };
+        self.errors.record(CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Aborted), reason));
+    }
+}
+
+#[test]
+fn records_on_scope_exit() {
+    let errors = DeferredErrors::new();
+    {
+        let _first = Connection { name: "first", errors: errors.clone() };
+        let _second = Connection { name: "second", errors: errors.clone() };
+    }
+
+    let reasons: Vec<_> = errors.drain().iter().map(|error| error.get_reason().to_string()).collect();
+    assert_eq!(reasons, ["second closed", "first closed"]);
+    assert_eq!(errors.lost(), 0);
+}
+
+#[test]
+fn records_while_unwinding() {
+    let errors = DeferredErrors::new();
+
+    let result = std::panic::catch_unwind(|| {
+        let _connection = Connection { name: "db", errors: errors.clone() };
+        panic!("handler failed");
+    });
+
+    assert!(result.is_err());
+    let drained = errors.drain();
+    assert_eq!(drained.len(), 1);
+    assert_eq!(drained.errors()[0].get_reason(), "db closed while unwinding");
+}
+
+fn close(name: &str) -> CursedResult<()> {
+    panic!("cleanup of {} failed", name)
+}
+
+#[test]
+fn panicking_cleanup_is_recorded_while_unwinding() {
+    let errors = DeferredErrors::new();
+
+    let result = std::panic::catch_unwind(|| {
+        let _cleanup = defer_errors!(errors, close("db")?);
+        panic!("handler failed");
+    });
+
+    assert!(result.is_err());
+    let drained = errors.drain();
+    assert_eq!(drained.len(), 1);
+    assert_eq!(drained.errors()[0].get_error(), &CursedError::Call(CursedErrorType::Custom("panicked")));
+    assert_eq!(drained.errors()[0].get_reason(), "cleanup panicked: cleanup of db failed");
+}
+
+#[test]
+fn records_from_other_threads() {
+    let errors = DeferredErrors::new();
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let errors = errors.clone();
+            std::thread::spawn(move || drop(Connection { name: "worker", errors }))
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert_eq!(errors.drain().len(), 4);
+}
@@ -0,0 +1,66 @@
+//! error paths of a client exercised by injected faults
+
+#![cfg(feature = "testing")]
+
+use curerr::faults::{FaultInjector, FiredFault};
+use curerr::*;
+use std::time::Duration;
+
+struct Client {
+    faults: FaultInjector,
+    policy: RetryPolicy,
+}
+
+impl Client {
+    fn new(faults: FaultInjector) -> Self {
+        Self { faults, policy: RetryPolicy::fixed(3, Duration::from_millis(100)).with_sleep(|_| {}) }
+    }
+    fn fetch(&self, key: &str) -> CursedResult<String> {
+        retry(self.policy.clone(), || {
+            self.faults.check("fetch")?;
+            Ok(format!("value of {}", key))
+        })
+    }
+}
+
+#[test]
+fn transient_failure_is_retried() {
+    let client = Client::new(FaultInjector::new().fail_nth("fetch", 1, CursedError::Call(CursedErrorType::Timedout)));
+
+    assert_eq!(client.fetch("user:7").unwrap(), "value of user:7");
+    assert_eq!(client.faults.calls("fetch"), 2);
+    assert_eq!(client.faults.fired(), [FiredFault {
+        op: "fetch".to_string(),
+        call: 1,
+        error: CursedError::Call(CursedErrorType::Timedout),
+    }]);
+}
+
+#[test]
+fn retries_give_up() {
+    let client = Client::new(FaultInjector::new().fail_every("fetch", 1, CursedError::Connection(CursedErrorType::Reset)));
+
+    let error = client.fetch("user:7").unwrap_err();
+
+    assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Reset));
+    assert_eq!(error.get_reason(), "gave up after 3 attempts");
+    client.faults.assert_fired("fetch", &[1, 2, 3]);
+}
+
+#[test]
+fn permanent_failure_is_not_retried() {
+    let client = Client::new(
+        FaultInjector::new()
+            .fail_nth("fetch", 1, CursedError::Other(CursedErrorType::AccessDenied))
+            .fail_nth("fetch", 2, CursedError::Call(CursedErrorType::Timedout))
+    );
+
+    let error = client.fetch("user:7").unwrap_err();
+
+    assert_eq!(error.get_error(), &CursedError::Other(CursedErrorType::AccessDenied));
+    assert_eq!(client.faults.calls("fetch"), 1);
+
+    client.faults.reset();
+    assert_eq!(client.faults.calls("fetch"), 0);
+    assert!(client.faults.fired().is_empty());
+}
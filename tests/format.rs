@@ -0,0 +1,113 @@
+//! golden output of Display and Debug for every kind in each format version, so the
+//! formats downstream log parsers read can't change by accident
+//!
+//! The snapshots are rewritten by running the tests with `CURERR_UPDATE_FORMAT_SNAPSHOTS=1`,
+//! which fails them until they are run again without it.
+
+mod common;
+
+use common::{changed_lines, check_snapshot};
+use curerr::*;
+
+const CATEGORIES: [CursedCategory; 14] = [
+    CursedCategory::Connection,
+    CursedCategory::Address,
+    CursedCategory::Memory,
+    CursedCategory::Buffer,
+    CursedCategory::Envvar,
+    CursedCategory::Other,
+    CursedCategory::Input,
+    CursedCategory::File,
+    CursedCategory::Path,
+    CursedCategory::Data,
+    CursedCategory::Call,
+    CursedCategory::Io,
+    CursedCategory::Security,
+    CursedCategory::Database,
+];
+
+const TYPES: [CursedErrorType; 15] = [
+    CursedErrorType::NotImplemented,
+    CursedErrorType::AlreadyExists,
+    CursedErrorType::AccessDenied,
+    CursedErrorType::NotSupported,
+    CursedErrorType::Interrupted,
+    CursedErrorType::NotEnough,
+    CursedErrorType::Timedout,
+    CursedErrorType::Overflow,
+    CursedErrorType::NotFound,
+    CursedErrorType::Refused,
+    CursedErrorType::Invalid,
+    CursedErrorType::Aborted,
+    CursedErrorType::Reset,
+    CursedErrorType::Parse,
+    CursedErrorType::Custom("rate limited"),
+];
+
+fn kinds() -> Vec<CursedError> {
+    let mut kinds: Vec<_> = CATEGORIES.iter()
+        .chain(&[CursedCategory::Custom("queue")])
+        .flat_map(|category| TYPES.map(|error_type| category.with_type(error_type)))
+        .collect();
+    kinds.extend([CursedError::NoError, CursedError::Unknown(None), CursedError::Unknown(Some(std::io::ErrorKind::FileTooLarge))]);
+    kinds
+}
+
+/// handles of one kind with every part the formats write
+fn shapes(kind: CursedError) -> Vec<(&'static str, CursedErrorHandle)> {
+    let mut contexts = CursedErrorHandle::new(kind, "bad token".to_string());
+    contexts.push_context("while parsing line 3");
+    contexts.push_context("while loading config");
+    let mut empty_with_context = CursedErrorHandle::new(kind, String::new());
+    empty_with_context.push_context("while loading config");
+
+    vec![
+        ("reason", CursedErrorHandle::new(kind, "db is down".to_string())),
+        ("empty", CursedErrorHandle::new(kind, String::new())),
+        ("bare", CursedErrorHandle::from(kind)),
+        ("escapes", CursedErrorHandle::new(kind, "the \"name\" field\nin C:\\config\r\n\tat line 3".to_string())),
+        ("unicode", CursedErrorHandle::new(kind, "größe überschritten, 日本語 🚀".to_string())),
+        ("op", CursedErrorHandle::new(kind, "db is down".to_string()).with_op("connect")),
+        ("namespace", CursedErrorHandle::new(kind, "db is down".to_string()).with_namespace("storage")),
+        ("contexts", contexts.with_op("load")),
+        ("empty with context", empty_with_context),
+    ]
+}
+
+/// every kind with a reason, and every shape of a predefined, an `Other` and a custom kind
+fn render(version: FormatVersion) -> String {
+    let mut out = String::new();
+    let mut write = |name: String, error: CursedErrorHandle| {
+        let error = error.with_format_version(version);
+        out.push_str(&format!("{}\n  display: {}\n  debug: {:?}\n", name, error, error));
+    };
+
+    for kind in kinds() {
+        write(format!("{:?}", kind), CursedErrorHandle::new(kind, "db is down".to_string()));
+    }
+    for kind in [
+        CursedError::Connection(CursedErrorType::Refused),
+        CursedError::Other(CursedErrorType::NotFound),
+        CursedError::Custom { category: "queue", kind: CursedErrorType::Custom("rate limited") },
+    ] {
+        for (shape, error) in shapes(kind) {
+            write(format!("{:?} {}", kind, shape), error);
+        }
+    }
+    write("static".to_string(), CursedErrorHandle::new_static(&const { StaticError::new(CursedError::Call(CursedErrorType::Aborted), "shutting down") }));
+    out
+}
+
+fn check_format(file: &str, output: &str) {
+    check_snapshot(file, "CURERR_UPDATE_FORMAT_SNAPSHOTS", output, |expected| changed_lines(expected, output));
+}
+
+#[test]
+fn v1_matches_snapshot() {
+    check_format("tests/format_v1.txt", &render(FormatVersion::V1));
+}
+
+#[test]
+fn v2_matches_snapshot() {
+    check_format("tests/format_v2.txt", &render(FormatVersion::V2));
+}
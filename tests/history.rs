@@ -0,0 +1,122 @@
+//! `ErrorHistory` filled past its capacity, directly and as an error or panic hook, which
+//! are global, so the tests take turns
+
+mod common;
+
+use common::take_turn;
+use curerr::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn refused(request: usize) -> CursedErrorHandle {
+    CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), format!("request {}", request))
+}
+
+fn reasons(errors: &[CursedErrorHandle]) -> Vec<String> {
+    errors.iter().map(|error| error.get_reason().to_string()).collect()
+}
+
+#[test]
+fn keeps_only_the_newest_in_order() {
+    let _turn = take_turn();
+    let history = ErrorHistory::new(4);
+    assert!(history.recent(4).is_empty());
+
+    for request in 0..10 {
+        history.record(&refused(request));
+    }
+
+    assert_eq!(reasons(&history.recent(10)), ["request 6", "request 7", "request 8", "request 9"]);
+    assert_eq!(reasons(&history.recent(2)), ["request 8", "request 9"]);
+    assert!(history.recent(0).is_empty());
+
+    let mut dump = Vec::new();
+    history.dump_to(&mut dump).unwrap();
+    let dump = String::from_utf8(dump).unwrap();
+    let lines: Vec<_> = dump.lines().collect();
+    assert_eq!(lines.len(), 4);
+    for (line, request) in lines.iter().zip(6..) {
+        let (timestamp, error) = line.split_once(' ').unwrap();
+        assert_eq!(error, format!("CONNECTION_REFUSED: request {}", request));
+        assert_eq!(timestamp.len(), "2026-10-14T09:30:00.125Z".len(), "{}", timestamp);
+        assert!(timestamp.starts_with("20") && timestamp.ends_with('Z'), "{}", timestamp);
+        assert_eq!(&timestamp[10..11], "T");
+    }
+    let mut sorted = lines.clone();
+    sorted.sort();
+    assert_eq!(sorted, lines);
+}
+
+#[test]
+fn entries_are_bounded() {
+    let _turn = take_turn();
+    let history = ErrorHistory::new(2);
+    let mut error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), "x".repeat(4 * 1024 * 1024))
+        .with_op("decode")
+        .with_field("body", "y".repeat(1024 * 1024));
+    error.push_context("while syncing");
+    history.record(&error);
+
+    let entry = &history.recent(1)[0];
+    assert_eq!(entry.get_error(), &CursedError::Data(CursedErrorType::Invalid));
+    assert_eq!(entry.op(), Some("decode"));
+    assert_eq!(entry.location(), error.location());
+    assert!(entry.get_reason().len() <= 256);
+    assert!(entry.get_reason().ends_with(" bytes]"));
+    assert_eq!(entry.fields().count(), 0);
+    assert!(entry.contexts().is_empty());
+
+    assert!(ErrorHistory::new(0).recent(1).is_empty());
+}
+
+#[test]
+fn records_every_error_as_the_hook() {
+    let _turn = take_turn();
+    let history = Arc::new(ErrorHistory::new(3));
+    ErrorHistory::install(history.clone());
+
+    let threads: Vec<_> = (0..4).map(|thread| std::thread::spawn(move || {
+        for request in 0..5 {
+            let _ = refused(thread * 5 + request);
+        }
+    })).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let _ = refused(100);
+    let _ = "x".parse::<u8>().map_err(|error| CursedErrorHandle::new(CursedError::Data(CursedErrorType::Parse), error.to_string()));
+    clear_error_hook();
+    let _ = refused(101);
+
+    let recent = reasons(&history.recent(3));
+    assert!(recent[0].starts_with("request "), "{}", recent[0]);
+    assert_eq!(recent[1..], ["request 100", "invalid digit found in string"]);
+}
+
+#[test]
+fn other_hooks_keep_running() {
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+    static PANICS: AtomicUsize = AtomicUsize::new(0);
+
+    let _turn = take_turn();
+    let history = Arc::new(ErrorHistory::new(2));
+    set_error_hook(|_| { CREATED.fetch_add(1, Ordering::Relaxed); });
+    ErrorHistory::install(history.clone());
+    let stats = Arc::new(ErrorStats::new());
+    ErrorStats::install(stats.clone());
+
+    let _ = refused(1);
+    clear_error_hook();
+    assert_eq!(CREATED.load(Ordering::Relaxed), 1);
+    assert_eq!(reasons(&history.recent(2)), ["request 1"]);
+    assert_eq!(stats.count(CursedCategory::Connection, CursedErrorType::Refused), 1);
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| { PANICS.fetch_add(1, Ordering::Relaxed); }));
+    ErrorHistory::install_panic_hook(history);
+    let panicked = std::thread::spawn(|| panic!("after request 1")).join();
+    std::panic::set_hook(previous);
+
+    assert!(panicked.is_err());
+    assert_eq!(PANICS.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,83 @@
+//! a user error enum converted by hand with `IntoCursed`, and generic code bounding on
+//! the trait for it and the built in conversions alike
+
+use curerr::prelude::*;
+
+#[derive(Debug)]
+enum QueueError {
+    Full { capacity: usize },
+    Closed,
+    Poisoned(&'static str),
+}
+
+impl IntoCursed for QueueError {
+    fn into_cursed(self) -> CursedErrorHandle {
+        match self {
+            QueueError::Full { capacity } => CursedError::Buffer(CursedErrorType::Overflow)
+                .with_reason("queue is full")
+                .with_field("capacity", capacity),
+            QueueError::Closed => CursedError::Call(CursedErrorType::Aborted).into(),
+            QueueError::Poisoned(by) => CursedError::Memory(CursedErrorType::Invalid).with_reason(format!("poisoned by {}", by)),
+        }
+    }
+}
+
+fn push(len: usize, capacity: usize) -> Result<usize, QueueError> {
+    match len < capacity {
+        true => Ok(len + 1),
+        false => Err(QueueError::Full { capacity }),
+    }
+}
+
+/// retries `f` once, for any error type the crate can convert
+fn twice<T, E: IntoCursed>(mut f: impl FnMut() -> Result<T, E>) -> CursedResult<T> {
+    f().or_else(|_| f()).into_cursed_err()
+}
+
+fn fill(capacity: usize) -> CursedResult<usize> {
+    let mut len = 0;
+    for _ in 0..3 {
+        len = twice(|| push(len, capacity))?;
+    }
+    Ok(len)
+}
+
+#[test]
+fn variants_map_to_their_kinds() {
+    let full = QueueError::Full { capacity: 8 }.into_cursed();
+    assert_eq!(full.get_error(), &CursedError::Buffer(CursedErrorType::Overflow));
+    assert_eq!(full.fields().collect::<Vec<_>>(), [("capacity", "8".into())]);
+
+    let closed = QueueError::Closed.into_cursed();
+    assert_eq!(closed.to_string(), "call aborted error");
+
+    let poisoned = QueueError::Poisoned("worker 3").into_cursed();
+    assert_eq!(poisoned.get_reason(), "poisoned by worker 3");
+}
+
+#[test]
+fn results_flow_through_question_marks() {
+    assert_eq!(fill(10).unwrap(), 3);
+
+    let error = fill(2).unwrap_err();
+    assert_eq!(error.get_error(), &CursedError::Buffer(CursedErrorType::Overflow));
+    assert_eq!(error.get_reason(), "queue is full");
+}
+
+#[test]
+fn built_in_conversions_share_the_bound() {
+    #[cfg(feature = "std-conversions")]
+    {
+        let parsed: CursedResult<u8> = twice(|| "300".parse::<u8>());
+        assert_eq!(parsed.unwrap_err().get_error(), &CursedError::Data(CursedErrorType::Overflow));
+    }
+
+    let kind: CursedResult<()> = twice(|| Err(CursedError::Io(CursedErrorType::NotFound)));
+    assert_eq!(kind.unwrap_err().to_string(), "io not found error");
+
+    let handle: CursedResult<()> = twice(|| Err(CursedErrorHandle::new_static(&const { curerr::StaticError::new(CursedError::NoError, "none") })));
+    assert_eq!(handle.unwrap_err().get_reason(), "none");
+
+    let errors: CursedResult<()> = twice(|| Err(MultiError::new()));
+    assert!(errors.is_err());
+}
@@ -0,0 +1,52 @@
+//! `fn main` returning `MainResult`, `MainExit` or calling `run_main`, the test runs
+//! itself as a child process for each of them and checks stderr and the exit code
+
+use curerr::*;
+use std::process::{Command, ExitCode, Termination};
+
+const MODE: &str = "CURERR_MAIN_MODE";
+
+fn run() -> CursedResult<()> {
+    let error = CursedErrorHandle::new(CursedError::Data(CursedErrorType::Parse), "bad port".to_string());
+
+    Err(CursedErrorHandle::new(CursedError::Envvar(CursedErrorType::NotFound), "PORT is not usable".to_string())
+        .with_source(error)
+        .help("export PORT=8080")
+        .with_docs_url("https://example.com/errors/port"))
+}
+
+fn result_main() -> MainResult {
+    run()?;
+    Ok(())
+}
+
+fn run_child(mode: &str) -> (String, Option<i32>) {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .env(MODE, mode)
+        .output()
+        .unwrap();
+
+    (String::from_utf8(output.stderr).unwrap(), output.status.code())
+}
+
+fn main() -> ExitCode {
+    match std::env::var(MODE).as_deref() {
+        // what the standard library does with the result returned from `main`
+        Ok("result") => return result_main().report(),
+        Ok("exit") => return MainExit::from(result_main()).report(),
+        Ok("run_main") => return run_main(run),
+        _ => (),
+    }
+
+    let report = "envvar not found error: \"PORT is not usable\"\n\
+        caused by: data parse error: \"bad port\"\n\
+        help: export PORT=8080\n\
+        docs: https://example.com/errors/port\n";
+
+    assert_eq!(run_child("result"), (format!("Error: {}", report), Some(1)));
+    assert_eq!(run_child("exit"), (report.to_string(), Some(78)));
+    assert_eq!(run_child("run_main"), (report.to_string(), Some(78)));
+
+    println!("main_result: ok");
+    ExitCode::SUCCESS
+}
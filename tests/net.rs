@@ -0,0 +1,41 @@
+//! socket errors of real listeners and connections, reported with the io error once
+
+use curerr::*;
+use std::net::{TcpListener, TcpStream};
+
+/// checks that the report of `error` names the io error once, as the cause
+fn assert_reported_once(error: &CursedErrorHandle, message: &str) {
+    let report = error.report().to_string();
+    assert_eq!(report.matches(message).count(), 1, "{}", report);
+    assert!(report.ends_with(&format!("caused by: {}", message)), "{}", report);
+}
+
+#[test]
+fn binding_twice() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let io = TcpListener::bind(addr).unwrap_err();
+    let message = io.to_string();
+    let error = CursedErrorHandle::bind_err(addr, io);
+
+    assert_eq!(error.get_error(), &CursedError::Address(CursedErrorType::AlreadyExists));
+    assert_eq!(error.get_reason(), format!("failed to bind {}", addr));
+    assert_eq!(error.op(), Some("bind"));
+    assert!(error.os_code().is_some());
+    assert_reported_once(&error, &message);
+}
+
+#[test]
+fn connecting_to_a_closed_port() {
+    // a port that was just free is most likely still closed
+    let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let io = TcpStream::connect(addr).unwrap_err();
+    let message = io.to_string();
+    let error = CursedErrorHandle::connect_err(addr, io);
+
+    assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Refused));
+    assert_eq!(error.get_reason(), format!("failed to connect to {}", addr));
+    assert_eq!(error.addr(), Some(addr));
+    assert!(error.get_error().is_retryable());
+    assert_reported_once(&error, &message);
+}
@@ -0,0 +1,88 @@
+//! an application policy overriding two mappings, checked through the conversions and
+//! handles built on them
+//!
+//! Only `global_policy_reaches_other_threads` installs the global policy, the other
+//! tests run inside `with_policy`, which ignores it.
+
+use curerr::*;
+use std::io::ErrorKind;
+
+const TIMED_OUT: CursedError = CursedError::Call(CursedErrorType::Timedout);
+const DENIED: CursedError = CursedError::Security(CursedErrorType::AccessDenied);
+
+/// permission errors are fatal, timeouts get the status the load balancer retries on
+fn policy() -> ConversionPolicy {
+    ConversionPolicy::new()
+        .map_io_kind(ErrorKind::PermissionDenied, DENIED)
+        .severity(DENIED, CursedSeverity::Critical)
+        .retryable(DENIED, false)
+        .http_status(TIMED_OUT, 598)
+}
+
+fn open_error(kind: ErrorKind) -> CursedErrorHandle {
+    CursedErrorHandle::open_err("/srv/data", std::io::Error::from(kind))
+}
+
+#[test]
+fn overridden_entries_change() {
+    with_policy(policy(), || {
+        let denied = open_error(ErrorKind::PermissionDenied);
+        assert_eq!(denied.get_error(), &DENIED);
+        assert_eq!(denied.get_error().severity(), CursedSeverity::Critical);
+        assert!(!denied.get_error().is_retryable());
+
+        let timed_out = open_error(ErrorKind::TimedOut);
+        assert_eq!(timed_out.get_error(), &TIMED_OUT);
+        assert_eq!(timed_out.get_error().http_status(), 598);
+    });
+}
+
+#[test]
+fn other_entries_keep_the_built_in_mappings() {
+    let builtin = |error: CursedError| (error.http_status(), error.exit_code(), error.severity(), error.is_retryable());
+    let expected = with_policy(ConversionPolicy::new(), || {
+        [TIMED_OUT, DENIED, CursedError::Connection(CursedErrorType::Timedout)].map(builtin)
+    });
+
+    with_policy(policy(), || {
+        assert_eq!(TIMED_OUT.exit_code(), expected[0].1);
+        assert_eq!(TIMED_OUT.severity(), expected[0].2);
+        assert!(TIMED_OUT.is_retryable());
+        assert_eq!(DENIED.http_status(), expected[1].0);
+        assert_eq!(DENIED.exit_code(), expected[1].1);
+        assert_eq!(builtin(CursedError::Connection(CursedErrorType::Timedout)), expected[2]);
+
+        assert_eq!(open_error(ErrorKind::NotFound).get_error(), &CursedError::File(CursedErrorType::NotFound));
+        assert_eq!(CursedError::Custom { category: "queue", kind: CursedErrorType::Timedout }.http_status(), 504);
+    });
+}
+
+#[test]
+fn scoped_policies_nest_and_restore() {
+    let inner = ConversionPolicy::new().http_status(TIMED_OUT, 599);
+
+    with_policy(policy(), || {
+        with_policy(inner, || assert_eq!(TIMED_OUT.http_status(), 599));
+        assert_eq!(TIMED_OUT.http_status(), 598);
+
+        let panicked = std::panic::catch_unwind(|| {
+            with_policy(ConversionPolicy::new(), || panic!("inside the policy"))
+        });
+        assert!(panicked.is_err());
+        assert_eq!(TIMED_OUT.http_status(), 598);
+    });
+}
+
+#[test]
+fn global_policy_reaches_other_threads() {
+    set_policy(policy());
+    let (status, retryable) = std::thread::spawn(|| (TIMED_OUT.http_status(), open_error(ErrorKind::PermissionDenied).get_error().is_retryable()))
+        .join()
+        .unwrap();
+    let scoped = with_policy(ConversionPolicy::new(), || TIMED_OUT.http_status());
+    clear_policy();
+
+    assert_eq!((status, retryable), (598, false));
+    assert_eq!(scoped, 504);
+    assert_eq!(std::thread::spawn(|| TIMED_OUT.http_status()).join().unwrap(), 504);
+}
@@ -0,0 +1,127 @@
+//! every public path of the crate, so moving code between modules can't drop one
+
+use std::io::ErrorKind;
+use std::time::Duration;
+
+type Attempt = fn() -> curerr::CursedResult<()>;
+type BoxedAttempt = fn() -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+type IoAttempt = fn() -> std::io::Result<()>;
+type Sample = fn(&'static str) -> Option<curerr::CursedErrorHandle>;
+type Inspect = fn(curerr::CursedErrorRef<'_>) -> u32;
+
+#[test]
+fn root_paths() {
+    #[allow(unused_imports)]
+    use curerr::{
+        AsCursedError, CircuitBreaker, ConversionPolicy, CursedCategory, CursedError, CursedErrorHandle, CursedErrorRef,
+        CursedErrorType, CursedIterExt, CursedReport, CursedResult, CursedResultBridge, CursedSeverity, DeferGuard,
+        DeferredErrors, ErrorFactory, ErrorHistory, ErrorReporter, ErrorStats, FormatVersion, InternStats, IntoCursed,
+        MainError, MainExit, MainResult, MultiError, MultiReport, NormalizedError, NotFoundError, PeerAddr,
+        PermissionError, ResultExt, RetryPolicy, SamplingReporter, ScopeGuard, Sensitive, StaticError, Taxonomy,
+        TaxonomyDiff, TaxonomyEntry, TimeoutError, capture_backtraces,
+    };
+
+    let _: fn(bool) = curerr::reveal_sensitive;
+    let _: fn(bool) = curerr::keep_original_kind;
+    let _: fn(bool) = curerr::classify_by_text;
+    let _: fn(bool) = curerr::record_trace;
+    let _: fn(bool, fn() -> u16) -> u16 = curerr::with_trace;
+    let _: fn(bool) = curerr::capture_backtraces;
+    let _: fn(ConversionPolicy) = curerr::set_policy;
+    let _: fn() = curerr::clear_policy;
+    let _: fn(ConversionPolicy, fn() -> u16) -> u16 = curerr::with_policy;
+    let _: fn(&'static str) = curerr::set_docs_base_url;
+    let _: fn() = curerr::clear_docs_base_url;
+    let _: fn(fn(&CursedErrorHandle)) = curerr::set_error_hook;
+    let _: fn(fn(&CursedErrorHandle)) = curerr::add_error_hook;
+    let _: fn(&str, u16) -> CursedResult<Vec<std::net::SocketAddr>> = curerr::resolve;
+    let _: fn() = curerr::clear_error_hook;
+    let _: fn(usize) = curerr::set_intern_capacity;
+    let _: fn(Duration) = curerr::set_dedup_window;
+    let _: fn(usize) = curerr::set_max_reason_len;
+    let _: fn(usize, fn() -> u16) -> u16 = curerr::with_max_reason_len;
+    let _: fn(curerr::FormatVersion) = curerr::set_default_format_version;
+    let _: fn(&'static str) -> ScopeGuard = curerr::scope;
+    let _: fn() -> Taxonomy = curerr::taxonomy;
+    let _: fn() -> InternStats = curerr::intern_stats;
+    let _: fn() -> &'static [(ErrorKind, CursedError)] = curerr::error_kind_mappings;
+    let _: fn() -> &'static [(CursedError, ErrorKind)] = curerr::reverse_error_kind_mappings;
+    let _: fn(std::fmt::Error) -> CursedErrorHandle = curerr::wrap_any;
+    let _: fn(CursedError, BoxedAttempt) -> CursedResult<()> = curerr::try_wrap;
+    let _: fn(Attempt) -> CursedResult<()> = curerr::try_wrap_with;
+    let _: fn(&'static str, &'static str, IoAttempt) -> CursedResult<()> = curerr::wrap_io;
+    let _: fn(&'static str, IoAttempt) -> CursedResult<()> = curerr::wrap_io_op;
+    let _: fn(RetryPolicy, Attempt) -> CursedResult<()> = curerr::retry;
+    let _: fn(Duration, Attempt) -> CursedResult<()> = curerr::with_deadline;
+    let _: fn(Attempt) -> std::process::ExitCode = curerr::run_main;
+    let _: fn(CursedErrorHandle) = curerr::set_last_error;
+    let _: fn() -> Option<CursedErrorHandle> = curerr::take_last_error;
+    let _: fn() -> u32 = curerr::last_error_code;
+    let _: fn(Inspect) -> Option<u32> = curerr::with_last_error;
+    let _: extern "C" fn() -> u32 = curerr::curerr_last_error_code;
+    let _: extern "C" fn() = curerr::curerr_clear_last_error;
+    let _: unsafe extern "C" fn(*mut std::ffi::c_char, usize) -> usize = curerr::curerr_last_message;
+
+    #[cfg(feature = "metrics")]
+    let _: fn() = curerr::enable_metrics;
+    #[cfg(feature = "axum")]
+    let _: fn(bool) = curerr::expose_internal_reasons;
+}
+
+#[test]
+fn macros() {
+    let error = curerr::CursedError::Data(curerr::CursedErrorType::Parse);
+
+    assert!(curerr::cursed_match!(error, Data(Parse)));
+
+    let check = || -> curerr::CursedResult<()> {
+        curerr::cursed_ensure_eq!(1, 1, Data(Invalid));
+        curerr::cursed_ensure_ne!(1, 2, Data(Invalid));
+        Ok(())
+    };
+    assert!(check().is_ok());
+
+    mod cache {
+        curerr::cursed_ns!("cache");
+
+        pub fn refused() -> curerr::CursedErrorHandle {
+            cursed(curerr::CursedError::Connection(curerr::CursedErrorType::Refused), "down")
+        }
+    }
+    assert_eq!(cache::refused().namespace(), Some("cache"));
+}
+
+#[test]
+fn compat() {
+    let _: fn() -> &'static [curerr::compat::Classification] = curerr::compat::classifications;
+    let _: fn(&str) -> Option<curerr::CursedError> = curerr::compat::expected;
+    let _: fn(&str, fn() -> curerr::CursedErrorHandle) = curerr::compat::assert_classification;
+    let _: fn(Sample) -> Vec<&'static str> = curerr::compat::assert_all;
+}
+
+#[test]
+fn faults() {
+    let _: fn() -> curerr::faults::FaultInjector = curerr::faults::FaultInjector::new;
+    let _: fn(&curerr::faults::FaultInjector, &str) -> curerr::CursedResult<()> = curerr::faults::FaultInjector::check;
+
+    #[cfg(feature = "testing")]
+    let _: fn(&curerr::faults::FaultInjector) -> Vec<curerr::faults::FiredFault> = curerr::faults::FaultInjector::fired;
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn registry() {
+    let _: &[curerr::registry::ErrorSpec] = curerr::registry::REGISTRY;
+    let _: &[curerr::registry::ErrorSpec] = curerr::registry::CUSTOM_TYPES;
+    let _: fn() -> &'static [curerr::CursedError] = curerr::CursedError::all_combinations;
+}
+
+#[test]
+fn prelude() {
+    #[allow(unused_imports)]
+    use curerr::prelude::{
+        cursed_ensure_eq, cursed_ensure_ne, cursed_match, cursed_ns, wrap_any, CursedCategory, CursedError,
+        CursedErrorHandle, CursedErrorType, CursedIterExt, CursedResult, CursedResultBridge, IntoCursed, MultiError,
+        ResultExt,
+    };
+}
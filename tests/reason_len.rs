@@ -0,0 +1,107 @@
+//! the cap on reasons, set for each test with `with_max_reason_len`
+//!
+//! Only `global_cap_reaches_other_threads` sets the global cap, the other tests run
+//! inside `with_max_reason_len`, which ignores it.
+
+use curerr::*;
+
+fn invalid(reason: String) -> CursedErrorHandle {
+    CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), reason)
+}
+
+#[test]
+fn disabled_by_zero() {
+    let reason = "x".repeat(8 * 1024 * 1024);
+    let error = with_max_reason_len(0, || invalid(reason.clone()));
+
+    assert_eq!(error.get_reason(), reason);
+    assert_eq!(error.fields().count(), 0);
+}
+
+#[test]
+fn global_cap_reaches_other_threads() {
+    set_max_reason_len(16);
+    let error = std::thread::spawn(|| invalid("x".repeat(100))).join().unwrap();
+    let scoped = with_max_reason_len(0, || invalid("x".repeat(100)));
+    set_max_reason_len(0);
+
+    // shorter than the marker
+    assert_eq!(error.get_reason(), "x".repeat(16));
+    assert_eq!(scoped.get_reason().len(), 100);
+}
+
+#[test]
+fn multi_megabyte_reasons_are_cut() {
+    let reason = "response body ".repeat(3 * 1024 * 1024);
+    let error = with_max_reason_len(1024, || invalid(reason.clone()));
+
+    assert_eq!(error.get_reason().len(), 1024);
+    assert!(reason.starts_with(error.get_reason().split('…').next().unwrap()));
+    assert!(error.get_reason().ends_with(&format!(" [truncated {} bytes]", reason.len() - 994)));
+    assert_eq!(error.fields().collect::<Vec<_>>(), [("reason_len", reason.len().to_string().into())]);
+}
+
+#[test]
+fn cuts_never_split_code_points() {
+    let reason = "日本語".repeat(1000);
+
+    for len in 30..40 {
+        let error = with_max_reason_len(len, || invalid(reason.clone()));
+        let (kept, marker) = error.get_reason().split_once('…').unwrap();
+
+        assert!(error.get_reason().len() <= len, "cap {}", len);
+        assert_eq!(kept.len() % 3, 0, "cap {}", len);
+        assert!(reason.starts_with(kept));
+        assert_eq!(marker, format!(" [truncated {} bytes]", reason.len() - kept.len()));
+    }
+
+    // shorter than the marker
+    assert_eq!(with_max_reason_len(7, || invalid(reason.clone())).get_reason(), "日本");
+}
+
+#[test]
+fn every_path_applies_the_cap() {
+    with_max_reason_len(40, every_path_applies_the_cap_of_40);
+}
+
+fn every_path_applies_the_cap_of_40() {
+    let reason = "ä".repeat(100);
+
+    let mut error = invalid("short".to_string());
+    error.set_reason(reason.clone());
+    assert_eq!(error.get_reason(), format!("{}… [truncated 186 bytes]", "ä".repeat(7)));
+    assert_eq!(error.fields().collect::<Vec<_>>(), [("reason_len", "200".into())]);
+    error.set_reason("short again".to_string());
+    assert_eq!(error.fields().count(), 0);
+
+    let interned = CursedErrorHandle::new_interned(CursedError::Data(CursedErrorType::Invalid), &reason);
+    assert_eq!(interned.get_reason(), invalid(reason.clone()).get_reason());
+
+    // encoded without a cap by another process
+    let mut bytes = Vec::new();
+    with_max_reason_len(0, || invalid(reason.clone())).encode(&mut bytes);
+    #[cfg(feature = "serde")]
+    let json = serde_json::to_string(&with_max_reason_len(0, || invalid(reason.clone()))).unwrap();
+
+    let decoded = CursedErrorHandle::decode(&bytes).unwrap();
+    assert_eq!(decoded.get_reason(), interned.get_reason());
+    assert_eq!(decoded.fields().collect::<Vec<_>>(), [("reason_len", "200".into())]);
+
+    #[cfg(feature = "serde")]
+    {
+        let deserialized: CursedErrorHandle = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.get_reason(), interned.get_reason());
+        assert_eq!(deserialized.fields().collect::<Vec<_>>(), [("reason_len", "200".into())]);
+
+        // a cut reason fits the cap when read back
+        let json = serde_json::to_string(&deserialized).unwrap();
+        let again: CursedErrorHandle = serde_json::from_str(&json).unwrap();
+        assert_eq!(again.get_reason(), deserialized.get_reason());
+        assert_eq!(again.fields().collect::<Vec<_>>(), [("reason_len", "200".into())]);
+
+        // and is cut again by a smaller one, with the length it was read with
+        let smaller: CursedErrorHandle = with_max_reason_len(32, || serde_json::from_str(&json).unwrap());
+        assert_eq!(smaller.get_reason(), format!("{}… [truncated 31 bytes]", "ä".repeat(4)));
+        assert_eq!(smaller.fields().collect::<Vec<_>>(), [("reason_len", "39".into())]);
+    }
+}
@@ -0,0 +1,73 @@
+//! every error kind has its codes and mappings decided in the registry
+
+#![cfg(feature = "unstable")]
+
+use curerr::registry::{CUSTOM_TYPES, REGISTRY};
+use curerr::*;
+
+#[test]
+fn every_combination_has_a_row() {
+    let missing: Vec<_> = CursedError::all_combinations()
+        .iter()
+        .filter(|error| !REGISTRY.iter().any(|spec| spec.error == **error))
+        .collect();
+
+    assert!(missing.is_empty(), "no registry rows for {:?}", missing);
+    assert_eq!(REGISTRY.len(), CursedError::all_combinations().len());
+}
+
+#[test]
+fn rows_are_used_by_the_mappings() {
+    for spec in REGISTRY.iter().chain(CUSTOM_TYPES) {
+        let error = spec.error;
+
+        assert_eq!(error.code(), spec.code, "{:?}", error);
+        assert_eq!(error.numeric_code(), spec.numeric_code, "{:?}", error);
+        assert_eq!(error.severity(), spec.severity, "{:?}", error);
+        assert_eq!(error.is_retryable(), spec.retryable, "{:?}", error);
+        assert_eq!(error.http_status(), spec.http_status, "{:?}", error);
+        assert_eq!(error.exit_code(), spec.exit_code, "{:?}", error);
+    }
+}
+
+#[test]
+fn codes_are_unique() {
+    let mut codes: Vec<_> = REGISTRY.iter().chain(CUSTOM_TYPES).map(|spec| spec.code).collect();
+    let mut numeric_codes: Vec<_> = REGISTRY.iter().chain(CUSTOM_TYPES).map(|spec| spec.numeric_code).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    numeric_codes.sort_unstable();
+    numeric_codes.dedup();
+
+    assert_eq!(codes.len(), REGISTRY.len() + CUSTOM_TYPES.len());
+    assert_eq!(numeric_codes.len(), REGISTRY.len() + CUSTOM_TYPES.len());
+}
+
+#[test]
+fn custom_parts_share_rows() {
+    let custom = |kind| CursedError::Custom { category: "queue", kind };
+
+    for kind in [CursedErrorType::Timedout, CursedErrorType::AccessDenied, CursedErrorType::Custom("rate limited")] {
+        let other = CursedError::Other(kind);
+
+        assert_eq!(custom(kind).severity(), other.severity());
+        assert_eq!(custom(kind).is_retryable(), other.is_retryable());
+        assert_eq!(custom(kind).http_status(), other.http_status());
+        assert_eq!(custom(kind).exit_code(), other.exit_code());
+    }
+    assert_eq!(custom(CursedErrorType::Timedout).numeric_code(), 9807);
+    assert_eq!(custom(CursedErrorType::Custom("rate limited")).numeric_code(), 9899);
+
+    let database = CursedError::Custom { category: "database", kind: CursedErrorType::Timedout };
+    let handle = CursedErrorHandle::new(database, "slow query".to_string());
+    assert_eq!(handle.get_error(), &CursedError::Database(CursedErrorType::Timedout));
+    assert_eq!(handle.get_error().numeric_code(), 1407);
+    assert_eq!(CursedCategory::Custom("database").with_type(CursedErrorType::Timedout), *handle.get_error());
+    assert_eq!(database.to_string().parse::<CursedError>().unwrap(), *handle.get_error());
+
+    let error = CursedError::Memory(CursedErrorType::Custom("fragmented"));
+    assert_eq!(error.code(), "MEMORY_FRAGMENTED");
+    assert_eq!(error.numeric_code(), 399);
+    assert_eq!(error.severity(), CursedSeverity::Critical);
+    assert_eq!(error.exit_code(), 71);
+}
@@ -0,0 +1,52 @@
+//! `ErrorReporter` fed more distinct errors than it tracks
+
+use curerr::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// a reason of letters only, so every one has its own fingerprint
+fn reason(mut n: usize) -> String {
+    let mut reason = String::from("table ");
+    loop {
+        reason.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+        if n == 0 {
+            return reason
+        }
+    }
+}
+
+#[test]
+fn tracks_a_bounded_number_of_fingerprints() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let now = Arc::new(Mutex::new(Instant::now()));
+    let reporter = ErrorReporter::new(Duration::from_secs(60), 0)
+        .with_sink({
+            let lines = lines.clone();
+            move |line| lines.lock().unwrap().push(line.to_string())
+        })
+        .with_clock({
+            let now = now.clone();
+            move || *now.lock().unwrap()
+        });
+    // a millisecond apart, so the oldest window is known and none rolls over
+    let error = |n| {
+        *now.lock().unwrap() += Duration::from_millis(1);
+        CursedErrorHandle::new(CursedError::Data(CursedErrorType::Invalid), reason(n))
+    };
+
+    for n in 0..1024 {
+        reporter.report(&error(n));
+    }
+    assert!(lines.lock().unwrap().is_empty());
+
+    reporter.report(&error(1024));
+    reporter.report(&error(1025));
+    assert_eq!(*lines.lock().unwrap(), [
+        "suppressed 1 similar error: data invalid error: \"table a\"",
+        "suppressed 1 similar error: data invalid error: \"table b\"",
+    ]);
+
+    reporter.flush();
+    assert_eq!(lines.lock().unwrap().len(), 2 + 1024);
+}
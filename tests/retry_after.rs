@@ -0,0 +1,66 @@
+//! `Retry-After` values far beyond any real date or delay, read without overflowing and
+//! capped by the retry policy
+
+use curerr::*;
+use std::cell::RefCell;
+use std::time::Duration;
+
+const YEAR: Duration = Duration::from_secs(365 * 86_400);
+
+fn hint(value: &str) -> Option<Duration> {
+    CursedErrorHandle::from_http_status(503, "maintenance".to_string(), Some(value)).retry_after()
+}
+
+#[test]
+fn huge_dates_are_unreadable_or_far_away() {
+    assert_eq!(hint("Fri, 01 Jan 1000000000000 00:00:00 GMT"), None);
+    assert_eq!(hint("Fri, 31 Dec 18446744073709551615 23:59:60 GMT"), None);
+
+    // may still be a time on platforms with 64 bit seconds
+    if let Some(delay) = hint("Fri, 01 Jan 100000000000 00:00:00 GMT") {
+        assert!(delay > YEAR * 1_000_000, "{:?}", delay);
+    }
+}
+
+#[test]
+fn dates_count_leap_days() {
+    // both delays are relative to the time they are read at, microseconds apart
+    let days = |earlier: &str, later: &str| ((hint(later).unwrap() - hint(earlier).unwrap()).as_secs_f64() / 86_400.0).round();
+
+    assert_eq!(days("Tue, 29 Feb 2400 00:00:00 GMT", "Wed, 01 Mar 2400 00:00:00 GMT"), 1.0);
+    assert_eq!(days("Sun, 28 Feb 2100 00:00:00 GMT", "Mon, 01 Mar 2100 00:00:00 GMT"), 1.0);
+    assert_eq!(days("Sat, 01 Jan 2400 00:00:00 GMT", "Mon, 01 Jan 2401 00:00:00 GMT"), 366.0);
+    assert_eq!(days("Fri, 01 Jan 2100 00:00:00 GMT", "Sat, 01 Jan 2101 00:00:00 GMT"), 365.0);
+}
+
+#[test]
+fn dates_past_the_end_of_their_month_are_unreadable() {
+    assert_eq!(hint("Tue, 31 Feb 2026 00:00:00 GMT"), None);
+    assert_eq!(hint("Mon, 29 Feb 2100 00:00:00 GMT"), None);
+    assert_eq!(hint("Thu, 31 Apr 2100 00:00:00 GMT"), None);
+    assert!(hint("Tue, 29 Feb 2400 00:00:00 GMT").is_some());
+    assert!(hint("Fri, 31 Dec 2100 00:00:00 GMT").is_some());
+}
+
+#[test]
+fn huge_second_counts() {
+    assert_eq!(hint("18446744073709551615"), Some(Duration::from_secs(u64::MAX)));
+    assert_eq!(hint("18446744073709551616"), None);
+    assert_eq!(hint("99999999999999999999999999"), None);
+}
+
+#[test]
+fn retry_caps_hints_at_the_maximum_delay() {
+    thread_local! {
+        static SLEPT: RefCell<Vec<Duration>> = const { RefCell::new(Vec::new()) };
+    }
+
+    let policy = RetryPolicy::exponential(3, Duration::from_millis(10), Duration::from_secs(1))
+        .with_sleep(|delay| SLEPT.with(|slept| slept.borrow_mut().push(delay)));
+    let error = retry(policy, || -> CursedResult<()> {
+        Err(CursedErrorHandle::from_http_status(429, "slow down".to_string(), Some("18446744073709551615")))
+    }).unwrap_err();
+
+    assert_eq!(error.get_reason(), "gave up after 3 attempts");
+    assert_eq!(SLEPT.with(|slept| slept.take()), [Duration::from_secs(1); 2]);
+}
@@ -0,0 +1,95 @@
+//! `retry_async` on a paused tokio clock, so the delays are checked exactly without
+//! waiting for them
+
+#![cfg(feature = "tokio")]
+
+use curerr::*;
+use std::cell::Cell;
+use std::time::Duration;
+use tokio::time::Instant;
+
+fn refused() -> CursedErrorHandle {
+    CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "upstream is down".to_string())
+}
+
+#[tokio::test(start_paused = true)]
+async fn succeeds_after_two_failures() {
+    let calls = Cell::new(0);
+    let start = Instant::now();
+    let policy = RetryPolicy::exponential(5, Duration::from_millis(100), Duration::from_secs(1));
+
+    let result = retry_async(policy, || async {
+        calls.set(calls.get() + 1);
+        match calls.get() {
+            ..=2 => Err(refused()),
+            calls => Ok(calls),
+        }
+    }).await;
+
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(start.elapsed(), Duration::from_millis(300));
+}
+
+#[tokio::test(start_paused = true)]
+async fn non_retryable_errors_short_circuit() {
+    let calls = Cell::new(0);
+    let start = Instant::now();
+
+    let error = retry_async(RetryPolicy::fixed(5, Duration::from_secs(1)), || async {
+        calls.set(calls.get() + 1);
+        Err::<(), _>(CursedErrorHandle::new(CursedError::File(CursedErrorType::NotFound), "config.toml".to_string()))
+    }).await.unwrap_err();
+
+    assert_eq!(calls.get(), 1);
+    assert_eq!(error.get_reason(), "config.toml");
+    assert_eq!(start.elapsed(), Duration::ZERO);
+}
+
+#[tokio::test(start_paused = true)]
+async fn honors_retry_hints_and_notes_exhaustion() {
+    let calls = Cell::new(0);
+    let start = Instant::now();
+
+    let error = retry_async(RetryPolicy::fixed(3, Duration::from_millis(100)), || async {
+        calls.set(calls.get() + 1);
+        match calls.get() {
+            1 => Err::<(), _>(CursedErrorHandle::from_http_status(429, "slow down".to_string(), Some("5"))),
+            _ => Err(refused()),
+        }
+    }).await.unwrap_err();
+
+    assert_eq!(start.elapsed(), Duration::from_millis(5_100));
+    assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Refused));
+    assert_eq!(error.get_reason(), "gave up after 3 attempts");
+    assert_eq!(error.find_source::<CursedErrorHandle>().unwrap().get_reason(), "upstream is down");
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancellation_stops_the_backoff() {
+    let calls = Cell::new(0);
+    let start = Instant::now();
+    let shutdown = tokio::time::sleep(Duration::from_secs(90));
+
+    let error = retry_async_until(RetryPolicy::fixed(10, Duration::from_secs(60)), shutdown, || async {
+        calls.set(calls.get() + 1);
+        Err::<(), _>(refused())
+    }).await.unwrap_err();
+
+    assert_eq!(calls.get(), 2);
+    assert_eq!(start.elapsed(), Duration::from_secs(90));
+    assert_eq!(error.get_error(), &CursedError::Call(CursedErrorType::Aborted));
+    assert_eq!(error.get_reason(), "retry cancelled after 2 attempts");
+    assert_eq!(error.find_source::<CursedErrorHandle>().unwrap().get_reason(), "upstream is down");
+}
+
+#[tokio::test(start_paused = true)]
+async fn single_attempts_are_counted_in_the_singular() {
+    let error = retry_async(RetryPolicy::fixed(1, Duration::from_secs(1)), || async { Err::<(), _>(refused()) }).await.unwrap_err();
+    assert_eq!(error.get_reason(), "gave up after 1 attempt");
+
+    let shutdown = tokio::time::sleep(Duration::from_secs(30));
+    let error = retry_async_until(RetryPolicy::fixed(10, Duration::from_secs(60)), shutdown, || async {
+        Err::<(), _>(refused())
+    }).await.unwrap_err();
+    assert_eq!(error.get_reason(), "retry cancelled after 1 attempt");
+}
@@ -0,0 +1,79 @@
+//! round trips of generated errors through every string and binary form
+
+#![cfg(feature = "proptest")]
+
+use curerr::*;
+use proptest::prelude::*;
+
+/// the error after a form that can't hold the converted io kind of unknown errors
+fn without_io_kind(error: CursedError) -> CursedError {
+    match error {
+        CursedError::Unknown(_) => CursedError::Unknown(None),
+        error => error,
+    }
+}
+
+proptest! {
+    #[test]
+    fn display_parses_back(error in any::<CursedError>()) {
+        // the phrase alone can't tell a custom type apart from an unknown error
+        prop_assume!(!matches!(
+            error,
+            CursedError::Other(CursedErrorType::Custom(_)) | CursedError::Custom { kind: CursedErrorType::Custom(_), .. }
+        ));
+
+        prop_assert_eq!(error.to_string().parse::<CursedError>().unwrap(), error);
+    }
+
+    #[test]
+    fn codes_parse_back(error in any::<CursedError>()) {
+        let expected = match error {
+            CursedError::Custom { .. } => CursedError::Unknown(None),
+            error => without_io_kind(error),
+        };
+        prop_assert_eq!(CursedError::from_code(&error.code()), expected);
+
+        let expected = match (error, error.get_type()) {
+            (CursedError::Custom { .. }, _) | (_, Some(CursedErrorType::Custom(_))) => None,
+            (error, _) => Some(without_io_kind(error)),
+        };
+        prop_assert_eq!(CursedError::from_numeric_code(error.numeric_code()), expected);
+    }
+
+    #[test]
+    fn encoding_decodes_back(error in any::<CursedErrorHandle>()) {
+        let mut bytes = Vec::new();
+        error.encode(&mut bytes);
+        let decoded = CursedErrorHandle::decode(&bytes).unwrap();
+
+        prop_assert_eq!(*decoded.get_error(), without_io_kind(*error.get_error()));
+        prop_assert_eq!(decoded.get_reason(), error.get_reason());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_deserializes_back(error in any::<CursedErrorHandle>()) {
+        let json = serde_json::to_string(&error).unwrap();
+        let deserialized: CursedErrorHandle = serde_json::from_str(&json).unwrap();
+
+        prop_assert_eq!(deserialized.get_error(), error.get_error());
+        prop_assert_eq!(deserialized.get_reason(), error.get_reason());
+        prop_assert_eq!(serde_json::from_str::<CursedError>(&serde_json::to_string(error.get_error()).unwrap()).unwrap(), *error.get_error());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn canonical_json_is_sorted_serde(
+        error in any::<CursedErrorHandle>(),
+        fields in prop::collection::btree_map("[\\x00-\\x7f\\PC]{0,8}", "[\\x00-\\x7f\\PC]{0,16}", 0..6),
+    ) {
+        let sorted = fields.iter().fold(error.clone(), |error, (key, value)| error.with_field(key.clone(), value));
+        let reversed = fields.iter().rev().fold(error, |error, (key, value)| error.with_field(key.clone(), value));
+
+        prop_assert_eq!(sorted.canonical_json(), reversed.canonical_json());
+        prop_assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&sorted.canonical_json()).unwrap(),
+            serde_json::to_value(&sorted).unwrap()
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! snapshot of the stable api, so it can't change without updating `tests/stable_api.txt`
+//!
+//! The listing is read from the sources, starting at `src/lib.rs` and following the `mod`
+//! declarations. Items behind the `unstable` feature, either in an `unstable!` block or
+//! with a cfg naming the feature, are left out. After an intended change the snapshot
+//! is rewritten by running the test with `CURERR_UPDATE_STABLE_API=1`, which fails it
+//! until it is run again without it.
+
+mod common;
+
+use common::check_snapshot;
+use std::path::Path;
+
+const SNAPSHOT: &str = "tests/stable_api.txt";
+
+#[test]
+fn stable_api_matches_snapshot() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut listing = Vec::new();
+    list_module(&root.join("src/lib.rs"), "curerr", &mut listing);
+    let listing = listing.join("\n") + "\n";
+
+    check_snapshot(SNAPSHOT, "CURERR_UPDATE_STABLE_API", &listing, |expected| {
+        let removed = expected.lines().filter(|line| !listing.lines().any(|item| item == *line));
+        let added = listing.lines().filter(|line| !expected.lines().any(|item| item == *line));
+        let diff: Vec<_> = removed.map(|line| format!("- {}", line)).chain(added.map(|line| format!("+ {}", line))).collect();
+        match diff.is_empty() {
+            true => "(only the order of the items changed)".to_string(),
+            false => diff.join("\n"),
+        }
+    });
+}
+
+/// a block the listing is in, with the depth of the braces inside it
+struct Scope {
+    kind: ScopeKind,
+    header: String,
+    depth: usize,
+}
+
+#[derive(PartialEq)]
+enum ScopeKind {
+    Module,
+    /// a public enum or trait, whose variants and methods are public without `pub`
+    Public,
+    /// a struct or impl, listed in front of its public members
+    Item,
+    /// a fn or macro body, nothing in it is listed
+    Body,
+}
+
+fn list_module(file: &Path, path: &str, listing: &mut Vec<String>) {
+    let source = strip_comments_and_strings(&std::fs::read_to_string(file).unwrap());
+    let dir = match file.file_name().unwrap().to_str().unwrap() {
+        "lib.rs" | "mod.rs" => file.parent().unwrap().to_path_buf(),
+        name => file.parent().unwrap().join(name.trim_end_matches(".rs")),
+    };
+
+    let mut depth = 0;
+    let mut scopes: Vec<Scope> = Vec::new();
+    let mut attrs: Vec<String> = Vec::new();
+    let mut item = String::new();
+    // depth that ends the unstable item being skipped
+    let mut skip: Option<usize> = None;
+    let mut lines = source.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    while let Some(line) = lines.next() {
+        let start = depth;
+        depth = depth + line.matches('{').count() - line.matches('}').count();
+        while scopes.last().is_some_and(|scope| depth < scope.depth) {
+            scopes.pop();
+        }
+
+        if let Some(end) = skip {
+            if depth <= end && (line.contains('}') || line.contains(';')) {
+                skip = None;
+            }
+            continue
+        }
+        if line.starts_with("#[") {
+            if line.starts_with("#[cfg") && line.contains("\"unstable\"") {
+                skip = Some(start);
+            } else {
+                attrs.push(line.to_string());
+            }
+            continue
+        }
+        if line.starts_with("unstable!") {
+            skip = Some(start);
+            if line.contains('}') {
+                skip = None;
+            }
+            continue
+        }
+        let attrs = std::mem::take(&mut attrs);
+        if scopes.last().is_some_and(|scope| scope.kind == ScopeKind::Body) {
+            continue
+        }
+
+        // a signature or use spanning lines is joined until its body or end
+        item.clear();
+        item.push_str(line);
+        let mut end = depth;
+        while (is_fn(&item) && !item.contains('{') || item.starts_with("pub use ")) && !item.contains(';') {
+            let Some(line) = lines.next() else { break };
+            item.push(' ');
+            item.push_str(line);
+            end = end + line.matches('{').count() - line.matches('}').count();
+        }
+        depth = end;
+        let signature = normalize(&item);
+
+        let public = signature.starts_with("pub ")
+            || scopes.last().is_some_and(|scope| scope.kind == ScopeKind::Public && start == scope.depth);
+        let opens = depth > start;
+
+        if let Some(name) = signature.strip_prefix("pub mod ").or_else(|| signature.strip_prefix("mod ")) {
+            let name = name.trim_end_matches(';');
+            let module = format!("{}::{}", path, name);
+            if public {
+                listing.push(format!("{}: {}", path, with_attrs(&attrs, &format!("pub mod {}", name))));
+            }
+            match opens {
+                true => scopes.push(Scope { kind: ScopeKind::Module, header: module, depth }),
+                false => {
+                    let file = match dir.join(format!("{}.rs", name)) {
+                        file if file.exists() => file,
+                        _ => dir.join(name).join("mod.rs"),
+                    };
+                    list_module(&file, &module, listing);
+                },
+            }
+            continue
+        }
+
+        let module = scopes.iter().rev().find(|scope| scope.kind == ScopeKind::Module).map_or(path, |scope| &scope.header);
+        let parents: Vec<&str> = scopes.iter().filter(|scope| scope.kind != ScopeKind::Module).map(|scope| scope.header.as_str()).collect();
+        let macro_name = signature.strip_prefix("macro_rules! ");
+        let trait_impl = signature.starts_with("impl") && signature.contains(" for ") && parents.is_empty();
+
+        if let Some(name) = macro_name.filter(|_| attrs.iter().any(|attr| attr == "#[macro_export]")) {
+            listing.push(format!("curerr: macro {}!", name));
+        } else if public || trait_impl {
+            let member = with_attrs(&attrs, &signature);
+            listing.push(match parents.is_empty() {
+                true => format!("{}: {}", module, member),
+                false => format!("{}: {} {{ {}{}", module, parents.join(" { "), member, " }".repeat(parents.len())),
+            });
+        }
+
+        if opens {
+            let kind = match () {
+                _ if macro_name.is_some() || is_fn(&signature) => ScopeKind::Body,
+                _ if public && (signature.contains("enum ") || signature.contains("trait ")) => ScopeKind::Public,
+                // the fields of a struct variant
+                _ if public && !signature.starts_with("pub ") => ScopeKind::Public,
+                _ => ScopeKind::Item,
+            };
+            scopes.push(Scope { kind, header: signature, depth });
+        }
+    }
+}
+
+fn is_fn(item: &str) -> bool {
+    let item = ["pub ", "pub(crate) ", "pub(super) "].iter().find_map(|vis| item.strip_prefix(vis)).unwrap_or(item);
+    ["fn ", "const fn ", "unsafe fn ", "async fn ", "extern "].iter().any(|prefix| item.starts_with(prefix))
+}
+
+/// the item without its body or value, with single spaces
+fn normalize(item: &str) -> String {
+    let mut item = item.split_whitespace().collect::<Vec<_>>().join(" ");
+    for (from, to) in [("( ", "("), (" )", ")"), (", )", ")"), ("{ ", "{"), (", }", "}"), ("[ ", "["), (" ]", "]"), (",)", ")")] {
+        item = item.replace(from, to);
+    }
+    if let Some(body) = item.find(" {").or_else(|| item.find('{').filter(|_| !item.starts_with("pub use"))) {
+        item.truncate(body);
+    }
+    if item.starts_with("pub const ") || item.starts_with("pub static ") {
+        if let Some(value) = item.find(" = ") {
+            item.truncate(value);
+        }
+    }
+    item.trim_end_matches([',', ';']).to_string()
+}
+
+fn with_attrs(attrs: &[String], item: &str) -> String {
+    let cfgs: Vec<&str> = attrs.iter().filter(|attr| attr.starts_with("#[cfg(")).map(String::as_str).collect();
+    match cfgs.is_empty() {
+        true => item.to_string(),
+        false => format!("{} {}", cfgs.join(" "), item),
+    }
+}
+
+/// drops comments and the braces and line breaks of string and char literals, so that
+/// only code braces count
+fn strip_comments_and_strings(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1).copied()) {
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            },
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            },
+            ('"', _) => {
+                out.push('"');
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    let len = if chars[i] == '\\' { 2 } else { 1 };
+                    for &c in chars.iter().skip(i).take(len) {
+                        out.push(match c {
+                            '{' => '(',
+                            '}' => ')',
+                            '\n' => ' ',
+                            c => c,
+                        });
+                    }
+                    i += len;
+                }
+                out.push('"');
+                i += 1;
+            },
+            ('\'', Some('\\')) => {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+                out.push_str("' '");
+            },
+            ('\'', Some(_)) if chars.get(i + 2) == Some(&'\'') => {
+                i += 3;
+                out.push_str("' '");
+            },
+            (c, _) => {
+                out.push(c);
+                i += 1;
+            },
+        }
+    }
+    out
+}
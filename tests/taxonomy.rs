@@ -0,0 +1,53 @@
+//! the taxonomy against the committed `tests/taxonomy.json`, so no code or mapping changes
+//! without the fixture changing along with it
+//!
+//! The fixture is rewritten by running the tests with `CURERR_UPDATE_TAXONOMY=1`, which
+//! fails them until they are run again without it.
+
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::{changed_lines, check_snapshot};
+use curerr::*;
+
+#[test]
+fn round_trips_through_serde() {
+    let json = serde_json::to_string(&taxonomy()).unwrap();
+    let read: Taxonomy = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(read, taxonomy());
+}
+
+#[test]
+fn detects_changed_mappings() {
+    let old = taxonomy();
+    let mut new = old.clone();
+    let entry = new.entries.iter_mut().find(|entry| entry.code == "CONNECTION_REFUSED").unwrap();
+    entry.retryable = false;
+    entry.http_status = 503;
+    new.entries.retain(|entry| entry.code != "IO_RESET");
+    new.entries.push(TaxonomyEntry { code: "QUEUE_FULL".to_string(), phrase: "queue full".to_string(), ..old.entries[0].clone() });
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.added.iter().map(|entry| entry.code.as_str()).collect::<Vec<_>>(), ["QUEUE_FULL"]);
+    assert_eq!(diff.removed.iter().map(|entry| entry.code.as_str()).collect::<Vec<_>>(), ["IO_RESET"]);
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].0, *old.get("CONNECTION_REFUSED").unwrap());
+    assert_eq!(
+        diff.to_string(),
+        "+ QUEUE_FULL\n- IO_RESET\n~ CONNECTION_REFUSED: retryable true -> false, http_status 502 -> 503\n"
+    );
+    assert!(new.diff(&new).is_empty());
+}
+
+#[test]
+fn matches_fixture() {
+    let output = serde_json::to_string_pretty(&taxonomy()).unwrap() + "\n";
+    check_snapshot("tests/taxonomy.json", "CURERR_UPDATE_TAXONOMY", &output, |expected| {
+        match serde_json::from_str::<Taxonomy>(expected).map(|committed| committed.diff(&taxonomy())) {
+            Ok(diff) if !diff.is_empty() => diff.to_string(),
+            _ => changed_lines(expected, &output),
+        }
+    });
+}
@@ -0,0 +1,85 @@
+//! the locations of traces, recorded for each test with `with_trace`, which ignores
+//! `record_trace`
+
+use curerr::*;
+use std::panic::Location;
+
+fn lines(error: &CursedErrorHandle) -> Vec<u32> {
+    error.trace().map(|location| location.line()).collect()
+}
+
+#[track_caller]
+fn here() -> u32 {
+    Location::caller().line()
+}
+
+fn refused() -> CursedResult<()> {
+    Err(CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "upstream is down".to_string()))
+}
+
+fn dial() -> CursedResult<()> {
+    refused().op("dial")
+}
+
+fn fetch() -> CursedResult<()> {
+    dial().context("while fetching the profile")
+}
+
+#[test]
+fn locations_accumulate_in_propagation_order() {
+    with_trace(true, || {
+        let line = here();
+        let mut error = fetch().unwrap_err();
+        error.push_context("while rendering");
+
+        assert_eq!(lines(&error), [line - 10, line - 6, line + 2]);
+        assert!(error.trace().all(|location| location.file() == file!()));
+        assert!(error.report().to_string().ends_with(&format!(
+            "\n  at {0}:{1} -> {0}:{2} -> {0}:{3}",
+            file!(),
+            line - 10,
+            line - 6,
+            line + 2
+        )));
+    });
+}
+
+#[test]
+fn keeps_the_first_eight() {
+    with_trace(true, || {
+        let mut error = refused().unwrap_err();
+        let first = here() + 2;
+        for depth in 0..20 {
+            error = error.with_op("retry");
+            error.push_context(format!("attempt {}", depth));
+        }
+
+        assert_eq!(lines(&error), [first, first + 1].repeat(4));
+        assert_eq!(error.contexts().len(), 20);
+    });
+}
+
+#[test]
+fn foreign_errors_start_the_trace_where_they_are_wrapped() {
+    with_trace(true, || {
+        let result: Result<(), std::fmt::Error> = Err(std::fmt::Error);
+        let line = here();
+        let error = result.context("while formatting").unwrap_err();
+
+        assert_eq!(lines(&error), [line + 1]);
+        assert_eq!(error.location().map(Location::line), Some(line + 1));
+
+        let error = CursedErrorHandle::new_static(&const { StaticError::new(CursedError::Call(CursedErrorType::Aborted), "shutting down") }).with_op("stop");
+        assert_eq!(lines(&error), [here() - 1]);
+    });
+}
+
+#[test]
+fn nothing_is_recorded_by_default() {
+    let mut error = fetch().unwrap_err();
+    error.push_context("while rendering");
+
+    assert_eq!(error.trace().len(), 0);
+    assert!(!error.report().to_string().contains("\n  at "));
+    assert_eq!(with_trace(true, || with_trace(false, fetch)).unwrap_err().trace().len(), 0);
+}
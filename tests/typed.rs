@@ -0,0 +1,76 @@
+//! the typed errors of a cache client whose calls can only time out or miss, validated
+//! from handles and widened back into them
+
+use curerr::*;
+
+fn get(key: &str) -> Result<&'static str, NotFoundError> {
+    match key {
+        "motd" => Ok("hello"),
+        key => Err(NotFoundError::new(CursedCategory::Custom("cache"), format!("no entry {}", key)).with_field("key", key)),
+    }
+}
+
+fn connect(attempts: u32) -> Result<(), TimeoutError> {
+    Err(TimeoutError::new(CursedCategory::Connection, "cache didn't answer")
+        .with_field("attempts", attempts)
+        .with_op("connect"))
+}
+
+fn greeting() -> CursedResult<String> {
+    connect(1).or_else(|_| connect(2))?;
+    Ok(get("motd")?.to_string())
+}
+
+#[test]
+fn try_from_rejects_other_types() {
+    let refused = CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "cache is down".to_string())
+        .with_field("host", "cache-1");
+    let error = TimeoutError::try_from(refused).unwrap_err();
+    assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Refused));
+    assert_eq!(error.get_reason(), "cache is down");
+    assert_eq!(error.fields().collect::<Vec<_>>(), [("host", "cache-1".into())]);
+
+    let denied = CursedErrorHandle::new(CursedError::File(CursedErrorType::AccessDenied), "/var/cache".to_string());
+    let denied = NotFoundError::try_from(denied).unwrap_err();
+    assert!(PermissionError::try_from(denied).is_ok());
+
+    let timed_out = CursedErrorHandle::timed_out_after("cache get", std::time::Duration::from_secs(2), None);
+    assert_eq!(TimeoutError::try_from(timed_out).unwrap().timeout_limit(), Some(std::time::Duration::from_secs(2)));
+}
+
+#[test]
+fn constructors_only_make_their_type() {
+    for category in [CursedCategory::Io, CursedCategory::Custom("cache"), CursedCategory::NoError, CursedCategory::Unknown] {
+        assert_eq!(TimeoutError::new(category, "").get_error().get_type(), Some(CursedErrorType::Timedout));
+        assert_eq!(NotFoundError::new(category, "").get_error().get_type(), Some(CursedErrorType::NotFound));
+        assert_eq!(PermissionError::new(category, "").get_error().get_type(), Some(CursedErrorType::AccessDenied));
+    }
+    assert_eq!(TimeoutError::new(CursedCategory::Unknown, "").get_error(), &CursedError::Other(CursedErrorType::Timedout));
+}
+
+#[test]
+fn widening_keeps_reason_and_fields() {
+    let error = greeting().unwrap_err();
+    assert_eq!(error.get_error(), &CursedError::Connection(CursedErrorType::Timedout));
+    assert_eq!(error.get_reason(), "cache didn't answer");
+    assert_eq!(error.op(), Some("connect"));
+    assert_eq!(error.fields().collect::<Vec<_>>(), [("attempts", "2".into())]);
+
+    let missing = get("banner").unwrap_err();
+    let display = missing.to_string();
+    let handle: CursedErrorHandle = missing.into();
+    assert_eq!(handle.to_string(), display);
+    assert_eq!(handle.get_reason(), "no entry banner");
+    assert_eq!(handle.fields().collect::<Vec<_>>(), [("key", "banner".into())]);
+}
+
+#[test]
+fn result_extensions_keep_the_handle() {
+    let error = get("banner").context("while rendering the header").unwrap_err();
+    assert_eq!(error.get_error(), &CursedError::Custom { category: "cache", kind: CursedErrorType::NotFound });
+    assert_eq!(error.contexts(), ["while rendering the header"]);
+    assert!(std::error::Error::source(&error).is_none());
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(connect(1).unwrap_err());
+    assert_eq!(CursedError::classify(&*boxed), CursedError::Connection(CursedErrorType::Timedout));
+}
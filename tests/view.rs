@@ -0,0 +1,162 @@
+//! borrowed views of errors, checked against their owners and for allocations
+
+use curerr::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// counts the allocations of each thread, so tests running in parallel don't mix
+struct CountingAllocator;
+
+std::thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, u64) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+fn errors() -> Vec<CursedErrorHandle> {
+    vec![
+        {
+            let mut error = CursedErrorHandle::new(CursedError::Connection(CursedErrorType::Refused), "db is down".to_string())
+                .with_op("connect")
+                .with_namespace("storage");
+            error.push_context("while starting");
+            error
+        },
+        CursedErrorHandle::open_err("config.toml", std::io::Error::from_raw_os_error(2)),
+        CursedErrorHandle::new_static(&const { StaticError::new(CursedError::Memory(CursedErrorType::NotEnough), "heap") }),
+        CursedErrorHandle::new(CursedError::Custom { category: "billing", kind: CursedErrorType::Custom("expired") }, "card".to_string()),
+        CursedErrorHandle::new(CursedError::Unknown(Some(std::io::ErrorKind::FileTooLarge)), "too big".to_string()),
+        CursedErrorHandle::new(CursedError::NoError, String::new()),
+    ]
+}
+
+#[test]
+fn views_match_their_owners() {
+    for error in errors() {
+        let view = error.as_ref_view();
+
+        assert_eq!(view.category, error.get_error().category());
+        assert_eq!(view.error_type, error.get_error().get_type());
+        assert_eq!(view.reason, error.get_reason());
+        assert_eq!(view.io_kind, match error.get_error() {
+            CursedError::Unknown(kind) => *kind,
+            _ => None,
+        });
+        assert_eq!(view.kind(), *error.get_error());
+        assert_eq!(view.code(), error.code());
+        assert_eq!(view.severity, error.get_error().severity());
+        assert_eq!(view.os_code, error.os_code());
+        assert_eq!(view.op, error.op());
+        assert_eq!(view.namespace, error.namespace());
+        assert_eq!(view.contexts, error.contexts());
+        assert_eq!(view.to_string(), error.to_string());
+        assert_eq!(view, error);
+        assert_eq!(error, view);
+
+        let owned = CursedErrorHandle::from(view);
+        assert_eq!(owned, view);
+        assert_eq!(owned.get_error(), error.get_error());
+    }
+}
+
+#[test]
+fn views_dont_allocate() {
+    let errors = errors();
+    for error in &errors {
+        let (view, allocations) = count_allocations(|| error.as_ref_view());
+        assert_eq!(allocations, 0, "view of {} allocated", error);
+        let (equal, allocations) = count_allocations(|| view == *error);
+        assert!(equal);
+        assert_eq!(allocations, 0, "comparing the view of {} allocated", error);
+    }
+}
+
+#[test]
+fn reporters_take_views() {
+    let reported = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let reporter = SamplingReporter::new({
+        let reported = reported.clone();
+        move |error| reported.lock().unwrap().push((error.kind(), error.reason.to_string()))
+    }).with_rate(CursedSeverity::Warning, 0.0);
+
+    let forced = ErrorFactory::new().tag(SamplingReporter::FORCE_TAG).err_kind(CursedError::Call(CursedErrorType::Timedout), "forced");
+    assert_eq!(forced.as_ref_view().tags, Some("force"));
+    for error in errors().iter().chain([&forced]) {
+        reporter.report(error);
+    }
+    reporter.report(CursedErrorRef { reason: "not a handle", ..forced.as_ref_view() });
+
+    let reported = reported.lock().unwrap();
+    assert_eq!(reported.iter().map(|(_, reason)| reason.as_str()).collect::<Vec<_>>(), [
+        "No such file or directory (os error 2) (path config.toml)",
+        "heap",
+        "card",
+        "too big",
+        "",
+        "forced",
+        "not a handle",
+    ]);
+    assert_eq!(reported[3].0, CursedError::Unknown(Some(std::io::ErrorKind::FileTooLarge)));
+
+    let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let reporter = ErrorReporter::new(std::time::Duration::from_secs(60), 1).with_sink({
+        let lines = lines.clone();
+        move |line| lines.lock().unwrap().push(line.to_string())
+    });
+    let first = errors().remove(0);
+    reporter.report(&first);
+    reporter.report(&first.clone().with_field("attempt", 2));
+    reporter.flush();
+    assert_eq!(*lines.lock().unwrap(), [
+        "connect: connection refused error: \"while starting: db is down\"",
+        "suppressed 1 similar error: connect: connection refused error: \"while starting: db is down\"",
+    ]);
+}
+
+#[test]
+fn stats_count_views() {
+    let stats = ErrorStats::new();
+    let error = CursedErrorHandle::new(CursedError::Call(CursedErrorType::Timedout), "slow".to_string());
+    stats.record(&error);
+
+    let ((), allocations) = count_allocations(|| stats.record(&error));
+    assert_eq!(allocations, 0);
+
+    stats.record(CursedErrorRef {
+        category: CursedCategory::Call,
+        error_type: Some(CursedErrorType::Timedout),
+        io_kind: None,
+        reason: "slow as well",
+        severity: CursedSeverity::Warning,
+        os_code: None,
+        op: None,
+        namespace: None,
+        contexts: &[],
+        tags: None,
+    });
+    assert_eq!(stats.count(CursedCategory::Call, CursedErrorType::Timedout), 3);
+
+    stats.record(&CursedErrorHandle::new(CursedError::Unknown(Some(std::io::ErrorKind::FileTooLarge)), "big".to_string()));
+    stats.record(&CursedErrorHandle::new(CursedError::Unknown(None), "odd".to_string()));
+    assert_eq!(stats.top_n(3)[1..], [
+        (CursedError::Unknown(None), 1),
+        (CursedError::Unknown(Some(std::io::ErrorKind::FileTooLarge)), 1),
+    ]);
+}
@@ -0,0 +1,20 @@
+//! errors of `wrap_io` and `wrap_io_op` located at the caller, not inside curerr
+
+use curerr::*;
+
+fn missing() -> std::io::Result<String> {
+    std::fs::read_to_string("/definitely/not/here.conf")
+}
+
+#[test]
+fn located_at_the_caller() {
+    let line = line!() + 1;
+    let error = wrap_io("read", "/definitely/not/here.conf", missing).unwrap_err();
+    let location = error.location().unwrap();
+    assert_eq!((location.file(), location.line()), (file!(), line));
+
+    let line = line!() + 1;
+    let error = wrap_io_op("read", missing).unwrap_err();
+    let location = error.location().unwrap();
+    assert_eq!((location.file(), location.line()), (file!(), line));
+}